@@ -0,0 +1,32 @@
+#![feature(test)]
+#![cfg(feature = "bytes")]
+
+extern crate test;
+use bytes::Bytes;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use test::Bencher;
+
+const LEN: usize = 1 << 20;
+
+#[bench]
+fn bytes_to_python(b: &mut Bencher) {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let bytes = Bytes::from(vec![0u8; LEN]);
+    b.iter(|| {
+        let obj = bytes.to_object(py);
+        test::black_box(obj);
+    });
+}
+
+#[bench]
+fn bytes_from_python(b: &mut Bencher) {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let obj = PyBytes::new(py, &vec![0u8; LEN]);
+    b.iter(|| {
+        let bytes: Bytes = obj.extract().unwrap();
+        test::black_box(bytes);
+    });
+}
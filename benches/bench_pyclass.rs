@@ -1,7 +1,12 @@
 #![feature(test)]
 
 extern crate test;
-use pyo3::{class::PyObjectProtocol, prelude::*, type_object::LazyStaticType};
+use pyo3::{
+    class::{basic::CompareOp, PyObjectProtocol},
+    prelude::*,
+    type_object::LazyStaticType,
+};
+use std::hash::{Hash, Hasher};
 use test::Bencher;
 
 /// This is a feature-rich class instance used to benchmark various parts of the pyclass lifecycle.
@@ -44,3 +49,72 @@ fn first_time_init(b: &mut Bencher) {
         ty.get_or_init::<MyClass>(py);
     });
 }
+
+/// A value type whose Rust `Hash` is comparatively expensive.
+struct LongKey(Vec<u64>);
+
+impl Hash for LongKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+#[pyclass(hash)]
+#[derive(Hash)]
+struct UncachedHash {
+    key: LongKey,
+}
+
+#[pyclass(hash, cache_hash)]
+#[derive(Hash)]
+struct CachedHash {
+    key: LongKey,
+}
+
+#[bench]
+fn repeated_hash_uncached(b: &mut Bencher) {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let obj = PyCell::new(
+        py,
+        UncachedHash {
+            key: LongKey((0..1000).collect()),
+        },
+    )
+    .unwrap();
+    b.iter(|| obj.hash().unwrap());
+}
+
+#[bench]
+fn repeated_hash_cached(b: &mut Bencher) {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let obj = PyCell::new(
+        py,
+        CachedHash {
+            key: LongKey((0..1000).collect()),
+        },
+    )
+    .unwrap();
+    b.iter(|| obj.hash().unwrap());
+}
+
+#[pyclass(eq)]
+#[derive(PartialEq)]
+struct EqElements {
+    elements: Vec<i32>,
+}
+
+#[bench]
+fn eq_with_itself(b: &mut Bencher) {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let obj = PyCell::new(
+        py,
+        EqElements {
+            elements: (0..1000).collect(),
+        },
+    )
+    .unwrap();
+    b.iter(|| obj.rich_compare(obj, CompareOp::Eq).unwrap());
+}
@@ -863,12 +863,18 @@ fn emit_cargo_configuration(interpreter_config: &InterpreterConfig) -> Result<()
         println!("cargo:rustc-cfg=py_sys_config=\"{}\"", flag)
     }
 
-    // Enable use of const generics on Rust 1.51 and greater
+    let rustc_minor = rustc_minor_version().unwrap_or(0);
 
-    if rustc_minor_version().unwrap_or(0) >= 51 {
+    // Enable use of const generics on Rust 1.51 and greater
+    if rustc_minor >= 51 {
         println!("cargo:rustc-cfg=min_const_generics");
     }
 
+    // Enable conversions for `std::num::Saturating` on Rust 1.74 and greater
+    if rustc_minor >= 74 {
+        println!("cargo:rustc-cfg=num_saturating");
+    }
+
     Ok(())
 }
 
@@ -13,6 +13,7 @@ pub mod kw {
     syn::custom_keyword!(item);
     syn::custom_keyword!(pass_module);
     syn::custom_keyword!(name);
+    syn::custom_keyword!(overridable);
     syn::custom_keyword!(signature);
     syn::custom_keyword!(transparent);
 }
@@ -18,6 +18,13 @@ pub struct ConstSpec {
 }
 
 impl ConstSpec {
+    pub fn python_name(&self) -> syn::Ident {
+        match &self.attributes.name {
+            Some(name) => name.0.clone(),
+            None => self.rust_ident.unraw(),
+        }
+    }
+
     /// Null-terminated Python name
     pub fn python_name_with_deprecation(&self) -> TokenStream {
         if let Some(name) = &self.attributes.name {
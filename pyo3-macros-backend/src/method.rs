@@ -88,26 +88,61 @@ pub enum FnType {
     ClassAttribute,
 }
 
+impl FnType {
+    pub fn is_getter(&self) -> bool {
+        if let FnType::Getter(_) = self {
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_classmethod(&self) -> bool {
+        if let FnType::FnClass = self {
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_setter(&self) -> bool {
+        if let FnType::Setter(_) = self {
+            true
+        } else {
+            false
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum SelfType {
-    Receiver { mutable: bool },
+    Receiver {
+        mutable: bool,
+        span: proc_macro2::Span,
+    },
     TryFromPyCell(proc_macro2::Span),
 }
 
 impl SelfType {
     pub fn receiver(&self, cls: &syn::Type) -> TokenStream {
         match self {
-            SelfType::Receiver { mutable: false } => {
+            SelfType::Receiver { mutable: false, .. } => {
                 quote! {
                     let _cell = _py.from_borrowed_ptr::<pyo3::PyCell<#cls>>(_slf);
                     let _ref = _cell.try_borrow()?;
                     let _slf = &_ref;
                 }
             }
-            SelfType::Receiver { mutable: true } => {
+            SelfType::Receiver {
+                mutable: true,
+                span,
+            } => {
+                // Spanned so that a `&mut self` receiver on a `#[pyclass(frozen)]` is reported at
+                // the receiver.
+                let borrow = quote_spanned!(*span => _cell.try_borrow_mut()?);
                 quote! {
                     let _cell = _py.from_borrowed_ptr::<pyo3::PyCell<#cls>>(_slf);
-                    let mut _ref = _cell.try_borrow_mut()?;
+                    let mut _ref = #borrow;
                     let _slf = &mut _ref;
                 }
             }
@@ -135,6 +170,11 @@ pub struct FnSpec<'a> {
     pub output: syn::Type,
     pub doc: syn::LitStr,
     pub name_is_deprecated: bool,
+    /// Whether the Python name was given with `name`, `#[getter(...)]` or `#[setter(...)]`, so
+    /// that `#[pyclass(rename_all = "...")]` leaves it alone.
+    pub name_is_explicit: bool,
+    /// Whether `doc` starts with a text signature, which repeats the Python name.
+    pub has_text_signature: bool,
 }
 
 pub fn get_return_info(output: &syn::ReturnType) -> syn::Type {
@@ -148,6 +188,7 @@ pub fn parse_method_receiver(arg: &syn::FnArg) -> syn::Result<SelfType> {
     match arg {
         syn::FnArg::Receiver(recv) => Ok(SelfType::Receiver {
             mutable: recv.mutability.is_some(),
+            span: recv.span(),
         }),
         syn::FnArg::Typed(syn::PatType { ty, .. }) => {
             if let syn::Type::ImplTrait(_) = &**ty {
@@ -170,6 +211,7 @@ impl<'a> FnSpec<'a> {
             args: fn_attrs,
             mut python_name,
         } = parse_method_attributes(meth_attrs, options.name.map(|name| name.0))?;
+        let name_is_explicit = python_name.is_some();
 
         match fn_type_attr {
             Some(MethodTypeAttribute::New) => {
@@ -194,7 +236,8 @@ impl<'a> FnSpec<'a> {
         let python_name = python_name.as_ref().unwrap_or(name).unraw();
 
         let text_signature = Self::parse_text_signature(meth_attrs, &fn_type, &python_name)?;
-        let doc = utils::get_doc(&meth_attrs, text_signature, true)?;
+        let has_text_signature = text_signature.is_some();
+        let doc = utils::get_doc(&meth_attrs, text_signature.clone(), true)?;
 
         let arguments = if skip_first_arg {
             sig.inputs
@@ -209,7 +252,7 @@ impl<'a> FnSpec<'a> {
                 .collect::<syn::Result<_>>()?
         };
 
-        Ok(FnSpec {
+        let spec = FnSpec {
             tp: fn_type,
             name,
             python_name,
@@ -218,7 +261,69 @@ impl<'a> FnSpec<'a> {
             output: ty,
             doc,
             name_is_deprecated: options.name_is_deprecated,
-        })
+            name_is_explicit,
+            has_text_signature,
+        };
+        if let Some(text_signature) = &text_signature {
+            spec.check_text_signature(text_signature)?;
+        }
+        Ok(spec)
+    }
+
+    /// Checks that a `#[text_signature]` declares the same keyword-only parameters, `*args` and
+    /// `**kwargs` as the function accepts, so that it can't drift from the actual signature.
+    ///
+    /// Positional parameters are not compared, as the signature may name them differently or
+    /// include `$self`, and `/` is allowed although arguments can always be passed by keyword.
+    pub fn check_text_signature(&self, text_signature: &syn::LitStr) -> syn::Result<()> {
+        let declared = utils::TextSignatureParams::parse(text_signature)?;
+        let span = text_signature.span();
+        let args = self.args.iter().filter(|arg| !arg.py);
+
+        let mut keyword_only: Vec<String> = args
+            .clone()
+            .filter(|arg| self.is_kw_only(arg.name))
+            .map(|arg| arg.name.unraw().to_string())
+            .collect();
+        let mut declared_keyword_only = declared.keyword_only;
+        keyword_only.sort();
+        declared_keyword_only.sort();
+        let list = |names: &[String]| {
+            if names.is_empty() {
+                "none".to_string()
+            } else {
+                let names: Vec<_> = names.iter().map(|name| format!("`{}`", name)).collect();
+                names.join(", ")
+            }
+        };
+        ensure_spanned!(
+            declared_keyword_only == keyword_only,
+            span => format!(
+                "text_signature declares the keyword-only parameters {}, but the function has {}",
+                list(&declared_keyword_only),
+                list(&keyword_only)
+            )
+        );
+
+        let has_varargs = args.clone().any(|arg| self.is_args(arg.name));
+        ensure_spanned!(
+            declared.has_varargs == has_varargs,
+            span => if has_varargs {
+                "the function accepts `*args`, but text_signature doesn't declare them"
+            } else {
+                "text_signature declares `*args`, but the function doesn't accept them"
+            }
+        );
+        let has_kwargs = args.clone().any(|arg| self.is_kwargs(arg.name));
+        ensure_spanned!(
+            declared.has_kwargs == has_kwargs,
+            span => if has_kwargs {
+                "the function accepts `**kwargs`, but text_signature doesn't declare them"
+            } else {
+                "text_signature declares `**kwargs`, but the function doesn't accept them"
+            }
+        );
+        Ok(())
     }
 
     pub fn python_name_with_deprecation(&self) -> TokenStream {
@@ -228,6 +333,53 @@ impl<'a> FnSpec<'a> {
         quote!({#deprecation #name})
     }
 
+    /// The Python name of a method of `cls`, which follows the
+    /// `#[pyclass(rename_all = "...")]` of the class unless the name was given explicitly.
+    pub fn python_name_for_class(&self, cls: &syn::Type, null_terminated: bool) -> TokenStream {
+        let python_name = &self.python_name;
+        match (self.name_is_explicit, null_terminated) {
+            (true, true) => self.python_name_with_deprecation(),
+            (true, false) => quote!(stringify!(#python_name)),
+            (false, _) => {
+                let name = python_name.to_string();
+                let terminator = if null_terminated { "\0" } else { "" };
+                Self::select_renamed(cls, |rule| {
+                    let renamed = rule.map_or_else(|| name.clone(), |rule| rule.apply(&name));
+                    renamed + terminator
+                })
+            }
+        }
+    }
+
+    /// The doc string of a method of `cls`, whose text signature, if any, uses the name from
+    /// [`FnSpec::python_name_for_class`].
+    pub fn doc_for_class(&self, cls: &syn::Type) -> TokenStream {
+        let doc = &self.doc;
+        if self.name_is_explicit || !self.has_text_signature {
+            return quote!(#doc);
+        }
+        let name = self.python_name.to_string();
+        let doc = doc.value();
+        let rest = &doc[name.len()..];
+        Self::select_renamed(cls, |rule| match rule {
+            Some(rule) => format!("{}{}", rule.apply(&name), rest),
+            None => doc.clone(),
+        })
+    }
+
+    /// Generates the string for every `rename_all` case conversion and selects the one of `cls`.
+    fn select_renamed(
+        cls: &syn::Type,
+        string: impl Fn(Option<utils::RenameRule>) -> String,
+    ) -> TokenStream {
+        let strings = std::iter::once(None)
+            .chain(utils::RenameRule::ALL.iter().copied().map(Some))
+            .map(string);
+        quote! {
+            [#(#strings),*][<#cls as pyo3::class::impl_::PyClassImpl>::RENAME_ALL as usize]
+        }
+    }
+
     fn parse_text_signature(
         meth_attrs: &mut Vec<syn::Attribute>,
         fn_type: &FnType,
@@ -332,6 +484,42 @@ impl<'a> FnSpec<'a> {
         Ok((fn_type, skip_first_arg))
     }
 
+    /// A text signature built from the arguments, e.g. `(a, b=0, *args, c=None, **kwargs)`.
+    ///
+    /// Defaults which are Rust literals are written as the equal Python literal, and any other
+    /// default as `...`, so that `inspect.signature` can still parse the signature.
+    pub fn generated_text_signature(&self) -> String {
+        let mut params = Vec::new();
+        let mut keyword_only = false;
+        for arg in &self.args {
+            if arg.py {
+                continue;
+            }
+            let name = arg.name.unraw().to_string();
+            if self.is_args(arg.name) {
+                keyword_only = true;
+                params.push(format!("*{}", name));
+            } else if self.is_kwargs(arg.name) {
+                params.push(format!("**{}", name));
+            } else {
+                if self.is_kw_only(arg.name) && !keyword_only {
+                    keyword_only = true;
+                    params.push("*".to_string());
+                }
+                let default = self.default_value(arg.name).map(syn::parse2::<syn::Expr>);
+                match default {
+                    Some(Ok(default)) => {
+                        params.push(format!("{}={}", name, python_default(&default)))
+                    }
+                    Some(Err(_)) => params.push(format!("{}=...", name)),
+                    None if arg.optional.is_some() => params.push(format!("{}=None", name)),
+                    None => params.push(name),
+                }
+            }
+        }
+        format!("({})", params.join(", "))
+    }
+
     pub fn is_args(&self, name: &syn::Ident) -> bool {
         for s in self.attrs.iter() {
             if let Argument::VarArgs(path) = s {
@@ -379,6 +567,30 @@ impl<'a> FnSpec<'a> {
     }
 }
 
+/// Writes the default value of an argument, given as a Rust expression, as a Python literal.
+fn python_default(default: &syn::Expr) -> String {
+    match default {
+        syn::Expr::Lit(syn::ExprLit { lit, .. }) => match lit {
+            syn::Lit::Int(int) => int.base10_digits().to_string(),
+            syn::Lit::Float(float) => float.base10_digits().to_string(),
+            syn::Lit::Bool(b) if b.value => "True".to_string(),
+            syn::Lit::Bool(_) => "False".to_string(),
+            syn::Lit::Str(s) => format!("{:?}", s.value()),
+            _ => "...".to_string(),
+        },
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => match python_default(expr).as_str() {
+            "..." => "...".to_string(),
+            value => format!("-{}", value),
+        },
+        syn::Expr::Path(path) if path.path.is_ident("None") => "None".to_string(),
+        _ => "...".to_string(),
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 struct MethodAttributes {
     ty: Option<MethodTypeAttribute>,
@@ -2,10 +2,10 @@
 
 use crate::method::{FnType, SelfType};
 use crate::pyimpl::PyClassMethodsType;
-use crate::pymethod::{impl_py_getter_def, impl_py_setter_def, PropertyType};
-use crate::utils;
+use crate::pymethod::{impl_py_getter_def, impl_py_setter_def, BytesEncoding, PropertyType};
+use crate::utils::{self, RenameRule};
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use quote::{quote, quote_spanned};
 use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
@@ -18,11 +18,67 @@ pub struct PyClassArgs {
     pub base: syn::TypePath,
     pub has_dict: bool,
     pub has_weaklist: bool,
+    /// The span of the first `dict` or `weakref` argument, at which the layout of their slots is
+    /// checked.
+    pub slots_span: Option<Span>,
     pub is_gc: bool,
     pub is_basetype: bool,
     pub has_extends: bool,
     pub has_unsendable: bool,
-    pub module: Option<syn::LitStr>,
+    pub has_to_dict: bool,
+    pub has_eq: bool,
+    pub has_eq_dict: bool,
+    pub has_eq_tuple: bool,
+    pub has_ord: bool,
+    pub has_copy: bool,
+    pub has_deepcopy: bool,
+    pub has_clone: bool,
+    pub has_getnewargs_ex: bool,
+    pub has_getstate: bool,
+    pub has_positional_new: bool,
+    pub has_lenient_new: bool,
+    pub has_unary_ops: bool,
+    pub has_hash_fields: bool,
+    pub has_hash: bool,
+    pub has_cache_hash: bool,
+    pub has_iterator: bool,
+    pub has_repr: bool,
+    pub has_repr_debug: bool,
+    pub has_str: bool,
+    pub has_frozen: bool,
+    pub has_get_all: bool,
+    pub has_set_all: bool,
+    pub has_sequence: bool,
+    pub has_mapping: bool,
+    pub has_reentrant: bool,
+    pub has_format: bool,
+    pub repr_maxlen: Option<usize>,
+    pub instancecheck: Option<syn::Path>,
+    pub subclasscheck: Option<syn::Path>,
+    pub post_init: Option<syn::Path>,
+    pub set_name: Option<syn::Ident>,
+    pub set_owner: Option<syn::Ident>,
+    pub bytes: Option<syn::Ident>,
+    pub array: Option<syn::Ident>,
+    pub getitem: Option<syn::Ident>,
+    pub sequence_from: Option<syn::Ident>,
+    pub intern: Option<syn::Expr>,
+    pub enter: Option<syn::Ident>,
+    pub exit: Option<syn::Ident>,
+    pub anext: Option<syn::Ident>,
+    pub proxy: Option<syn::Ident>,
+    pub module: Option<syn::Expr>,
+    pub rename_all: Option<RenameRule>,
+    pub match_args: Option<Vec<syn::Ident>>,
+    pub buffer: Option<BufferFields>,
+}
+
+/// The fields of `#[pyclass(buffer(data = ..., shape = ...))]`.
+pub struct BufferFields {
+    /// The field holding the items, e.g. a `Vec<f64>`.
+    pub data: syn::Ident,
+    /// The field holding the length of each dimension, e.g. a `Vec<usize>`.
+    pub shape: syn::Ident,
 }
 
 impl Parse for PyClassArgs {
@@ -33,6 +89,90 @@ impl Parse for PyClassArgs {
         for expr in vars {
             slf.add_expr(&expr)?;
         }
+        if slf.has_eq_dict && !slf.has_eq {
+            return Err(input.error("`eq_dict` requires `eq`"));
+        }
+        if slf.has_eq_tuple && !slf.has_eq {
+            return Err(input.error("`eq_tuple` requires `eq`"));
+        }
+        if slf.has_eq && slf.has_ord {
+            return Err(input.error(
+                "`eq` and `ord` cannot be used together, as `ord` also implements `==` and `!=`",
+            ));
+        }
+        if slf.has_positional_new && slf.has_lenient_new {
+            return Err(input.error("`positional_new` and `lenient_new` cannot be used together"));
+        }
+        if slf.intern.is_some() && !slf.has_positional_new && !slf.has_lenient_new {
+            return Err(input.error("`intern` requires `positional_new` or `lenient_new`"));
+        }
+        if slf.intern.is_some() && !slf.has_frozen {
+            return Err(input.error(
+                "`intern` requires `frozen`, as interned instances are shared by every caller",
+            ));
+        }
+        if slf.exit.is_some() && slf.enter.is_none() {
+            return Err(input.error("`exit` requires `enter`"));
+        }
+        if slf.has_reentrant && slf.enter.is_none() {
+            return Err(input.error("`reentrant` requires `enter`"));
+        }
+        if slf.sequence_from.is_some() && slf.getitem.is_some() {
+            return Err(input.error("`sequence_from` and `getitem` cannot be used together"));
+        }
+        if slf.sequence_from.is_some() && slf.has_iterator {
+            return Err(input.error("`sequence_from` and `iterator` cannot be used together"));
+        }
+        if slf.repr_maxlen.is_some() && !slf.has_repr {
+            return Err(input.error("`repr_maxlen` requires `repr`"));
+        }
+        if slf.has_copy && slf.has_deepcopy {
+            return Err(input.error(
+                "`copy` and `deepcopy` cannot be used together, `copy` also generates `__deepcopy__`",
+            ));
+        }
+        if slf.has_clone && !slf.has_frozen {
+            return Err(input.error("`clone` requires `frozen`"));
+        }
+        if slf.has_clone && slf.has_copy {
+            return Err(input
+                .error("`clone` and `copy` cannot be used together, as both generate `__copy__`"));
+        }
+        if slf.has_repr && slf.has_repr_debug {
+            return Err(input.error("`repr` and `repr_debug` cannot be used together"));
+        }
+        if slf.has_hash && slf.has_hash_fields {
+            return Err(input.error("`hash` and `hash_fields` cannot be used together"));
+        }
+        if slf.has_hash_fields && !slf.has_frozen {
+            return Err(input.error(
+                "`hash_fields` requires `frozen`, as the hash must not change while the object is alive",
+            ));
+        }
+        if slf.has_cache_hash && !slf.has_hash && !slf.has_hash_fields {
+            return Err(input.error("`cache_hash` requires `hash` or `hash_fields`"));
+        }
+        if slf.has_cache_hash && !slf.has_frozen {
+            return Err(input
+                .error("`cache_hash` requires `frozen`, as the hash must not change once cached"));
+        }
+        if slf.has_frozen && (slf.set_name.is_some() || slf.set_owner.is_some()) {
+            return Err(input.error("`set_name` and `set_owner` cannot be used with `frozen`"));
+        }
+        if slf.has_frozen && slf.enter.is_some() {
+            return Err(input.error("`enter` cannot be used with `frozen`"));
+        }
+        if slf.has_frozen && slf.has_set_all {
+            return Err(input.error("`set_all` cannot be used with `frozen`"));
+        }
+        if slf.has_frozen && slf.is_gc {
+            return Err(input.error(
+                "`gc` cannot be used with `frozen`, as `__clear__` mutably borrows the object",
+            ));
+        }
+        if slf.has_sequence && slf.has_mapping {
+            return Err(input.error("`sequence` and `mapping` cannot be used together"));
+        }
         Ok(slf)
     }
 }
@@ -46,10 +186,56 @@ impl Default for PyClassArgs {
             base: parse_quote! { pyo3::PyAny },
             has_dict: false,
             has_weaklist: false,
+            slots_span: None,
             is_gc: false,
             is_basetype: false,
             has_extends: false,
             has_unsendable: false,
+            has_to_dict: false,
+            has_eq: false,
+            has_eq_dict: false,
+            has_eq_tuple: false,
+            has_ord: false,
+            has_copy: false,
+            has_deepcopy: false,
+            has_clone: false,
+            has_getnewargs_ex: false,
+            has_getstate: false,
+            has_positional_new: false,
+            has_lenient_new: false,
+            has_unary_ops: false,
+            has_hash_fields: false,
+            has_hash: false,
+            has_cache_hash: false,
+            has_iterator: false,
+            has_repr: false,
+            has_repr_debug: false,
+            has_str: false,
+            has_frozen: false,
+            has_get_all: false,
+            has_set_all: false,
+            has_sequence: false,
+            has_mapping: false,
+            has_reentrant: false,
+            has_format: false,
+            repr_maxlen: None,
+            instancecheck: None,
+            subclasscheck: None,
+            post_init: None,
+            set_name: None,
+            set_owner: None,
+            bytes: None,
+            array: None,
+            getitem: None,
+            sequence_from: None,
+            intern: None,
+            enter: None,
+            exit: None,
+            anext: None,
+            proxy: None,
+            rename_all: None,
+            match_args: None,
+            buffer: None,
         }
     }
 }
@@ -61,10 +247,79 @@ impl PyClassArgs {
         match expr {
             syn::Expr::Path(exp) if exp.path.segments.len() == 1 => self.add_path(exp),
             syn::Expr::Assign(assign) => self.add_assign(assign),
+            syn::Expr::Call(call) => self.add_call(call),
             _ => bail_spanned!(expr.span() => "failed to parse arguments"),
         }
     }
 
+    /// Match a list of names, e.g. `match_args(x, y)`, or the fields of `buffer(data = x, shape = y)`
+    fn add_call(&mut self, call: &syn::ExprCall) -> syn::Result<()> {
+        match &*call.func {
+            syn::Expr::Path(exp) if exp.path.is_ident("match_args") => {
+                let mut names = Vec::new();
+                for arg in &call.args {
+                    match arg {
+                        syn::Expr::Path(exp) if exp.path.get_ident().is_some() => {
+                            names.push(exp.path.get_ident().unwrap().clone());
+                        }
+                        _ => bail_spanned!(arg.span() => "expected the name of a field"),
+                    }
+                }
+                self.match_args = Some(names);
+                Ok(())
+            }
+            syn::Expr::Path(exp) if exp.path.is_ident("buffer") => {
+                let mut data = None;
+                let mut shape = None;
+                for arg in &call.args {
+                    let (key, value) = match arg {
+                        syn::Expr::Assign(syn::ExprAssign { left, right, .. }) => {
+                            match (&**left, &**right) {
+                                (syn::Expr::Path(key), syn::Expr::Path(value))
+                                    if key.path.get_ident().is_some()
+                                        && value.path.get_ident().is_some() =>
+                                {
+                                    (
+                                        key.path.get_ident().unwrap(),
+                                        value.path.get_ident().unwrap(),
+                                    )
+                                }
+                                _ => {
+                                    bail_spanned!(arg.span() => "expected `data = field` or `shape = field`")
+                                }
+                            }
+                        }
+                        _ => {
+                            bail_spanned!(arg.span() => "expected `data = field` or `shape = field`")
+                        }
+                    };
+                    let slot = if key == "data" {
+                        &mut data
+                    } else if key == "shape" {
+                        &mut shape
+                    } else {
+                        bail_spanned!(key.span() => "expected `data` or `shape`");
+                    };
+                    ensure_spanned!(
+                        slot.is_none(),
+                        key.span() => format!("`{}` is given more than once", key)
+                    );
+                    *slot = Some(value.clone());
+                }
+                match (data, shape) {
+                    (Some(data), Some(shape)) => {
+                        self.buffer = Some(BufferFields { data, shape });
+                        Ok(())
+                    }
+                    _ => {
+                        bail_spanned!(call.span() => "`buffer` requires both `data = field` and `shape = field`")
+                    }
+                }
+            }
+            _ => bail_spanned!(call.func.span() => "expected `match_args(...)` or `buffer(...)`"),
+        }
+    }
+
     /// Match a key/value flag
     fn add_assign(&mut self, assign: &syn::ExprAssign) -> syn::Result<()> {
         let syn::ExprAssign { left, right, .. } = assign;
@@ -121,15 +376,127 @@ impl PyClassArgs {
                 _ => expected!("type path (e.g., my_mod::BaseClass)"),
             },
             "module" => match &**right {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(_),
+                    ..
+                }) => {
+                    self.module = Some(syn::Expr::clone(right));
+                }
+                syn::Expr::Lit(_) => expected!(r#"string literal or constant (e.g., "my_mod")"#),
+                _ => {
+                    // Any constant expression evaluating to a `&'static str` is allowed too, e.g.
+                    // `concat!(env!("CARGO_PKG_NAME"), ".sub")`.
+                    self.module = Some(syn::Expr::clone(right));
+                }
+            },
+            "rename_all" => match &**right {
                 syn::Expr::Lit(syn::ExprLit {
                     lit: syn::Lit::Str(lit),
                     ..
                 }) => {
-                    self.module = Some(lit.clone());
+                    self.rename_all = Some(RenameRule::from_name(&lit.value()).ok_or_else(|| {
+                        err_spanned!(
+                            lit.span() => "expected one of \"camelCase\", \"PascalCase\" or \"kebab-case\""
+                        )
+                    })?);
+                }
+                _ => expected!(r#"string literal (e.g., "camelCase")"#),
+            },
+            "instancecheck" => match &**right {
+                syn::Expr::Path(exp) => {
+                    self.instancecheck = Some(exp.path.clone());
+                }
+                _ => expected!("function path (e.g., my_mod::check)"),
+            },
+            "subclasscheck" => match &**right {
+                syn::Expr::Path(exp) => {
+                    self.subclasscheck = Some(exp.path.clone());
+                }
+                _ => expected!("function path (e.g., my_mod::check)"),
+            },
+            "post_init" => match &**right {
+                syn::Expr::Path(exp) => {
+                    self.post_init = Some(exp.path.clone());
+                }
+                _ => expected!("function path (e.g., my_mod::validate)"),
+            },
+            "set_name" => match &**right {
+                syn::Expr::Path(exp) if exp.path.get_ident().is_some() => {
+                    self.set_name = exp.path.get_ident().cloned();
+                }
+                _ => expected!("field name (e.g., name)"),
+            },
+            "set_owner" => match &**right {
+                syn::Expr::Path(exp) if exp.path.get_ident().is_some() => {
+                    self.set_owner = exp.path.get_ident().cloned();
+                }
+                _ => expected!("field name (e.g., owner)"),
+            },
+            "bytes" => match &**right {
+                syn::Expr::Path(exp) if exp.path.get_ident().is_some() => {
+                    self.bytes = exp.path.get_ident().cloned();
+                }
+                _ => expected!("method name (e.g., to_bytes)"),
+            },
+            "array" => match &**right {
+                syn::Expr::Path(exp) if exp.path.get_ident().is_some() => {
+                    self.array = exp.path.get_ident().cloned();
+                }
+                _ => expected!("method name (e.g., to_ndarray)"),
+            },
+            "getitem" => match &**right {
+                syn::Expr::Path(exp) if exp.path.get_ident().is_some() => {
+                    self.getitem = exp.path.get_ident().cloned();
+                }
+                _ => expected!("field name (e.g., items)"),
+            },
+            "sequence_from" => match &**right {
+                syn::Expr::Path(exp) if exp.path.get_ident().is_some() => {
+                    self.sequence_from = exp.path.get_ident().cloned();
+                }
+                _ => expected!("field name (e.g., items)"),
+            },
+            "enter" => match &**right {
+                syn::Expr::Path(exp) if exp.path.get_ident().is_some() => {
+                    self.enter = exp.path.get_ident().cloned();
+                }
+                _ => expected!("method name (e.g., acquire)"),
+            },
+            "exit" => match &**right {
+                syn::Expr::Path(exp) if exp.path.get_ident().is_some() => {
+                    self.exit = exp.path.get_ident().cloned();
+                }
+                _ => expected!("method name (e.g., release)"),
+            },
+            "anext" => match &**right {
+                syn::Expr::Path(exp) if exp.path.get_ident().is_some() => {
+                    self.anext = exp.path.get_ident().cloned();
+                }
+                _ => expected!("field name (e.g., receiver)"),
+            },
+            "proxy" => match &**right {
+                syn::Expr::Path(exp) if exp.path.get_ident().is_some() => {
+                    self.proxy = exp.path.get_ident().cloned();
+                }
+                _ => expected!("field name (e.g., inner)"),
+            },
+            "repr_maxlen" => match &**right {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(lit),
+                    ..
+                }) => {
+                    self.repr_maxlen = Some(lit.base10_parse()?);
                 }
-                _ => expected!(r#"string literal (e.g., "my_mod")"#),
+                _ => expected!("integer literal (e.g., 80)"),
             },
-            _ => expected!("one of freelist/name/extends/module", left.span()),
+            "intern" => {
+                // Like `freelist`, any expression evaluating to a `usize` is allowed.
+                self.intern = Some(syn::Expr::clone(right));
+            }
+            _ => expected!(
+                "one of freelist/name/extends/module/instancecheck/subclasscheck/post_init/set_name/set_owner/bytes/array/getitem/sequence_from/repr_maxlen/intern/enter/exit/anext/proxy/rename_all",
+                left.span()
+            ),
         };
 
         Ok(())
@@ -144,18 +511,101 @@ impl PyClassArgs {
             }
             "weakref" => {
                 self.has_weaklist = true;
+                self.slots_span.get_or_insert(exp.span());
             }
             "subclass" => {
                 self.is_basetype = true;
             }
             "dict" => {
                 self.has_dict = true;
+                self.slots_span.get_or_insert(exp.span());
             }
             "unsendable" => {
                 self.has_unsendable = true;
             }
+            "to_dict" => {
+                self.has_to_dict = true;
+            }
+            "eq" => {
+                self.has_eq = true;
+            }
+            "eq_dict" => {
+                self.has_eq_dict = true;
+            }
+            "eq_tuple" => {
+                self.has_eq_tuple = true;
+            }
+            "ord" => {
+                self.has_ord = true;
+            }
+            "copy" => {
+                self.has_copy = true;
+            }
+            "deepcopy" => {
+                self.has_deepcopy = true;
+            }
+            "clone" => {
+                self.has_clone = true;
+            }
+            "getnewargs_ex" => {
+                self.has_getnewargs_ex = true;
+            }
+            "getstate" => {
+                self.has_getstate = true;
+            }
+            "positional_new" => {
+                self.has_positional_new = true;
+            }
+            "lenient_new" => {
+                self.has_lenient_new = true;
+            }
+            "unary_ops" => {
+                self.has_unary_ops = true;
+            }
+            "hash_fields" => {
+                self.has_hash_fields = true;
+            }
+            "hash" => {
+                self.has_hash = true;
+            }
+            "cache_hash" => {
+                self.has_cache_hash = true;
+            }
+            "iterator" => {
+                self.has_iterator = true;
+            }
+            "repr" => {
+                self.has_repr = true;
+            }
+            "repr_debug" => {
+                self.has_repr_debug = true;
+            }
+            "str" => {
+                self.has_str = true;
+            }
+            "frozen" => {
+                self.has_frozen = true;
+            }
+            "get_all" => {
+                self.has_get_all = true;
+            }
+            "set_all" => {
+                self.has_set_all = true;
+            }
+            "sequence" => {
+                self.has_sequence = true;
+            }
+            "mapping" => {
+                self.has_mapping = true;
+            }
+            "reentrant" => {
+                self.has_reentrant = true;
+            }
+            "format" => {
+                self.has_format = true;
+            }
             _ => bail_spanned!(
-                exp.path.span() => "expected one of gc/weakref/subclass/dict/unsendable"
+                exp.path.span() => "expected one of gc/weakref/subclass/dict/unsendable/to_dict/eq/eq_dict/eq_tuple/ord/copy/deepcopy/clone/getnewargs_ex/getstate/positional_new/lenient_new/unary_ops/hash_fields/hash/cache_hash/iterator/repr/repr_debug/str/frozen/get_all/set_all/sequence/mapping/reentrant/format"
             ),
         };
         Ok(())
@@ -167,44 +617,356 @@ pub fn build_py_class(
     attr: &PyClassArgs,
     methods_type: PyClassMethodsType,
 ) -> syn::Result<TokenStream> {
-    let text_signature = utils::parse_text_signature_attrs(
-        &mut class.attrs,
-        &get_class_python_name(&class.ident, attr),
-    )?;
-    let doc = utils::get_doc(&class.attrs, text_signature, true)?;
+    let doc = ClassDoc::parse(&mut class.attrs, get_class_python_name(&class.ident, attr))?;
     let mut descriptors = Vec::new();
+    let mut roles = FieldRoles::default();
 
     ensure_spanned!(
         class.generics.params.is_empty(),
-        class.generics.span() => "#[pyclass] cannot have generic parameters"
+        class.generics.span() => "#[pyclass] cannot have generic parameters; put it on a type alias of each instantiation instead, e.g. `type IntWrapper = Wrapper<i64>;`"
     );
 
     match &mut class.fields {
         syn::Fields::Named(fields) => {
             for field in fields.named.iter_mut() {
-                let field_descs = parse_descriptors(field)?;
-                if !field_descs.is_empty() {
-                    descriptors.push((field.clone(), field_descs));
+                let options =
+                    parse_descriptors(field, attr.has_get_all, attr.has_set_all, attr.rename_all)?;
+                if options.transient {
+                    ensure_spanned!(
+                        attr.has_getstate,
+                        field.span() => "`transient` requires `#[pyclass(getstate)]`"
+                    );
+                    roles.transient.extend(field.ident.clone());
+                }
+                if options.hash_key {
+                    let ident = field.ident.clone().unwrap();
+                    roles.set_hash_key(field, syn::Member::Named(ident))?;
+                }
+                if !options.descs.is_empty() {
+                    descriptors.push((field.clone(), options));
                 }
             }
         }
         syn::Fields::Unnamed(fields) => {
-            for field in fields.unnamed.iter_mut() {
-                let field_descs = parse_descriptors(field)?;
-                if !field_descs.is_empty() {
-                    descriptors.push((field.clone(), field_descs));
+            ensure_spanned!(
+                !attr.has_get_all && !attr.has_set_all,
+                fields.span() => "`get_all` and `set_all` require named fields"
+            );
+            ensure_spanned!(
+                !attr.has_getstate,
+                fields.span() => "`getstate` requires named fields"
+            );
+            for (index, field) in fields.unnamed.iter_mut().enumerate() {
+                let options = parse_descriptors(field, false, false, None)?;
+                ensure_spanned!(
+                    !options.transient,
+                    field.span() => "`transient` requires `#[pyclass(getstate)]`"
+                );
+                if options.hash_key {
+                    roles.set_hash_key(field, syn::Member::Unnamed(index.into()))?;
+                }
+                if !options.descs.is_empty() {
+                    descriptors.push((field.clone(), options));
                 }
             }
         }
         syn::Fields::Unit => { /* No fields for unit struct */ }
     }
+    if roles.hash_key.is_some() {
+        ensure_spanned!(
+            !attr.has_eq && !attr.has_ord && !attr.has_hash && !attr.has_hash_fields,
+            class.ident.span() => "`#[pyo3(hash)]` on a field cannot be used together with `#[pyclass(eq)]`, `ord`, `hash` or `hash_fields`"
+        );
+    }
+
+    let mut python_names = std::collections::HashSet::new();
+    if attr.has_to_dict {
+        python_names.insert("to_dict".to_string());
+    }
+    for (field, options) in &descriptors {
+        if let Some(ident) = &field.ident {
+            let name = options.python_name(ident);
+            let span = options.name.as_ref().unwrap_or(ident).span();
+            ensure_spanned!(
+                python_names.insert(name.clone()),
+                span => format!("the Python name `{}` is already used by another attribute of this class", name)
+            );
+        }
+    }
+
+    if attr.has_frozen {
+        for (_, options) in &descriptors {
+            for desc in &options.descs {
+                if let FnType::Setter(SelfType::Receiver { span, .. }) = desc {
+                    bail_spanned!(*span => "`set` cannot be used on a field of a `frozen` class");
+                }
+            }
+        }
+    }
+
+    impl_class(
+        ClassDef {
+            cls: &class.ident,
+            doc,
+            fields: &class.fields,
+            descriptors,
+            roles,
+            variants: None,
+        },
+        &attr,
+        methods_type,
+    )
+}
+
+pub fn build_py_enum(
+    enum_: &mut syn::ItemEnum,
+    attr: &PyClassArgs,
+    methods_type: PyClassMethodsType,
+) -> syn::Result<TokenStream> {
+    let doc = ClassDoc::parse(&mut enum_.attrs, get_class_python_name(&enum_.ident, attr))?;
+
+    ensure_spanned!(
+        enum_.generics.params.is_empty(),
+        enum_.generics.span() => "#[pyclass] cannot have generic parameters"
+    );
+    let unsupported = [
+        (attr.has_extends, "extends"),
+        (attr.has_eq, "eq"),
+        (attr.has_ord, "ord"),
+        (attr.has_hash, "hash"),
+        (attr.has_hash_fields, "hash_fields"),
+        (attr.has_repr, "repr"),
+        (attr.has_repr_debug, "repr_debug"),
+        (attr.has_copy, "copy"),
+        (attr.has_deepcopy, "deepcopy"),
+        (attr.has_clone, "clone"),
+        (attr.has_positional_new, "positional_new"),
+        (attr.has_lenient_new, "lenient_new"),
+        (attr.has_unary_ops, "unary_ops"),
+        (attr.buffer.is_some(), "buffer"),
+    ];
+    for (used, option) in unsupported.iter() {
+        ensure_spanned!(
+            !used,
+            enum_.ident.span() => format!("`{}` is not supported on enums", option)
+        );
+    }
+
+    ensure_spanned!(
+        !enum_.variants.is_empty(),
+        enum_.ident.span() => "#[pyclass] enums must have at least one variant"
+    );
+    let mut variants = EnumVariants {
+        idents: Vec::new(),
+        falsy: None,
+    };
+    for variant in enum_.variants.iter_mut() {
+        if let syn::Fields::Unit = variant.fields {
+            variants.idents.push(variant.ident.clone());
+        } else {
+            bail_spanned!(variant.span() => "#[pyclass] enums can only have fieldless variants");
+        }
+        if parse_variant_options(variant)? {
+            ensure_spanned!(
+                variants.falsy.is_none(),
+                variant.ident.span() => "only one variant can be `falsy`"
+            );
+            variants.falsy = Some(variant.ident.clone());
+        }
+    }
+
+    impl_class(
+        ClassDef {
+            cls: &enum_.ident,
+            doc,
+            fields: &syn::Fields::Unit,
+            descriptors: Vec::new(),
+            roles: FieldRoles::default(),
+            variants: Some(&variants),
+        },
+        &attr,
+        methods_type,
+    )
+}
+
+/// Builds a class from a type alias of one instantiation of a generic struct, e.g.
+/// `type IntWrapper = Wrapper<i64>;`.
+///
+/// The generated impls are for the alias, so each alias of the same struct gets its own type
+/// object, named after the alias by default. The struct's fields are not visible here, so the
+/// options which generate code from the fields are rejected.
+pub fn build_py_class_alias(
+    alias: &mut syn::ItemType,
+    attr: &PyClassArgs,
+    methods_type: PyClassMethodsType,
+) -> syn::Result<TokenStream> {
+    let doc = ClassDoc::parse(&mut alias.attrs, get_class_python_name(&alias.ident, attr))?;
+
+    ensure_spanned!(
+        alias.generics.params.is_empty(),
+        alias.generics.span() => "#[pyclass] type aliases cannot have generic parameters"
+    );
+    let field_options = [
+        (attr.has_to_dict, "to_dict"),
+        (attr.has_eq_dict, "eq_dict"),
+        (attr.has_eq_tuple, "eq_tuple"),
+        (attr.has_copy, "copy"),
+        (attr.has_deepcopy, "deepcopy"),
+        (attr.has_getnewargs_ex, "getnewargs_ex"),
+        (attr.has_getstate, "getstate"),
+        (attr.has_positional_new, "positional_new"),
+        (attr.has_lenient_new, "lenient_new"),
+        (attr.has_unary_ops, "unary_ops"),
+        (attr.has_hash_fields, "hash_fields"),
+        (attr.has_repr, "repr"),
+        (attr.has_get_all, "get_all"),
+        (attr.has_set_all, "set_all"),
+    ];
+    for (enabled, option) in field_options.iter() {
+        ensure_spanned!(
+            !enabled,
+            alias.ident.span() => format!(
+                "`{}` is not supported on a type alias, because #[pyclass] cannot see its fields",
+                option
+            )
+        );
+    }
+
+    impl_class(
+        ClassDef {
+            cls: &alias.ident,
+            doc,
+            fields: &syn::Fields::Unit,
+            descriptors: Vec::new(),
+            roles: FieldRoles::default(),
+            variants: None,
+        },
+        attr,
+        methods_type,
+    )
+}
+
+/// The variants of a `#[pyclass]` enum.
+pub struct EnumVariants {
+    pub idents: Vec<syn::Ident>,
+    /// The variant marked `#[pyo3(falsy)]`, if any.
+    pub falsy: Option<syn::Ident>,
+}
+
+/// Parses and removes the `#[pyo3(...)]` options of an enum variant, returning whether it is
+/// marked `falsy`.
+fn parse_variant_options(variant: &mut syn::Variant) -> syn::Result<bool> {
+    let mut falsy = false;
+    let mut new_attrs = Vec::new();
+    for attr in variant.attrs.drain(..) {
+        if attr.path.is_ident("pyo3") {
+            if let syn::Meta::List(list) = attr.parse_meta()? {
+                for meta in list.nested.iter() {
+                    match meta {
+                        syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("falsy") => {
+                            falsy = true;
+                        }
+                        _ => {
+                            bail_spanned!(meta.span() => "only `falsy` is supported on enum variants")
+                        }
+                    }
+                }
+                continue;
+            }
+        }
+        new_attrs.push(attr);
+    }
+    variant.attrs = new_attrs;
+    Ok(falsy)
+}
+
+/// Fields singled out by `#[pyo3(...)]` options which affect the whole class.
+#[derive(Default)]
+struct FieldRoles {
+    /// The `#[pyo3(transient)]` fields, left out of the state of `#[pyclass(getstate)]`.
+    transient: Vec<syn::Ident>,
+    /// The `#[pyo3(hash)]` field and its type, which alone define `__hash__` and `__eq__`.
+    hash_key: Option<(syn::Member, syn::Type)>,
+}
+
+impl FieldRoles {
+    fn set_hash_key(&mut self, field: &syn::Field, member: syn::Member) -> syn::Result<()> {
+        ensure_spanned!(
+            self.hash_key.is_none(),
+            field.span() => "only one field can have `#[pyo3(hash)]`"
+        );
+        self.hash_key = Some((member, field.ty.clone()));
+        Ok(())
+    }
+}
+
+/// The `#[pyo3(...)]` options of a `#[pyclass]` field.
+pub struct FieldPyO3Options {
+    /// The getter and setter to generate.
+    pub descs: Vec<FnType>,
+    /// `allow_threads`: release the GIL while the getter clones the field.
+    pub allow_threads: bool,
+    /// `lock`: clone the value guarded by a `Mutex` field while holding its lock.
+    pub lock: bool,
+    /// `scale = N`: expose an integer field as a `decimal.Decimal` with `N` decimal places.
+    pub scale: Option<u32>,
+    /// `hex`, `base64` or `as_bytes`: expose a byte array field as an encoded `str` or as `bytes`.
+    pub encoding: Option<BytesEncoding>,
+    /// `as_indices`: expose a `FixedBitSet` field as a `frozenset` of the indices of its set bits.
+    pub as_indices: bool,
+    /// `as_deque`: return a `VecDeque` field from the getter as a `collections.deque`.
+    pub as_deque: bool,
+    /// `as_frozenset`: return a collection field from the getter as a `frozenset`.
+    pub as_frozenset: bool,
+    /// `transient`: leave the field out of the state of `#[pyclass(getstate)]`.
+    pub transient: bool,
+    /// `hash`: make `__hash__` and `__eq__` of the class use only this field.
+    pub hash_key: bool,
+    /// `name = "..."`: the Python name of the property, if it differs from the field name.
+    pub name: Option<syn::Ident>,
+    /// `no_match_arg`: leave a readable field out of `__match_args__`.
+    pub no_match_arg: bool,
+    /// `unit = "..."`: expose a `uom` quantity field as its value in the given unit.
+    pub unit: Option<syn::Path>,
+    /// The `rename_all` case conversion of the class, applied unless `name` is given.
+    pub rename_all: Option<RenameRule>,
+}
 
-    impl_class(&class.ident, &attr, doc, descriptors, methods_type)
+impl FieldPyO3Options {
+    /// The name under which the field with the given identifier is exposed to Python.
+    fn python_name(&self, ident: &syn::Ident) -> String {
+        match (&self.name, self.rename_all) {
+            (Some(name), _) => name.unraw().to_string(),
+            (None, Some(rule)) => rule.apply(&ident.unraw().to_string()),
+            (None, None) => ident.unraw().to_string(),
+        }
+    }
 }
 
-/// Parses `#[pyo3(get, set)]`
-fn parse_descriptors(item: &mut syn::Field) -> syn::Result<Vec<FnType>> {
+/// Parses the `#[pyo3(...)]` options of a field.
+///
+/// With `#[pyclass(get_all)]` or `#[pyclass(set_all)]`, a field without an explicit `get` or `set`
+/// gets the class-wide descriptors, spanned at the field so that a type which can't be converted
+/// is reported there. An explicit `get` or `set` replaces the class-wide setting for that field.
+fn parse_descriptors(
+    item: &mut syn::Field,
+    get_all: bool,
+    set_all: bool,
+    rename_all: Option<RenameRule>,
+) -> syn::Result<FieldPyO3Options> {
     let mut descs = Vec::new();
+    let mut allow_threads = false;
+    let mut lock = false;
+    let mut scale = None;
+    let mut encoding = None;
+    let mut as_indices = false;
+    let mut as_deque = false;
+    let mut as_frozenset = false;
+    let mut transient = false;
+    let mut hash_key = false;
+    let mut name = None;
+    let mut no_match_arg = false;
+    let mut unit = None;
     let mut new_attrs = Vec::new();
     for attr in item.attrs.drain(..) {
         if let Ok(syn::Meta::List(list)) = attr.parse_meta() {
@@ -212,11 +974,73 @@ fn parse_descriptors(item: &mut syn::Field) -> syn::Result<Vec<FnType>> {
                 for meta in list.nested.iter() {
                     if let syn::NestedMeta::Meta(metaitem) = meta {
                         if metaitem.path().is_ident("get") {
-                            descs.push(FnType::Getter(SelfType::Receiver { mutable: false }));
+                            descs.push(FnType::Getter(SelfType::Receiver {
+                                mutable: false,
+                                span: metaitem.span(),
+                            }));
                         } else if metaitem.path().is_ident("set") {
-                            descs.push(FnType::Setter(SelfType::Receiver { mutable: true }));
+                            descs.push(FnType::Setter(SelfType::Receiver {
+                                mutable: true,
+                                span: metaitem.span(),
+                            }));
+                        } else if metaitem.path().is_ident("allow_threads") {
+                            allow_threads = true;
+                        } else if metaitem.path().is_ident("lock") {
+                            lock = true;
+                        } else if metaitem.path().is_ident("hex") {
+                            set_encoding(&mut encoding, BytesEncoding::Hex, metaitem)?;
+                        } else if metaitem.path().is_ident("base64") {
+                            set_encoding(&mut encoding, BytesEncoding::Base64, metaitem)?;
+                        } else if metaitem.path().is_ident("as_bytes") {
+                            set_encoding(&mut encoding, BytesEncoding::Bytes, metaitem)?;
+                        } else if metaitem.path().is_ident("as_indices") {
+                            as_indices = true;
+                        } else if metaitem.path().is_ident("as_deque") {
+                            as_deque = true;
+                        } else if metaitem.path().is_ident("as_frozenset") {
+                            as_frozenset = true;
+                        } else if metaitem.path().is_ident("transient") {
+                            transient = true;
+                        } else if metaitem.path().is_ident("hash") {
+                            hash_key = true;
+                        } else if metaitem.path().is_ident("no_match_arg") {
+                            no_match_arg = true;
+                        } else if metaitem.path().is_ident("name") {
+                            match metaitem {
+                                syn::Meta::NameValue(syn::MetaNameValue {
+                                    lit: syn::Lit::Str(lit),
+                                    ..
+                                }) => name = Some(lit.parse_with(syn::Ident::parse_any).map_err(
+                                    |_| err_spanned!(lit.span() => "`name` must be an identifier"),
+                                )?),
+                                _ => bail_spanned!(
+                                    metaitem.span() => "expected `name = \"...\"` with a string literal"
+                                ),
+                            }
+                        } else if metaitem.path().is_ident("unit") {
+                            match metaitem {
+                                syn::Meta::NameValue(syn::MetaNameValue {
+                                    lit: syn::Lit::Str(lit),
+                                    ..
+                                }) => unit = Some(lit.parse::<syn::Path>().map_err(
+                                    |_| err_spanned!(lit.span() => "`unit` must be the path of a unit type, e.g. \"meter\""),
+                                )?),
+                                _ => bail_spanned!(
+                                    metaitem.span() => "expected `unit = \"...\"` with a string literal"
+                                ),
+                            }
+                        } else if metaitem.path().is_ident("scale") {
+                            match metaitem {
+                                syn::Meta::NameValue(syn::MetaNameValue {
+                                    lit: syn::Lit::Int(lit),
+                                    ..
+                                }) => scale = Some(lit.base10_parse()?),
+                                _ => bail_spanned!(
+                                    metaitem.span() => "expected `scale = N` with an integer `N`"
+                                ),
+                            }
                         } else {
-                            bail_spanned!(metaitem.span() => "only get and set are supported");
+                            bail_spanned!(metaitem.span() => "only get, set, name, allow_threads, lock, scale, hex, base64, as_bytes, as_indices, as_deque, as_frozenset, no_match_arg, transient, hash and unit are supported");
                         }
                     }
                 }
@@ -228,9 +1052,156 @@ fn parse_descriptors(item: &mut syn::Field) -> syn::Result<Vec<FnType>> {
         }
     }
     item.attrs = new_attrs;
-    Ok(descs)
+    if descs.is_empty() {
+        let span = item
+            .ident
+            .as_ref()
+            .map_or_else(|| item.ty.span(), |ident| ident.span());
+        if get_all {
+            descs.push(FnType::Getter(SelfType::Receiver {
+                mutable: false,
+                span,
+            }));
+        }
+        if set_all {
+            descs.push(FnType::Setter(SelfType::Receiver {
+                mutable: true,
+                span,
+            }));
+        }
+    }
+    ensure_spanned!(
+        !allow_threads || descs.iter().any(|desc| desc.is_getter()),
+        item.span() => "`allow_threads` requires `get`"
+    );
+    if lock {
+        ensure_spanned!(
+            descs.iter().any(|desc| desc.is_getter()),
+            item.span() => "`lock` requires `get`"
+        );
+        ensure_spanned!(
+            !descs.iter().any(|desc| desc.is_setter()),
+            item.span() => "`lock` cannot be used with `set`, assign to the value behind the lock in a method instead"
+        );
+        ensure_spanned!(
+            !allow_threads,
+            item.span() => "`lock` and `allow_threads` cannot be used together, `lock` already releases the GIL while waiting for the lock"
+        );
+    }
+    ensure_spanned!(
+        scale.is_none() || !descs.is_empty(),
+        item.span() => "`scale` requires `get` or `set`"
+    );
+    if let Some(encoding) = encoding {
+        ensure_spanned!(
+            !descs.is_empty(),
+            item.span() => format!("`{}` requires `get` or `set`", encoding.name())
+        );
+        ensure_spanned!(
+            scale.is_none(),
+            item.span() => format!("`{}` and `scale` cannot be used together", encoding.name())
+        );
+    }
+    if as_indices {
+        ensure_spanned!(
+            !descs.is_empty(),
+            item.span() => "`as_indices` requires `get` or `set`"
+        );
+        ensure_spanned!(
+            scale.is_none() && encoding.is_none(),
+            item.span() => "`as_indices` cannot be used together with `scale`, `hex`, `base64` or `as_bytes`"
+        );
+    }
+    if as_deque {
+        ensure_spanned!(
+            descs.iter().any(|desc| desc.is_getter()),
+            item.span() => "`as_deque` requires `get`"
+        );
+        ensure_spanned!(
+            scale.is_none() && encoding.is_none() && !as_indices,
+            item.span() => "`as_deque` cannot be used together with `scale`, `hex`, `base64`, `as_bytes` or `as_indices`"
+        );
+    }
+    if as_frozenset {
+        ensure_spanned!(
+            descs.iter().any(|desc| desc.is_getter()),
+            item.span() => "`as_frozenset` requires `get`"
+        );
+        ensure_spanned!(
+            scale.is_none() && encoding.is_none() && !as_indices && !as_deque,
+            item.span() => "`as_frozenset` cannot be used together with `scale`, `hex`, `base64`, `as_bytes`, `as_indices` or `as_deque`"
+        );
+    }
+    ensure_spanned!(
+        name.is_none() || !descs.is_empty(),
+        item.span() => "`name` requires `get` or `set`"
+    );
+    ensure_spanned!(
+        !hash_key || !descs.iter().any(|desc| desc.is_setter()),
+        item.span() => "`hash` cannot be used with `set`, as the hash of an object must not change while it is alive"
+    );
+    ensure_spanned!(
+        !hash_key || !lock,
+        item.span() => "`hash` cannot be used with `lock`, as the value behind the lock can change while the object is alive"
+    );
+    ensure_spanned!(
+        !no_match_arg || descs.iter().any(|desc| desc.is_getter()),
+        item.span() => "`no_match_arg` requires `get`"
+    );
+    if unit.is_some() {
+        ensure_spanned!(
+            !descs.is_empty(),
+            item.span() => "`unit` requires `get` or `set`"
+        );
+        ensure_spanned!(
+            scale.is_none() && encoding.is_none() && !as_indices && !as_deque && !as_frozenset,
+            item.span() => "`unit` cannot be used together with `scale`, `hex`, `base64`, `as_bytes`, `as_indices`, `as_deque` or `as_frozenset`"
+        );
+    }
+    Ok(FieldPyO3Options {
+        descs,
+        allow_threads,
+        lock,
+        scale,
+        encoding,
+        as_indices,
+        as_deque,
+        as_frozenset,
+        transient,
+        hash_key,
+        name,
+        no_match_arg,
+        unit,
+        rename_all,
+    })
+}
+
+fn set_encoding(
+    encoding: &mut Option<BytesEncoding>,
+    new: BytesEncoding,
+    metaitem: &syn::Meta,
+) -> syn::Result<()> {
+    if let Some(existing) = encoding {
+        bail_spanned!(
+            metaitem.span() => format!("`{}` and `{}` cannot be used together", existing.name(), new.name())
+        );
+    }
+    *encoding = Some(new);
+    Ok(())
 }
 
+/// The generated slots which `#[pyproto]` methods can also fill, with the marker trait implemented
+/// for the class by both, so that filling one both ways is a compile error.
+const SLOT_MARKERS: &[(&str, &str)] = &[
+    ("Py_tp_richcompare", "PyClassRichcmpSlot"),
+    ("Py_tp_repr", "PyClassReprSlot"),
+    ("Py_tp_str", "PyClassStrSlot"),
+    ("Py_tp_hash", "PyClassHashSlot"),
+    ("Py_sq_item", "PyClassSqItemSlot"),
+    ("Py_mp_subscript", "PyClassMpSubscriptSlot"),
+    ("Py_tp_iter", "PyClassIterSlot"),
+];
+
 /// To allow multiple #[pymethods] block, we define inventory types.
 fn impl_methods_inventory(cls: &syn::Ident) -> TokenStream {
     // Try to build a unique type for better error messages
@@ -240,10 +1211,10 @@ fn impl_methods_inventory(cls: &syn::Ident) -> TokenStream {
     quote! {
         #[doc(hidden)]
         pub struct #inventory_cls {
-            methods: Vec<pyo3::class::PyMethodDefType>,
+            methods: ::std::vec::Vec<pyo3::class::PyMethodDefType>,
         }
         impl pyo3::class::impl_::PyMethodsInventory for #inventory_cls {
-            fn new(methods: Vec<pyo3::class::PyMethodDefType>) -> Self {
+            fn new(methods: ::std::vec::Vec<pyo3::class::PyMethodDefType>) -> Self {
                 Self { methods }
             }
             fn get(&'static self) -> &'static [pyo3::class::PyMethodDefType] {
@@ -263,26 +1234,75 @@ fn get_class_python_name<'a>(cls: &'a syn::Ident, attr: &'a PyClassArgs) -> &'a
     attr.name.as_ref().unwrap_or(cls)
 }
 
+/// The doc string of a `#[pyclass]`.
+struct ClassDoc {
+    doc: syn::LitStr,
+    /// Whether the class has a `#[text_signature]`, which then takes precedence over the one
+    /// generated from its `#[new]` method.
+    has_text_signature: bool,
+}
+
+impl ClassDoc {
+    fn parse(attrs: &mut Vec<syn::Attribute>, python_name: &syn::Ident) -> syn::Result<Self> {
+        let text_signature = utils::parse_text_signature_attrs(attrs, python_name)?;
+        let has_text_signature = text_signature.is_some();
+        Ok(ClassDoc {
+            doc: utils::get_doc(attrs, text_signature, true)?,
+            has_text_signature,
+        })
+    }
+}
+
+/// The struct, enum or type alias which `impl_class` generates a class for.
+struct ClassDef<'a> {
+    cls: &'a syn::Ident,
+    doc: ClassDoc,
+    /// The fields of a struct, or `Unit` for an enum or a type alias.
+    fields: &'a syn::Fields,
+    descriptors: Vec<(syn::Field, FieldPyO3Options)>,
+    roles: FieldRoles,
+    variants: Option<&'a EnumVariants>,
+}
+
 fn impl_class(
-    cls: &syn::Ident,
+    class: ClassDef,
     attr: &PyClassArgs,
-    doc: syn::LitStr,
-    descriptors: Vec<(syn::Field, Vec<FnType>)>,
     methods_type: PyClassMethodsType,
 ) -> syn::Result<TokenStream> {
+    let ClassDef {
+        cls,
+        doc: ClassDoc {
+            doc,
+            has_text_signature,
+        },
+        fields,
+        descriptors,
+        roles,
+        variants,
+    } = class;
     let cls_name = get_class_python_name(cls, attr).to_string();
 
     let extra = {
         if let Some(freelist) = &attr.freelist {
+            // Evaluated in a const context, so a non-const capacity is reported at the expression.
+            // Panicking in a const needs a newer Rust than the MSRV, so a capacity of zero is
+            // rejected by an out of bounds index instead, in a const whose name rustc shows.
+            let capacity = quote_spanned! {freelist.span()=>
+                const __FREELIST_CAPACITY: usize = #freelist;
+                #[allow(dead_code)]
+                const FREELIST_CAPACITY_MUST_BE_NONZERO: () =
+                    [()][(__FREELIST_CAPACITY == 0) as usize];
+            };
             quote! {
                 impl pyo3::freelist::PyClassWithFreeList for #cls {
                     #[inline]
                     fn get_free_list(_py: pyo3::Python) -> &mut pyo3::freelist::FreeList<*mut pyo3::ffi::PyObject> {
+                        #capacity
                         static mut FREELIST: *mut pyo3::freelist::FreeList<*mut pyo3::ffi::PyObject> = 0 as *mut _;
                         unsafe {
                             if FREELIST.is_null() {
                                 FREELIST = Box::into_raw(Box::new(
-                                    pyo3::freelist::FreeList::with_capacity(#freelist)));
+                                    pyo3::freelist::FreeList::with_capacity(__FREELIST_CAPACITY)));
                             }
                             &mut *FREELIST
                         }
@@ -296,26 +1316,295 @@ fn impl_class(
         }
     };
 
-    let extra = if !descriptors.is_empty() {
-        let path = syn::Path::from(syn::PathSegment::from(cls.clone()));
-        let ty = syn::Type::from(syn::TypePath { path, qself: None });
-        let desc_impls = impl_descriptors(&ty, descriptors)?;
+    let path = syn::Path::from(syn::PathSegment::from(cls.clone()));
+    let ty = syn::Type::from(syn::TypePath { path, qself: None });
+
+    let mut generated_methods = Vec::new();
+    if attr.has_to_dict {
+        generated_methods.push(impl_to_dict(&ty, &descriptors)?);
+    }
+    if attr.has_deepcopy {
+        ensure_spanned!(
+            !attr.has_extends,
+            attr.base.span() => "`deepcopy` is not supported together with `extends`"
+        );
+        generated_methods.push(impl_deepcopy(&ty, fields, attr.has_frozen));
+    }
+    if attr.has_copy {
+        ensure_spanned!(
+            !attr.has_extends,
+            attr.base.span() => "`copy` is not supported together with `extends`"
+        );
+        generated_methods.extend(impl_copy(&ty, fields, attr.has_frozen));
+    }
+    if attr.has_clone {
+        generated_methods.push(impl_identity_copy(&ty));
+    }
+    if attr.has_getnewargs_ex {
+        ensure_spanned!(
+            !attr.has_extends,
+            attr.base.span() => "`getnewargs_ex` is not supported together with `extends`"
+        );
+        generated_methods.push(impl_getnewargs_ex(&ty, &descriptors)?);
+    }
+    if attr.has_getstate {
+        ensure_spanned!(
+            !attr.has_extends,
+            attr.base.span() => "`getstate` is not supported together with `extends`"
+        );
+        ensure_spanned!(
+            !attr.has_frozen,
+            cls.span() => "`getstate` cannot be used with `frozen`, as `__setstate__` mutates the object"
+        );
+        generated_methods.extend(impl_getstate(&ty, fields, &descriptors, &roles.transient));
+    }
+    if let Some(match_args) = impl_match_args(&descriptors, attr.match_args.as_deref())? {
+        generated_methods.push(match_args);
+    }
+    if attr.set_name.is_some() || attr.set_owner.is_some() {
+        generated_methods.push(impl_set_name(&ty, attr));
+    }
+    if let Some(method) = &attr.bytes {
+        generated_methods.push(impl_bytes(&ty, method));
+    }
+    if let Some(method) = &attr.array {
+        generated_methods.push(impl_array(&ty, method));
+    }
+    if let Some(enter) = &attr.enter {
+        generated_methods.extend(impl_context_manager(
+            &ty,
+            enter,
+            attr.exit.as_ref(),
+            attr.has_reentrant,
+        ));
+    }
+    if let Some(variants) = variants {
+        generated_methods.extend(impl_enum_methods(&ty, &variants.idents));
+    }
+    if attr.has_format {
+        let variants = variants
+            .ok_or_else(|| err_spanned!(cls.span() => "`format` is only supported on enums"))?;
+        generated_methods.push(impl_enum_format(&ty, &variants.idents));
+    }
+
+    let extra = if attr.has_positional_new || attr.has_lenient_new {
+        let option = if attr.has_lenient_new {
+            "lenient_new"
+        } else {
+            "positional_new"
+        };
+        ensure_spanned!(
+            !attr.has_extends,
+            attr.base.span() => format!("`{}` is not supported together with `extends`", option)
+        );
+        let new_impl = impl_positional_new(
+            &ty,
+            fields,
+            &descriptors,
+            attr.has_lenient_new,
+            attr.has_frozen,
+            attr.intern.as_ref(),
+        )?;
         quote! {
-            #desc_impls
+            #new_impl
             #extra
         }
     } else {
         extra
     };
 
-    // insert space for weak ref
-    let weakref = if attr.has_weaklist {
-        quote! { pyo3::pyclass_slots::PyClassWeakRefSlot }
-    } else if attr.has_extends {
-        quote! { <Self::BaseType as pyo3::class::impl_::PyClassBaseType>::WeakRef }
+    let metaclass_methods = impl_metaclass_checks(&ty, attr);
+    let extra = if !metaclass_methods.is_empty() {
+        quote! {
+            impl pyo3::class::impl_::PyClassMetaclassMethods<#cls>
+                for pyo3::class::impl_::PyClassImplCollector<#cls>
+            {
+                fn py_class_metaclass_methods(self) -> &'static [pyo3::class::methods::PyMethodDefType] {
+                    static METHODS: &[pyo3::class::methods::PyMethodDefType] =
+                        &[#(#metaclass_methods),*];
+                    METHODS
+                }
+            }
+            #extra
+        }
     } else {
-        quote! { pyo3::pyclass_slots::PyClassDummySlot }
+        extra
     };
+
+    let mut metaclass_slots = Vec::new();
+    if let Some(variants) = variants {
+        metaclass_slots.push(impl_enum_contains(&ty, &variants.idents));
+        metaclass_slots.push(impl_enum_getitem(&variants.idents));
+    }
+    let extra = if !metaclass_slots.is_empty() {
+        let slot_fns = metaclass_slots.iter().map(|slot| &slot.func);
+        let slot_defs = metaclass_slots.iter().map(|slot| {
+            let GeneratedSlot { slot, name, .. } = slot;
+            quote! { pyo3::ffi::PyType_Slot { slot: #slot, pfunc: #name as _ } }
+        });
+        quote! {
+            impl pyo3::class::impl_::PyClassMetaclassSlots<#cls>
+                for pyo3::class::impl_::PyClassImplCollector<#cls>
+            {
+                fn py_class_metaclass_slots(self) -> &'static [pyo3::ffi::PyType_Slot] {
+                    #(#slot_fns)*
+                    const SLOTS: &[pyo3::ffi::PyType_Slot] = &[#(#slot_defs),*];
+                    SLOTS
+                }
+            }
+            #extra
+        }
+    } else {
+        extra
+    };
+
+    let extra = if !generated_methods.is_empty() {
+        quote! {
+            impl pyo3::class::impl_::PyClassGeneratedMethods<#cls>
+                for pyo3::class::impl_::PyClassImplCollector<#cls>
+            {
+                fn py_class_generated_methods(self) -> &'static [pyo3::class::methods::PyMethodDefType] {
+                    static METHODS: &[pyo3::class::methods::PyMethodDefType] =
+                        &[#(#generated_methods),*];
+                    METHODS
+                }
+            }
+            #extra
+        }
+    } else {
+        extra
+    };
+
+    let mut generated_slots = Vec::new();
+    if attr.has_eq {
+        generated_slots.push(impl_richcmp(&ty, attr, fields, &descriptors)?);
+    }
+    if attr.has_ord {
+        generated_slots.push(impl_ord(&ty));
+    }
+    if attr.has_hash_fields {
+        generated_slots.push(impl_hash_fields(&ty, fields, &descriptors)?);
+    }
+    if attr.has_hash {
+        generated_slots.push(impl_hash(&ty));
+    }
+    if let Some((key, key_ty)) = &roles.hash_key {
+        generated_slots.push(impl_key_richcmp(&ty, key, key_ty));
+        generated_slots.push(impl_key_hash(&ty, key, key_ty));
+    }
+    if attr.has_repr {
+        generated_slots.push(impl_repr(&ty, attr, &descriptors)?);
+    }
+    if attr.has_repr_debug {
+        generated_slots.push(impl_repr_debug(&ty));
+    }
+    if attr.has_str {
+        generated_slots.push(impl_str(&ty));
+    }
+    if attr.has_unary_ops {
+        ensure_spanned!(
+            !attr.has_extends,
+            attr.base.span() => "`unary_ops` is not supported together with `extends`"
+        );
+        generated_slots.extend(impl_unary_ops(&ty, cls, fields)?);
+    }
+    if let Some(field) = &attr.getitem {
+        generated_slots.push(impl_getitem(&ty, field));
+    }
+    if let Some(field) = &attr.sequence_from {
+        generated_slots.extend(impl_sequence_from(&ty, field));
+    }
+    if attr.has_iterator {
+        generated_slots.push(impl_iter_self());
+    }
+    if let Some(field) = &attr.anext {
+        generated_slots.extend(impl_anext(&ty, field));
+    }
+    if let Some(field) = &attr.proxy {
+        generated_slots.push(impl_proxy(&ty, field));
+    }
+    if let Some(validate) = &attr.post_init {
+        ensure_spanned!(
+            !attr.has_extends,
+            attr.base.span() => "`post_init` is not supported together with `extends`"
+        );
+        generated_slots.push(impl_post_init(&ty, validate));
+    }
+    if attr.buffer.is_some() {
+        generated_slots.extend(impl_shaped_buffer_slots(&ty));
+    }
+    if let Some(variants) = variants {
+        generated_slots.extend(impl_enum_slots(&ty, variants));
+    }
+    // Conflict with the same impls from `#[pyproto]` methods filling these slots.
+    let slot_markers = SLOT_MARKERS.iter().filter_map(|(slot, marker)| {
+        if generated_slots
+            .iter()
+            .any(|generated| generated.slot.to_string().ends_with(slot))
+        {
+            let marker = syn::Ident::new(marker, Span::call_site());
+            Some(quote! { impl pyo3::class::impl_::#marker for #cls {} })
+        } else {
+            None
+        }
+    });
+    let extra = quote! {
+        #(#slot_markers)*
+        #extra
+    };
+
+    let extra = if attr.is_basetype {
+        // Required by `__init_subclass__` in `#[pymethods]`.
+        quote! {
+            impl pyo3::class::impl_::PyClassSubclassable for #cls {}
+            #extra
+        }
+    } else {
+        extra
+    };
+
+    let extra = if !generated_slots.is_empty() {
+        let slot_fns = generated_slots.iter().map(|slot| &slot.func);
+        let slot_defs = generated_slots.iter().map(|slot| {
+            let GeneratedSlot { slot, name, .. } = slot;
+            quote! { pyo3::ffi::PyType_Slot { slot: #slot, pfunc: #name as _ } }
+        });
+        quote! {
+            impl pyo3::class::impl_::PyClassGeneratedSlots<#cls>
+                for pyo3::class::impl_::PyClassImplCollector<#cls>
+            {
+                fn py_class_generated_slots(self) -> &'static [pyo3::ffi::PyType_Slot] {
+                    #(#slot_fns)*
+                    const SLOTS: &[pyo3::ffi::PyType_Slot] = &[#(#slot_defs),*];
+                    SLOTS
+                }
+            }
+            #extra
+        }
+    } else {
+        extra
+    };
+
+    let extra = if !descriptors.is_empty() {
+        let desc_impls = impl_descriptors(&ty, descriptors)?;
+        quote! {
+            #desc_impls
+            #extra
+        }
+    } else {
+        extra
+    };
+
+    // insert space for weak ref
+    let weakref = if attr.has_weaklist {
+        quote! { pyo3::pyclass_slots::PyClassWeakRefSlot }
+    } else if attr.has_extends {
+        quote! { <Self::BaseType as pyo3::class::impl_::PyClassBaseType>::WeakRef }
+    } else {
+        quote! { pyo3::pyclass_slots::PyClassDummySlot }
+    };
+    // A base class's dict slot is not part of `LayoutAsBase`, so a subclass always has exactly one
+    // dict slot at the end of its own layout, whether or not the base also has `dict`.
     let dict = if attr.has_dict {
         quote! { pyo3::pyclass_slots::PyClassDictSlot }
     } else if attr.has_extends {
@@ -323,8 +1612,19 @@ fn impl_class(
     } else {
         quote! { pyo3::pyclass_slots::PyClassDummySlot }
     };
+    let hash_cache = if attr.has_cache_hash {
+        quote! { pyo3::pyclass_slots::PyClassHashCacheSlot }
+    } else {
+        quote! { pyo3::pyclass_slots::PyClassDummySlot }
+    };
+    let enter_depth = if attr.has_reentrant {
+        quote! { pyo3::pyclass_slots::PyClassEnterDepthSlot }
+    } else {
+        quote! { pyo3::pyclass_slots::PyClassDummySlot }
+    };
     let module = if let Some(m) = &attr.module {
-        quote! { Some(#m) }
+        // Spanned at the expression, so that one which isn't a `&'static str` is reported there.
+        quote_spanned! { m.span() => Some(#m) }
     } else {
         quote! { None }
     };
@@ -345,6 +1645,57 @@ fn impl_class(
         quote! {}
     };
 
+    // Enforce at compile time that `copy` can clone the struct
+    let clone_impl = if attr.has_copy {
+        let closure_name = format!("__assertion_closure_clone_{}", cls);
+        let closure_token = syn::Ident::new(&closure_name, Span::call_site());
+        quote! {
+            fn #closure_token() {
+                fn _assert_implements_clone<T: std::clone::Clone>() {}
+                _assert_implements_clone::<#cls>();
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let buffer_impl = match &attr.buffer {
+        Some(buffer) => impl_shaped_buffer(cls, buffer),
+        None => quote! {},
+    };
+
+    // Check the `extends` type up front, so that a type which can't be a base class is reported at
+    // `extends = ...` rather than somewhere inside the generated trait impls.
+    let base_assertion = if attr.has_extends {
+        let base = &attr.base;
+        let assertion = quote_spanned! {base.span()=>
+            fn _assert_base_type<T: pyo3::class::impl_::PyClassBaseType>() {}
+            _assert_base_type::<#base>();
+        };
+        quote! {
+            const _: fn() = || {
+                #assertion
+            };
+        }
+    } else {
+        quote! {}
+    };
+
+    // The dict and weakref slots are placed by offsets which `PyCell` computes; check them against
+    // the compiler's layout of this class. Panicking in a const needs a newer Rust than the MSRV, so
+    // an invalid layout is rejected by an out of bounds index instead, in a const whose name rustc
+    // shows, spanned at the `dict` or `weakref` argument.
+    let slot_layout_assertion = match attr.slots_span {
+        Some(span) => quote_spanned! {span=>
+            const _: () = {
+                const DICT_AND_WEAKREF_SLOTS_MUST_NOT_OVERLAP: () =
+                    [()][!pyo3::PyCell::<#cls>::SLOT_LAYOUT_IS_VALID as usize];
+                DICT_AND_WEAKREF_SLOTS_MUST_NOT_OVERLAP
+            };
+        },
+        None => quote! {},
+    };
+
     let (impl_inventory, iter_py_methods) = match methods_type {
         PyClassMethodsType::Specialization => (None, quote! { collector.py_methods().iter() }),
         PyClassMethodsType::Inventory => (
@@ -387,9 +1738,48 @@ fn impl_class(
         quote! { pyo3::class::impl_::ThreadCheckerStub<#cls> }
     };
 
+    let frozen = if attr.has_frozen {
+        ensure_spanned!(
+            !attr.has_extends,
+            attr.base.span() => "`frozen` is not supported together with `extends`"
+        );
+        quote! { pyo3::pyclass::True }
+    } else {
+        quote! { pyo3::pyclass::False }
+    };
+    // A frozen class can't be mutably borrowed, so `&mut Self` can't be extracted either.
+    let extract_mut = if attr.has_frozen {
+        quote! {}
+    } else {
+        quote! {
+            impl<'a> pyo3::derive_utils::ExtractExt<'a> for &'a mut #cls
+            {
+                type Target = pyo3::PyRefMut<'a, #cls>;
+            }
+        }
+    };
+
     let is_gc = attr.is_gc;
     let is_basetype = attr.is_basetype;
     let is_subclass = attr.has_extends;
+    let is_sequence = attr.has_sequence;
+    let is_mapping = attr.has_mapping;
+    // An explicit `#[text_signature]` on the class, even `"()"`, replaces the generated one.
+    let get_new_text_signature = if has_text_signature {
+        quote! {}
+    } else {
+        quote! {
+            fn get_new_text_signature() -> Option<&'static str> {
+                use pyo3::class::impl_::*;
+                let collector = PyClassImplCollector::<Self>::new();
+                collector.new_text_signature()
+            }
+        }
+    };
+    let rename_all = match attr.rename_all {
+        Some(rule) => rule.runtime_variant(),
+        None => quote!(pyo3::class::impl_::RenameRule::None),
+    };
 
     Ok(quote! {
         unsafe impl pyo3::type_object::PyTypeInfo for #cls {
@@ -409,7 +1799,10 @@ fn impl_class(
         impl pyo3::PyClass for #cls {
             type Dict = #dict;
             type WeakRef = #weakref;
+            type HashCache = #hash_cache;
+            type EnterDepth = #enter_depth;
             type BaseNativeType = #base_nativetype;
+            type Frozen = #frozen;
         }
 
         impl<'a> pyo3::derive_utils::ExtractExt<'a> for &'a #cls
@@ -417,10 +1810,7 @@ fn impl_class(
             type Target = pyo3::PyRef<'a, #cls>;
         }
 
-        impl<'a> pyo3::derive_utils::ExtractExt<'a> for &'a mut #cls
-        {
-            type Target = pyo3::PyRefMut<'a, #cls>;
-        }
+        #extract_mut
 
         #into_pyobject
 
@@ -431,6 +1821,9 @@ fn impl_class(
             const IS_GC: bool = #is_gc;
             const IS_BASETYPE: bool = #is_basetype;
             const IS_SUBCLASS: bool = #is_subclass;
+            const IS_SEQUENCE: bool = #is_sequence;
+            const IS_MAPPING: bool = #is_mapping;
+            const RENAME_ALL: pyo3::class::impl_::RenameRule = #rename_all;
 
             type Layout = PyCell<Self>;
             type BaseType = #base;
@@ -441,6 +1834,7 @@ fn impl_class(
                 let collector = PyClassImplCollector::<Self>::new();
                 #iter_py_methods
                     .chain(collector.py_class_descriptors())
+                    .chain(collector.py_class_generated_methods())
                     .chain(collector.object_protocol_methods())
                     .chain(collector.async_protocol_methods())
                     .chain(collector.context_protocol_methods())
@@ -449,11 +1843,22 @@ fn impl_class(
                     .chain(collector.number_protocol_methods())
                     .for_each(visitor)
             }
+            fn for_each_metaclass_method_def(visitor: &mut dyn FnMut(&pyo3::class::PyMethodDefType)) {
+                use pyo3::class::impl_::*;
+                let collector = PyClassImplCollector::<Self>::new();
+                collector.py_class_metaclass_methods().iter().for_each(visitor)
+            }
+            fn for_each_metaclass_slot(visitor: &mut dyn FnMut(&pyo3::ffi::PyType_Slot)) {
+                use pyo3::class::impl_::*;
+                let collector = PyClassImplCollector::<Self>::new();
+                collector.py_class_metaclass_slots().iter().for_each(visitor)
+            }
             fn get_new() -> Option<pyo3::ffi::newfunc> {
                 use pyo3::class::impl_::*;
                 let collector = PyClassImplCollector::<Self>::new();
                 collector.new_impl()
             }
+            #get_new_text_signature
             fn get_call() -> Option<pyo3::ffi::PyCFunctionWithKeywords> {
                 use pyo3::class::impl_::*;
                 let collector = PyClassImplCollector::<Self>::new();
@@ -464,8 +1869,9 @@ fn impl_class(
                 // Implementation which uses dtolnay specialization to load all slots.
                 use pyo3::class::impl_::*;
                 let collector = PyClassImplCollector::<Self>::new();
-                collector.object_protocol_slots()
+                collector.py_class_generated_slots()
                     .iter()
+                    .chain(collector.object_protocol_slots())
                     .chain(collector.number_protocol_slots())
                     .chain(collector.iter_protocol_slots())
                     .chain(collector.gc_protocol_slots())
@@ -487,25 +1893,33 @@ fn impl_class(
         #extra
 
         #gc_impl
+
+        #clone_impl
+
+        #buffer_impl
+
+        #base_assertion
+
+        #slot_layout_assertion
     })
 }
 
 fn impl_descriptors(
     cls: &syn::Type,
-    descriptors: Vec<(syn::Field, Vec<FnType>)>,
+    descriptors: Vec<(syn::Field, FieldPyO3Options)>,
 ) -> syn::Result<TokenStream> {
     let py_methods: Vec<TokenStream> = descriptors
         .iter()
-        .flat_map(|(field, fns)| {
-            fns.iter()
+        .flat_map(|(field, options)| {
+            options.descs.iter()
                 .map(|desc| {
                     let doc = utils::get_doc(&field.attrs, None, true)
                         .unwrap_or_else(|_| syn::LitStr::new("", Span::call_site()));
-                    let property_type = PropertyType::Descriptor(
-                        field.ident.as_ref().ok_or_else(
-                            || err_spanned!(field.span() => "`#[pyo3(get, set)]` is not supported on tuple struct fields")
-                        )?
-                    );
+                    let ident = field.ident.as_ref().ok_or_else(
+                        || err_spanned!(field.span() => "`#[pyo3(get, set)]` is not supported on tuple struct fields")
+                    )?;
+                    let name = options.python_name(ident);
+                    let property_type = field_property(field, ident, &name, options);
                     match desc {
                         FnType::Getter(self_ty) => {
                             impl_py_getter_def(cls, property_type, self_ty, &doc)
@@ -531,3 +1945,2092 @@ fn impl_descriptors(
         }
     })
 }
+
+/// Generates the `to_dict` method of `#[pyclass(to_dict)]`.
+///
+/// The returned dict contains every field with a `#[pyo3(get)]` descriptor, keyed by its Python
+/// name, with the value returned by its getter. Fields without a getter are skipped.
+fn impl_to_dict(
+    cls: &syn::Type,
+    descriptors: &[(syn::Field, FieldPyO3Options)],
+) -> syn::Result<TokenStream> {
+    let items = readable_field_items(descriptors, "to_dict")?;
+
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    Ok(quote! {
+        pyo3::class::PyMethodDefType::Method({
+            pyo3::class::PyMethodDef::noargs(
+                "to_dict\0",
+                pyo3::class::methods::PyCFunction({
+                    unsafe extern "C" fn __wrap(
+                        _slf: *mut pyo3::ffi::PyObject,
+                        _args: *mut pyo3::ffi::PyObject,
+                    ) -> *mut pyo3::ffi::PyObject
+                    {
+                        pyo3::callback::handle_panic(|_py| {
+                            #slf
+                            let _dict = pyo3::types::PyDict::new(_py);
+                            #(#items)*
+                            pyo3::callback::convert(_py, _dict)
+                        })
+                    }
+                    __wrap
+                }),
+                "to_dict($self)\n--\n\nReturn a dict of the readable fields of this object.\0"
+            )
+        })
+    })
+}
+
+/// Generates the statements which insert every field with a `#[pyo3(get)]` descriptor into
+/// `_dict`, keyed by its Python name, for `to_dict` and `getnewargs_ex`.
+fn readable_field_items(
+    descriptors: &[(syn::Field, FieldPyO3Options)],
+    option: &str,
+) -> syn::Result<Vec<TokenStream>> {
+    let mut items = Vec::new();
+    for (field, options) in descriptors {
+        if !options.descs.iter().any(|desc| desc.is_getter()) {
+            continue;
+        }
+        let ident = field.ident.as_ref().ok_or_else(|| {
+            err_spanned!(field.span() => format!("`{}` is not supported on tuple struct fields", option))
+        })?;
+        let name = options.python_name(ident);
+        let value = field_object(field, ident, &name, options)?;
+        items.push(quote! {
+            _dict.set_item(#name, #value)?;
+        });
+    }
+    Ok(items)
+}
+
+/// Generates the `__match_args__` class attribute, a tuple of the Python names of the fields with a
+/// `#[pyo3(get)]` descriptor in declaration order, for positional patterns in `match` statements.
+///
+/// Fields marked `no_match_arg` are left out, and nothing is generated if no field remains. With
+/// `#[pyclass(match_args(...))]`, the tuple holds exactly the given names instead, which must be the
+/// Python names of distinct readable fields.
+fn impl_match_args(
+    descriptors: &[(syn::Field, FieldPyO3Options)],
+    explicit: Option<&[syn::Ident]>,
+) -> syn::Result<Option<TokenStream>> {
+    let readable: Vec<(String, &FieldPyO3Options)> = descriptors
+        .iter()
+        .filter(|(_, options)| options.descs.iter().any(|desc| desc.is_getter()))
+        .filter_map(|(field, options)| Some((options.python_name(field.ident.as_ref()?), options)))
+        .collect();
+    let names: Vec<String> = if let Some(explicit) = explicit {
+        let mut names = Vec::new();
+        for ident in explicit {
+            let name = ident.unraw().to_string();
+            let options = match readable.iter().find(|(readable, _)| *readable == name) {
+                Some((_, options)) => options,
+                None => bail_spanned!(
+                    ident.span() => format!("`match_args` refers to `{}`, which is not the Python name of a field with `#[pyo3(get)]`", name)
+                ),
+            };
+            ensure_spanned!(
+                !options.no_match_arg,
+                ident.span() => format!("`{}` is marked `no_match_arg`", name)
+            );
+            ensure_spanned!(
+                !names.contains(&name),
+                ident.span() => format!("`{}` is listed more than once in `match_args`", name)
+            );
+            names.push(name);
+        }
+        names
+    } else {
+        let names: Vec<String> = readable
+            .into_iter()
+            .filter(|(_, options)| !options.no_match_arg)
+            .map(|(name, _)| name)
+            .collect();
+        if names.is_empty() {
+            return Ok(None);
+        }
+        names
+    };
+    Ok(Some(quote! {
+        pyo3::class::PyMethodDefType::ClassAttribute({
+            pyo3::class::PyClassAttributeDef::new(
+                "__match_args__\0",
+                pyo3::class::methods::PyClassAttributeFactory({
+                    fn __wrap(py: pyo3::Python<'_>) -> pyo3::PyObject {
+                        pyo3::types::PyTuple::new(py, &[#(#names),*] as &[&str]).into()
+                    }
+                    __wrap
+                })
+            )
+        })
+    }))
+}
+
+/// Generates the `__getnewargs_ex__` method of `#[pyclass(getnewargs_ex)]`.
+///
+/// The positional arguments are always empty, and every field with a `#[pyo3(get)]` descriptor is
+/// passed as a keyword argument named after its Python name, so that `pickle` and `copy` can
+/// reconstruct the object with a `#[new]` taking keyword arguments.
+fn impl_getnewargs_ex(
+    cls: &syn::Type,
+    descriptors: &[(syn::Field, FieldPyO3Options)],
+) -> syn::Result<TokenStream> {
+    let items = readable_field_items(descriptors, "getnewargs_ex")?;
+
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    Ok(impl_generated_method_def(
+        cls,
+        "__getnewargs_ex__",
+        &[],
+        "Return the arguments to pass to __new__ when unpickling this object.",
+        quote! {
+            #slf
+            let _dict = pyo3::types::PyDict::new(_py);
+            #(#items)*
+            pyo3::callback::convert(_py, (pyo3::types::PyTuple::empty(_py), _dict))
+        },
+    ))
+}
+
+/// Generates the `__getstate__` and `__setstate__` methods of `#[pyclass(getstate)]`.
+///
+/// The state is a dict of every field which isn't `#[pyo3(transient)]`, keyed by its Python name.
+/// The values are cloned and converted with `IntoPy`, and extracted back with `FromPyObject`.
+/// `__setstate__` resets the transient fields to `Default::default()`. Conversion errors and a
+/// missing `Default` are reported at the type of the field.
+fn impl_getstate(
+    cls: &syn::Type,
+    fields: &syn::Fields,
+    descriptors: &[(syn::Field, FieldPyO3Options)],
+    transient: &[syn::Ident],
+) -> Vec<TokenStream> {
+    let mut items = Vec::new();
+    let mut restores = Vec::new();
+    for field in fields.iter() {
+        let ident = match &field.ident {
+            Some(ident) => ident,
+            None => continue,
+        };
+        let ty = &field.ty;
+        if transient.contains(ident) {
+            restores.push(quote_spanned! {ty.span()=>
+                _slf.#ident = <#ty as std::default::Default>::default();
+            });
+            continue;
+        }
+        let name = descriptors
+            .iter()
+            .find(|(desc_field, _)| desc_field.ident.as_ref() == Some(ident))
+            .map_or_else(
+                || ident.unraw().to_string(),
+                |(_, options)| options.python_name(ident),
+            );
+        items.push(quote_spanned! {ty.span()=>
+            let _value: &#ty = &_slf.#ident;
+            _dict.set_item(#name, pyo3::IntoPy::<pyo3::PyObject>::into_py(_value.clone(), _py))?;
+        });
+        restores.push(quote_spanned! {ty.span()=>
+            _slf.#ident = <#ty as pyo3::FromPyObject>::extract(
+                _state
+                    .get_item(#name)
+                    .ok_or_else(|| pyo3::exceptions::PyKeyError::new_err(#name))?,
+            )?;
+        });
+    }
+
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let slf_mut = SelfType::Receiver {
+        mutable: true,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    vec![
+        impl_generated_method_def(
+            cls,
+            "__getstate__",
+            &[],
+            "Return the fields of this object which are not transient, for pickling.",
+            quote! {
+                #slf
+                let _dict = pyo3::types::PyDict::new(_py);
+                #(#items)*
+                pyo3::callback::convert(_py, _dict)
+            },
+        ),
+        impl_generated_method_def(
+            cls,
+            "__setstate__",
+            &["state"],
+            "Restore the fields from a state returned by __getstate__, resetting the transient ones.",
+            quote! {
+                #slf_mut
+                let _state: &pyo3::types::PyDict = _output[0]
+                    .expect("state is a required argument")
+                    .downcast()?;
+                #(#restores)*
+                pyo3::callback::convert(_py, ())
+            },
+        ),
+    ]
+}
+
+/// Generates the `__deepcopy__` method of `#[pyclass(deepcopy)]`.
+///
+/// Fields of type `Py<T>` or `PyObject` are replaced by the result of `copy.deepcopy(field, memo)`;
+/// all other fields are cloned. Reference cycles back to this object resolve to the copy, see
+/// [`impl_deepcopy_method`].
+fn impl_deepcopy(cls: &syn::Type, fields: &syn::Fields, frozen: bool) -> TokenStream {
+    let members = field_members(fields);
+    let inits = fields.iter().zip(&members).map(|(field, member)| {
+        if utils::is_py_object(&field.ty) {
+            quote! { #member: _slf.#member.clone_ref(_py) }
+        } else {
+            quote! { #member: _slf.#member.clone() }
+        }
+    });
+    impl_deepcopy_method(cls, fields, quote! { #cls { #(#inits),* } }, frozen)
+}
+
+/// Generates the `__copy__` and `__deepcopy__` methods of `#[pyclass(copy)]`, which clone the whole
+/// struct with its `Clone` implementation.
+///
+/// `__copy__` returns the clone, so `Py<T>` fields share their objects with the original.
+/// `__deepcopy__` then replaces each `Py<T>` or `PyObject` field of the clone with a deep copy, as
+/// `#[pyclass(deepcopy)]` does.
+fn impl_copy(cls: &syn::Type, fields: &syn::Fields, frozen: bool) -> Vec<TokenStream> {
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    vec![
+        impl_generated_method_def(
+            cls,
+            "__copy__",
+            &[],
+            "Return a shallow copy of this object.",
+            quote! {
+                #slf
+                let _new = pyo3::Py::new(_py, std::clone::Clone::clone(&**_slf))?;
+                pyo3::callback::convert(_py, _new)
+            },
+        ),
+        impl_deepcopy_method(
+            cls,
+            fields,
+            quote! { std::clone::Clone::clone(&**_slf) },
+            frozen,
+        ),
+    ]
+}
+
+/// Generates the `__copy__` method of `#[pyclass(frozen, clone)]`, which returns the object itself.
+///
+/// This is sound because the object can't change: a `frozen` class has no setters and can't be
+/// borrowed mutably, so every holder of the object sees the same value as a copy would have, and
+/// only `is` tells them apart. `copy.copy` treats `tuple` and `frozenset` the same way. Interior
+/// mutability, e.g. a `Cell` or `Mutex` field, would defeat this, so it must not be used.
+fn impl_identity_copy(cls: &syn::Type) -> TokenStream {
+    impl_generated_method_def(
+        cls,
+        "__copy__",
+        &[],
+        "Return this object, which is immutable.",
+        quote! {
+            pyo3::ffi::Py_INCREF(_slf);
+            Ok(_slf)
+        },
+    )
+}
+
+/// The members of `fields`, by name or, for a tuple struct, by index.
+fn field_members(fields: &syn::Fields) -> Vec<syn::Member> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(index.into()),
+        })
+        .collect()
+}
+
+/// Generates a `__deepcopy__(memo)` method which creates the copy from `init` and deep-copies its
+/// `Py<T>` and `PyObject` fields.
+///
+/// The copy of a mutable class is registered in `memo` before its fields are copied, and then
+/// updated. A `frozen` class can't be updated after `Py::new`, so its fields are copied first, and,
+/// as `copy.deepcopy` does for a tuple, a copy made meanwhile by a reference cycle back to this
+/// object is returned instead of a new one.
+fn impl_deepcopy_method(
+    cls: &syn::Type,
+    fields: &syn::Fields,
+    init: TokenStream,
+    frozen: bool,
+) -> TokenStream {
+    let members: Vec<_> = field_members(fields)
+        .into_iter()
+        .zip(fields.iter())
+        .filter(|(_, field)| utils::is_py_object(&field.ty))
+        .map(|(member, _)| member)
+        .collect();
+
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let body = if frozen {
+        let value = if members.is_empty() {
+            quote! { let _value = #init; }
+        } else {
+            quote! { let mut _value = #init; }
+        };
+        quote! {
+            #value
+            #(
+                _value.#members = _deepcopy
+                    .call1((_slf.#members.clone_ref(_py), _memo))?
+                    .extract()?;
+            )*
+            let _copied = _memo.call_method1("get", (_id,))?;
+            if !_copied.is_none() {
+                return pyo3::callback::convert(_py, _copied);
+            }
+            let _new = pyo3::Py::new(_py, _value)?;
+            _memo.set_item(_id, &_new)?;
+        }
+    } else {
+        quote! {
+            let _new = pyo3::Py::new(_py, #init)?;
+            _memo.set_item(_id, &_new)?;
+            #(
+                let _value = _deepcopy.call1((_slf.#members.clone_ref(_py), _memo))?;
+                _new.borrow_mut(_py).#members = _value.extract()?;
+            )*
+        }
+    };
+    impl_generated_method_def(
+        cls,
+        "__deepcopy__",
+        &["memo"],
+        "Return a deep copy of this object.",
+        quote! {
+            #slf
+            let _memo = _output[0].expect("memo is a required argument");
+            let _deepcopy = _py.import("copy")?.getattr("deepcopy")?;
+            let _id = pyo3::AsPyPointer::as_ptr(_cell) as usize;
+            #body
+            pyo3::callback::convert(_py, _new)
+        },
+    )
+}
+
+/// Implements the buffer protocol for `#[pyclass(buffer(data = ..., shape = ...))]`, exporting the
+/// items of the `data` field as a read-only array with the dimensions in the `shape` field.
+///
+/// Both fields are sliced, so `data` can be e.g. a `Vec<T>`, `Box<[T]>` or `[T; N]` of a numeric
+/// type, and `shape` e.g. a `Vec<usize>` or `[usize; N]`. They are spanned at the field names, so
+/// that a field which can't be exported is reported there.
+fn impl_shaped_buffer(cls: &syn::Ident, buffer: &BufferFields) -> TokenStream {
+    let BufferFields { data, shape } = buffer;
+    let data = quote_spanned! {data.span()=> &self.#data[..] };
+    let shape = quote_spanned! {shape.span()=> &self.#shape[..] };
+    quote! {
+        impl pyo3::class::impl_::PyClassShapedBuffer for #cls {
+            unsafe fn fill_buffer(
+                &self,
+                view: *mut pyo3::ffi::Py_buffer,
+                flags: std::os::raw::c_int,
+            ) -> pyo3::PyResult<()> {
+                pyo3::class::impl_::fill_shaped_buffer(#data, #shape, view, flags)
+            }
+        }
+
+        impl pyo3::class::impl_::PyBufferProtocolProcs<#cls>
+            for pyo3::class::impl_::PyClassImplCollector<#cls>
+        {
+            fn buffer_procs(self) -> Option<&'static pyo3::class::impl_::PyBufferProcs> {
+                static PROCS: pyo3::class::impl_::PyBufferProcs =
+                    pyo3::class::impl_::PyBufferProcs {
+                        bf_getbuffer: Some(pyo3::class::impl_::shaped_getbuffer::<#cls>),
+                        bf_releasebuffer: Some(pyo3::class::impl_::shaped_releasebuffer::<#cls>),
+                    };
+                Some(&PROCS)
+            }
+        }
+    }
+}
+
+/// Generates the `bf_getbuffer` and `bf_releasebuffer` slots of `#[pyclass(buffer(...))]`, which
+/// are set from the type spec on Python 3.9 and later, and from the `PyBufferProcs` of
+/// `impl_shaped_buffer` on earlier versions.
+fn impl_shaped_buffer_slots(cls: &syn::Type) -> Vec<GeneratedSlot> {
+    let getbuffer = syn::Ident::new("__pyo3_bf_getbuffer", Span::call_site());
+    let releasebuffer = syn::Ident::new("__pyo3_bf_releasebuffer", Span::call_site());
+    vec![
+        GeneratedSlot {
+            slot: quote! { pyo3::ffi::Py_bf_getbuffer },
+            name: getbuffer.clone(),
+            func: quote! {
+                unsafe extern "C" fn #getbuffer(
+                    _slf: *mut pyo3::ffi::PyObject,
+                    _view: *mut pyo3::ffi::Py_buffer,
+                    _flags: std::os::raw::c_int,
+                ) -> std::os::raw::c_int {
+                    pyo3::class::impl_::shaped_getbuffer::<#cls>(_slf, _view, _flags)
+                }
+            },
+        },
+        GeneratedSlot {
+            slot: quote! { pyo3::ffi::Py_bf_releasebuffer },
+            name: releasebuffer.clone(),
+            func: quote! {
+                unsafe extern "C" fn #releasebuffer(
+                    _slf: *mut pyo3::ffi::PyObject,
+                    _view: *mut pyo3::ffi::Py_buffer,
+                ) {
+                    pyo3::class::impl_::shaped_releasebuffer::<#cls>(_slf, _view)
+                }
+            },
+        },
+    ]
+}
+
+/// Generates the `__set_name__` method of `#[pyclass(set_name = ..., set_owner = ...)]`.
+///
+/// Python calls `__set_name__(owner, name)` when an instance is assigned as an attribute in a
+/// class body. The name and the owning class are extracted into the given fields.
+fn impl_set_name(cls: &syn::Type, attr: &PyClassArgs) -> TokenStream {
+    let name = attr.set_name.as_ref().map(|field| {
+        quote! {
+            _slf.#field = _output[1].expect("name is a required argument").extract()?;
+        }
+    });
+    let owner = attr.set_owner.as_ref().map(|field| {
+        quote! {
+            _slf.#field = _output[0].expect("owner is a required argument").extract()?;
+        }
+    });
+    let slf = SelfType::Receiver {
+        mutable: true,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    impl_generated_method_def(
+        cls,
+        "__set_name__",
+        &["owner", "name"],
+        "Store the owning class and attribute name of this descriptor.",
+        quote! {
+            #slf
+            #name
+            #owner
+            pyo3::callback::convert(_py, ())
+        },
+    )
+}
+
+/// Generates the `__bytes__` method of `#[pyclass(bytes = method)]`.
+///
+/// `method` takes `&self` and returns `Vec<u8>` or `PyResult<Vec<u8>>`.
+fn impl_bytes(cls: &syn::Type, method: &syn::Ident) -> TokenStream {
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    impl_generated_method_def(
+        cls,
+        "__bytes__",
+        &[],
+        "Return the bytes representation of this object.",
+        quote! {
+            #slf
+            let _bytes = pyo3::class::impl_::PyClassBytesResult::into_bytes_result(
+                #cls::#method(_slf)
+            )?;
+            pyo3::callback::convert(_py, pyo3::types::PyBytes::new(_py, &_bytes))
+        },
+    )
+}
+
+/// Generates the `__array__` method of `#[pyclass(array = method)]`.
+///
+/// `method` takes `&self` and returns a value which `numpy.asarray` accepts, or a `PyResult` of
+/// one; see `pyo3::class::impl_::to_ndarray`.
+fn impl_array(cls: &syn::Type, method: &syn::Ident) -> TokenStream {
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    impl_generated_method_def_with_optional(
+        cls,
+        "__array__",
+        &[],
+        &["dtype", "copy"],
+        "Return a numpy array of this object.",
+        quote! {
+            #slf
+            let _data: pyo3::PyObject = pyo3::callback::convert(_py, #cls::#method(_slf))?;
+            pyo3::callback::convert(
+                _py,
+                pyo3::class::impl_::to_ndarray(_py, _data, _output[0], _output[1])?,
+            )
+        },
+    )
+}
+
+/// Generates the `__enter__` and `__exit__` methods of `#[pyclass(enter = ..., exit = ...)]`.
+///
+/// `__enter__` returns the resource created by `fn enter(&mut self, py: Python) -> R`, which
+/// may differ from the object itself. `__exit__` calls `fn exit(&mut self, py: Python)`, if
+/// given, and never suppresses exceptions. With `reentrant`, only the outermost `with` block calls
+/// them, see `pyo3::class::impl_::reentrant_enter`.
+fn impl_context_manager(
+    cls: &syn::Type,
+    enter: &syn::Ident,
+    exit: Option<&syn::Ident>,
+    reentrant: bool,
+) -> Vec<TokenStream> {
+    let slf = SelfType::Receiver {
+        mutable: true,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let acquire = quote! { pyo3::callback::convert(_py, #cls::#enter(_slf, _py)) };
+    let release = exit.map(|exit| {
+        quote! {
+            let _: () = pyo3::callback::convert(_py, #cls::#exit(_slf, _py))?;
+        }
+    });
+    let (enter_body, exit_body) = if reentrant {
+        (
+            quote! {
+                pyo3::callback::convert(
+                    _py,
+                    pyo3::class::impl_::reentrant_enter(_cell, || #acquire)?,
+                )
+            },
+            quote! {
+                pyo3::class::impl_::reentrant_exit(_cell, || {
+                    #release
+                    Ok(())
+                })?;
+            },
+        )
+    } else {
+        (acquire, quote! { #release })
+    };
+    vec![
+        impl_generated_method_def(
+            cls,
+            "__enter__",
+            &[],
+            "Acquire the resource managed by this object.",
+            quote! {
+                #slf
+                #enter_body
+            },
+        ),
+        impl_generated_method_def(
+            cls,
+            "__exit__",
+            &["exc_type", "exc_value", "traceback"],
+            "Release the resource managed by this object.",
+            quote! {
+                #slf
+                #exit_body
+                pyo3::callback::convert(_py, false)
+            },
+        ),
+    ]
+}
+
+/// Generates the `__instancecheck__` and `__subclasscheck__` metaclass methods of
+/// `#[pyclass(instancecheck = ..., subclasscheck = ...)]`.
+///
+/// Real instances and subclasses are always accepted. Otherwise the user's check function decides,
+/// but only for the class itself: its subclasses keep the default behaviour.
+fn impl_metaclass_checks(cls: &syn::Type, attr: &PyClassArgs) -> Vec<TokenStream> {
+    let mut methods = Vec::new();
+    let is_this_class = quote! {
+        _slf as *mut pyo3::ffi::PyTypeObject
+            == <#cls as pyo3::type_object::PyTypeInfo>::type_object_raw(_py)
+    };
+    if let Some(check) = &attr.instancecheck {
+        methods.push(impl_generated_method_def(
+            cls,
+            "__instancecheck__",
+            &["instance"],
+            "Check if an object is an instance of this class.",
+            quote! {
+                let _instance = _output[0].expect("instance is a required argument");
+                let _result = pyo3::ffi::PyType_IsSubtype(
+                    pyo3::ffi::Py_TYPE(pyo3::AsPyPointer::as_ptr(_instance)),
+                    _slf as *mut pyo3::ffi::PyTypeObject,
+                ) != 0
+                    || (#is_this_class && #check(_instance)?);
+                pyo3::callback::convert(_py, _result)
+            },
+        ));
+    }
+    if let Some(check) = &attr.subclasscheck {
+        methods.push(impl_generated_method_def(
+            cls,
+            "__subclasscheck__",
+            &["subclass"],
+            "Check if a class is a subclass of this class.",
+            quote! {
+                let _subclass = _output[0]
+                    .expect("subclass is a required argument")
+                    .downcast::<pyo3::types::PyType>()?;
+                let _result = pyo3::ffi::PyType_IsSubtype(
+                    _subclass.as_type_ptr(),
+                    _slf as *mut pyo3::ffi::PyTypeObject,
+                ) != 0
+                    || (#is_this_class && #check(_subclass)?);
+                pyo3::callback::convert(_py, _result)
+            },
+        ));
+    }
+    methods
+}
+
+/// Generates a `PyMethodDef` for a method generated by a `#[pyclass]` option.
+///
+/// `body` runs inside `handle_panic` with `_py`, the raw `_slf` pointer and `_output`, which holds
+/// the required `params` in order.
+fn impl_generated_method_def(
+    cls: &syn::Type,
+    python_name: &str,
+    params: &[&str],
+    doc: &str,
+    body: TokenStream,
+) -> TokenStream {
+    impl_generated_method_def_with_optional(cls, python_name, params, &[], doc, body)
+}
+
+/// Like `impl_generated_method_def`, with `optional_params` after the required `params` which
+/// default to `None`. Their `_output` entries are `None` when not passed.
+fn impl_generated_method_def_with_optional(
+    cls: &syn::Type,
+    python_name: &str,
+    params: &[&str],
+    optional_params: &[&str],
+    doc: &str,
+    body: TokenStream,
+) -> TokenStream {
+    let name = format!("{}\0", python_name);
+    let doc = format!(
+        "{}($self{}{})\n--\n\n{}\0",
+        python_name,
+        params
+            .iter()
+            .map(|param| format!(", {}", param))
+            .collect::<String>(),
+        optional_params
+            .iter()
+            .map(|param| format!(", {}=None", param))
+            .collect::<String>(),
+        doc
+    );
+    let num_required = params.len();
+    let num_params = params.len() + optional_params.len();
+    let params = params.iter().chain(optional_params);
+    quote! {
+        pyo3::class::PyMethodDefType::Method({
+            pyo3::class::PyMethodDef::cfunction_with_keywords(
+                #name,
+                pyo3::class::methods::PyCFunctionWithKeywords({
+                    unsafe extern "C" fn __wrap(
+                        _slf: *mut pyo3::ffi::PyObject,
+                        _args: *mut pyo3::ffi::PyObject,
+                        _kwargs: *mut pyo3::ffi::PyObject,
+                    ) -> *mut pyo3::ffi::PyObject
+                    {
+                        pyo3::callback::handle_panic(|_py| {
+                            let _args = _py.from_borrowed_ptr::<pyo3::types::PyTuple>(_args);
+                            let _kwargs: Option<&pyo3::types::PyDict> =
+                                _py.from_borrowed_ptr_or_opt(_kwargs);
+                            const DESCRIPTION: pyo3::derive_utils::FunctionDescription =
+                                pyo3::derive_utils::FunctionDescription {
+                                    cls_name: Some(<#cls as pyo3::type_object::PyTypeInfo>::NAME),
+                                    func_name: #python_name,
+                                    positional_parameter_names: &[#(#params),*],
+                                    positional_only_parameters: 0,
+                                    required_positional_parameters: #num_required,
+                                    keyword_only_parameters: &[],
+                                    accept_varargs: false,
+                                    accept_varkeywords: false,
+                                };
+                            let mut _output = [None; #num_params];
+                            DESCRIPTION.extract_arguments(_args, _kwargs, &mut _output)?;
+                            #body
+                        })
+                    }
+                    __wrap
+                }),
+                #doc
+            )
+        })
+    }
+}
+
+/// Generates the `__new__` of `#[pyclass(positional_new)]`.
+///
+/// The parameters are the fields with a `#[pyo3(set)]` descriptor in declaration order, all of
+/// them required and accepted either positionally or by keyword. A `frozen` class has no setters,
+/// so its parameters are the fields with a `#[pyo3(get)]` descriptor instead. The remaining fields
+/// are initialized with `Default::default()`.
+///
+/// With `lenient`, as for `#[pyclass(lenient_new)]`, the constructor also accepts `**kwargs` and
+/// ignores any keyword arguments that don't name a parameter.
+///
+/// With `intern`, as for `#[pyclass(frozen, intern = N)]`, up to `N` instances are cached keyed by
+/// their arguments, and constructing with arguments of the same types which compare equal returns
+/// the cached instance. The type is part of the key so that e.g. `True` doesn't find the instance
+/// created for `1`.
+fn impl_positional_new(
+    cls: &syn::Type,
+    fields: &syn::Fields,
+    descriptors: &[(syn::Field, FieldPyO3Options)],
+    lenient: bool,
+    frozen: bool,
+    intern: Option<&syn::Expr>,
+) -> syn::Result<TokenStream> {
+    if let syn::Fields::Unnamed(_) = fields {
+        let option = if lenient {
+            "lenient_new"
+        } else {
+            "positional_new"
+        };
+        bail_spanned!(fields.span() => format!("`{}` is not supported on tuple structs", option));
+    }
+    let param_fields: Vec<_> = descriptors
+        .iter()
+        .filter(|(_, options)| {
+            options.descs.iter().any(|desc| {
+                if frozen {
+                    desc.is_getter()
+                } else {
+                    desc.is_setter()
+                }
+            })
+        })
+        .filter_map(|(field, options)| field.ident.as_ref().map(|ident| (field, ident, options)))
+        .collect();
+    let params: Vec<&syn::Ident> = param_fields.iter().map(|(_, ident, _)| *ident).collect();
+    let param_names: Vec<_> = param_fields
+        .iter()
+        .map(|(_, ident, options)| options.python_name(ident))
+        .collect();
+    let num_params = params.len();
+
+    let inits = fields.iter().map(|field| {
+        let ident = field
+            .ident
+            .as_ref()
+            .expect("tuple structs are rejected above");
+        match params.iter().position(|param| *param == ident) {
+            Some(index) => {
+                let name = &param_names[index];
+                let (field, _, options) = param_fields[index];
+                // Accepts the same values as the setter.
+                let extract = crate::pymethod::setter_value(
+                    &field_property(field, ident, name, options),
+                    false,
+                );
+                quote! {
+                    #ident: {
+                        let _value = _output[#index].expect("all parameters are required");
+                        pyo3::class::impl_::extract_field_argument(_py, #name, || Ok(#extract))?
+                    }
+                }
+            }
+            None => quote! { #ident: Default::default() },
+        }
+    });
+
+    let (intern_lookup, intern_insert) = match intern {
+        Some(capacity) => (
+            quote! {
+                static INSTANCES: pyo3::class::impl_::InternedInstances =
+                    pyo3::class::impl_::InternedInstances::new();
+                let _key = if subtype == <#cls as pyo3::type_object::PyTypeInfo>::type_object_raw(_py) {
+                    let _key = pyo3::types::PyTuple::new(
+                        _py,
+                        _output.iter().map(|arg| {
+                            let arg = arg.expect("all parameters are required");
+                            pyo3::ToPyObject::to_object(&(arg.get_type(), arg), _py)
+                        }),
+                    );
+                    if let Some(instance) = INSTANCES.get(_py, _key)? {
+                        return Ok(pyo3::IntoPyPointer::into_ptr(instance));
+                    }
+                    Some(_key)
+                } else {
+                    None
+                };
+            },
+            quote! {
+                if let Some(_key) = _key {
+                    let _instance = _py.from_borrowed_ptr::<pyo3::PyAny>(cell as *mut pyo3::ffi::PyObject);
+                    INSTANCES.insert(_py, _key, _instance, #capacity)?;
+                }
+            },
+        ),
+        None => (quote!(), quote!()),
+    };
+
+    let mut signature_params = param_names.clone();
+    if lenient {
+        signature_params.push("**kwargs".to_string());
+    }
+    let text_signature = format!("({})", signature_params.join(", "));
+
+    Ok(quote! {
+        impl pyo3::class::impl_::PyClassNewImpl<#cls> for pyo3::class::impl_::PyClassImplCollector<#cls> {
+            fn new_text_signature(self) -> Option<&'static str> {
+                Some(#text_signature)
+            }
+
+            fn new_impl(self) -> Option<pyo3::ffi::newfunc> {
+                unsafe extern "C" fn __wrap(
+                    subtype: *mut pyo3::ffi::PyTypeObject,
+                    _args: *mut pyo3::ffi::PyObject,
+                    _kwargs: *mut pyo3::ffi::PyObject,
+                ) -> *mut pyo3::ffi::PyObject
+                {
+                    pyo3::callback::handle_panic(|_py| {
+                        let _args = _py.from_borrowed_ptr::<pyo3::types::PyTuple>(_args);
+                        let _kwargs: Option<&pyo3::types::PyDict> =
+                            _py.from_borrowed_ptr_or_opt(_kwargs);
+                        const DESCRIPTION: pyo3::derive_utils::FunctionDescription =
+                            pyo3::derive_utils::FunctionDescription {
+                                cls_name: Some(<#cls as pyo3::type_object::PyTypeInfo>::NAME),
+                                func_name: "__new__",
+                                positional_parameter_names: &[#(#param_names),*],
+                                positional_only_parameters: 0,
+                                required_positional_parameters: #num_params,
+                                keyword_only_parameters: &[],
+                                accept_varargs: false,
+                                accept_varkeywords: #lenient,
+                            };
+                        let mut _output = [None; #num_params];
+                        // With `lenient_new`, unknown keyword arguments are collected and dropped.
+                        DESCRIPTION.extract_arguments(_args, _kwargs, &mut _output)?;
+                        #intern_lookup
+
+                        let initializer: pyo3::PyClassInitializer<#cls> = #cls { #(#inits),* }.into();
+                        let cell = initializer.create_cell_from_subtype(_py, subtype)?;
+                        #intern_insert
+                        Ok(cell as *mut pyo3::ffi::PyObject)
+                    })
+                }
+                Some(__wrap)
+            }
+        }
+    })
+}
+
+/// Generates a `match` on `_slf` evaluating to the discriminant of the enum variant as an `isize`.
+fn enum_discriminant(cls: &syn::Type, variants: &[syn::Ident]) -> TokenStream {
+    quote! {
+        match &**_slf {
+            #(#cls::#variants => #cls::#variants as isize,)*
+        }
+    }
+}
+
+/// Generates a `match` on `_slf` evaluating to the Python name of the enum variant as a `&str`.
+fn enum_variant_name(cls: &syn::Type, variants: &[syn::Ident]) -> TokenStream {
+    let names = variants.iter().map(|variant| variant.unraw().to_string());
+    quote! {
+        match &**_slf {
+            #(#cls::#variants => #names,)*
+        }
+    }
+}
+
+/// Generates the class attributes, the `value` getter and `__reduce__` of a `#[pyclass]` enum.
+///
+/// Each variant becomes a class attribute named after it. Class attributes are created once, when
+/// the type object is initialized, so `Enum.Variant` always returns the same instance. A variant
+/// pickles as `getattr(Enum, "Variant")`, so unpickling also returns that instance.
+fn impl_enum_methods(cls: &syn::Type, variants: &[syn::Ident]) -> Vec<TokenStream> {
+    let mut methods: Vec<_> = variants
+        .iter()
+        .map(|variant| {
+            let name = format!("{}\0", variant.unraw());
+            quote! {
+                pyo3::class::PyMethodDefType::ClassAttribute({
+                    pyo3::class::PyClassAttributeDef::new(
+                        #name,
+                        pyo3::class::methods::PyClassAttributeFactory({
+                            fn __wrap(py: pyo3::Python<'_>) -> pyo3::PyObject {
+                                pyo3::IntoPy::into_py(#cls::#variant, py)
+                            }
+                            __wrap
+                        })
+                    )
+                })
+            }
+        })
+        .collect();
+
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let discriminant = enum_discriminant(cls, variants);
+    methods.push(quote! {
+        pyo3::class::PyMethodDefType::Getter({
+            pyo3::class::PyGetterDef::new(
+                "value\0",
+                pyo3::class::methods::PyGetter({
+                    unsafe extern "C" fn __wrap(
+                        _slf: *mut pyo3::ffi::PyObject,
+                        _: *mut std::os::raw::c_void,
+                    ) -> *mut pyo3::ffi::PyObject {
+                        pyo3::callback::handle_panic(|_py| {
+                            #slf
+                            pyo3::callback::convert(_py, #discriminant)
+                        })
+                    }
+                    __wrap
+                }),
+                "The discriminant of this variant.\0"
+            )
+        })
+    });
+    let variant_name = enum_variant_name(cls, variants);
+    methods.push(impl_generated_method_def(
+        cls,
+        "__reduce__",
+        &[],
+        "Return `getattr` and the class and name of this variant, for pickling.",
+        quote! {
+            #slf
+            let _getattr = _py.import("builtins")?.getattr("getattr")?;
+            pyo3::callback::convert(_py, (_getattr, (_cell.get_type(), #variant_name)))
+        },
+    ));
+    methods
+}
+
+/// Generates `__format__` of `#[pyclass(format)]` on an enum.
+///
+/// The spec `upper` or `lower` formats the name of the variant in that case, and an empty spec gives
+/// `str(variant)`, as usual. Any other spec raises `ValueError`.
+fn impl_enum_format(cls: &syn::Type, variants: &[syn::Ident]) -> TokenStream {
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let variant_name = enum_variant_name(cls, variants);
+    impl_generated_method_def(
+        cls,
+        "__format__",
+        &["format_spec"],
+        "Format the name of this variant, in upper case for `upper` or lower case for `lower`.",
+        quote! {
+            let _spec = _output[0]
+                .expect("format_spec is a required argument")
+                .extract::<&str>()?;
+            let _formatted = {
+                #slf
+                let _name = #variant_name;
+                match _spec {
+                    "" => None,
+                    "upper" => Some(_name.to_uppercase()),
+                    "lower" => Some(_name.to_lowercase()),
+                    _ => {
+                        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                            "unknown format spec '{}' for {}, expected 'upper' or 'lower'",
+                            _spec,
+                            _cell.get_type().name()?,
+                        )))
+                    }
+                }
+            };
+            match _formatted {
+                Some(_formatted) => pyo3::callback::convert(_py, _formatted),
+                None => {
+                    let _any = _py.from_borrowed_ptr::<pyo3::PyAny>(_slf);
+                    pyo3::callback::convert(_py, _any.str()?)
+                }
+            }
+        },
+    )
+}
+
+/// Generates the slots of a `#[pyclass]` enum.
+///
+/// Variants compare equal with `==` if they have the same discriminant, and hash like their
+/// discriminant. `int(variant)` returns the discriminant, and the repr is `Enum.Variant`. Variants
+/// are truthy, except for the one marked `#[pyo3(falsy)]`.
+fn impl_enum_slots(cls: &syn::Type, enum_variants: &EnumVariants) -> Vec<GeneratedSlot> {
+    let variants = &enum_variants.idents;
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let discriminant = enum_discriminant(cls, variants);
+    let variant_name = enum_variant_name(cls, variants);
+
+    let richcmp = syn::Ident::new("__pyo3_richcmp", Span::call_site());
+    let richcmp_func = quote! {
+        unsafe extern "C" fn #richcmp(
+            _slf: *mut pyo3::ffi::PyObject,
+            _other: *mut pyo3::ffi::PyObject,
+            _op: std::os::raw::c_int,
+        ) -> *mut pyo3::ffi::PyObject
+        {
+            pyo3::callback::handle_panic(|_py| {
+                let _other = _py.from_borrowed_ptr::<pyo3::PyAny>(_other);
+                let _other = match _other.downcast::<pyo3::PyCell<#cls>>() {
+                    Ok(_other) if _op == pyo3::ffi::Py_EQ || _op == pyo3::ffi::Py_NE => _other,
+                    _ => return pyo3::callback::convert(_py, _py.NotImplemented()),
+                };
+                let _value = {
+                    #slf
+                    #discriminant
+                };
+                let _other_value = {
+                    let _slf = &_other.try_borrow()?;
+                    #discriminant
+                };
+                pyo3::callback::convert(_py, (_value == _other_value) == (_op == pyo3::ffi::Py_EQ))
+            })
+        }
+    };
+    let int = syn::Ident::new("__pyo3_nb_int", Span::call_site());
+    let int_func = quote! {
+        unsafe extern "C" fn #int(_slf: *mut pyo3::ffi::PyObject) -> *mut pyo3::ffi::PyObject {
+            pyo3::callback::handle_panic(|_py| {
+                #slf
+                pyo3::callback::convert(_py, #discriminant)
+            })
+        }
+    };
+    let repr = syn::Ident::new("__pyo3_tp_repr", Span::call_site());
+    let repr_func = quote! {
+        unsafe extern "C" fn #repr(_slf: *mut pyo3::ffi::PyObject) -> *mut pyo3::ffi::PyObject {
+            pyo3::callback::handle_panic(|_py| {
+                #slf
+                let _variant = #variant_name;
+                pyo3::callback::convert(_py, format!("{}.{}", _cell.get_type().name()?, _variant))
+            })
+        }
+    };
+    let mut slots = vec![
+        GeneratedSlot {
+            slot: quote! { pyo3::ffi::Py_tp_richcompare },
+            name: richcmp,
+            func: richcmp_func,
+        },
+        hash_slot(
+            cls,
+            quote! {
+                let _hash = #discriminant as pyo3::ffi::Py_hash_t;
+                // -1 signals an error to Python.
+                Ok(if _hash == -1 { -2 } else { _hash })
+            },
+        ),
+        GeneratedSlot {
+            slot: quote! { pyo3::ffi::Py_nb_int },
+            name: int,
+            func: int_func,
+        },
+        GeneratedSlot {
+            slot: quote! { pyo3::ffi::Py_tp_repr },
+            name: repr,
+            func: repr_func,
+        },
+    ];
+    if let Some(falsy) = &enum_variants.falsy {
+        let bool_ = syn::Ident::new("__pyo3_nb_bool", Span::call_site());
+        let bool_func = quote! {
+            unsafe extern "C" fn #bool_(_slf: *mut pyo3::ffi::PyObject) -> std::os::raw::c_int {
+                pyo3::callback::handle_panic(|_py| {
+                    #slf
+                    pyo3::callback::convert(_py, #discriminant != #cls::#falsy as isize)
+                })
+            }
+        };
+        slots.push(GeneratedSlot {
+            slot: quote! { pyo3::ffi::Py_nb_bool },
+            name: bool_,
+            func: bool_func,
+        });
+    }
+    slots
+}
+
+/// Generates the `sq_contains` slot of the metaclass of a `#[pyclass]` enum.
+///
+/// `x in Enum` is true if `x` is an instance of the enum, or an `int` equal to the discriminant of
+/// one of its variants. Any other object is not a member, rather than raising `TypeError`.
+fn impl_enum_contains(cls: &syn::Type, variants: &[syn::Ident]) -> GeneratedSlot {
+    let name = syn::Ident::new("__pyo3_meta_sq_contains", Span::call_site());
+    let func = quote! {
+        unsafe extern "C" fn #name(
+            _slf: *mut pyo3::ffi::PyObject,
+            _item: *mut pyo3::ffi::PyObject,
+        ) -> std::os::raw::c_int {
+            pyo3::callback::handle_panic(|_py| {
+                let _item = _py.from_borrowed_ptr::<pyo3::PyAny>(_item);
+                let _contains = if _item.downcast::<pyo3::PyCell<#cls>>().is_ok() {
+                    true
+                } else if pyo3::ffi::PyLong_Check(pyo3::AsPyPointer::as_ptr(_item)) != 0 {
+                    // An `int` too large for `isize` is no discriminant.
+                    match _item.extract::<isize>() {
+                        Ok(_value) => [#(#cls::#variants as isize),*].contains(&_value),
+                        Err(_) => false,
+                    }
+                } else {
+                    false
+                };
+                pyo3::callback::convert(_py, _contains)
+            })
+        }
+    };
+    GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_sq_contains },
+        name,
+        func,
+    }
+}
+
+/// Generates the `mp_subscript` slot of the metaclass of a `#[pyclass]` enum.
+///
+/// `Enum["Variant"]` returns the class attribute of the variant with that Python name. The lookup
+/// is case-sensitive, and any other key raises `KeyError`.
+fn impl_enum_getitem(variants: &[syn::Ident]) -> GeneratedSlot {
+    let names = variants.iter().map(|variant| variant.unraw().to_string());
+    let name = syn::Ident::new("__pyo3_meta_mp_subscript", Span::call_site());
+    let func = quote! {
+        unsafe extern "C" fn #name(
+            _slf: *mut pyo3::ffi::PyObject,
+            _key: *mut pyo3::ffi::PyObject,
+        ) -> *mut pyo3::ffi::PyObject {
+            pyo3::callback::handle_panic(|_py| {
+                let _key = _py.from_borrowed_ptr::<pyo3::PyAny>(_key);
+                let _name = _key
+                    .downcast::<pyo3::types::PyString>()
+                    .ok()
+                    .and_then(|_name| _name.to_str().ok());
+                match _name {
+                    Some(_name) if [#(#names),*].contains(&_name) => {
+                        let _cls = _py.from_borrowed_ptr::<pyo3::PyAny>(_slf);
+                        pyo3::callback::convert(_py, _cls.getattr(_name)?)
+                    }
+                    _ => Err(pyo3::exceptions::PyKeyError::new_err(
+                        pyo3::ToPyObject::to_object(_key, _py),
+                    )),
+                }
+            })
+        }
+    };
+    GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_mp_subscript },
+        name,
+        func,
+    }
+}
+
+/// A type slot generated by a `#[pyclass]` option, e.g. `tp_richcompare` for `eq`.
+struct GeneratedSlot {
+    /// The slot id, e.g. `pyo3::ffi::Py_tp_richcompare`.
+    slot: TokenStream,
+    /// The name of the `extern "C"` function defined by `func`.
+    name: syn::Ident,
+    func: TokenStream,
+}
+
+/// Generates the `tp_richcompare` slot of `#[pyclass(eq)]`.
+///
+/// An object always compares equal to itself, without borrowing it or calling `PartialEq`; this
+/// is also what Python's containers assume. `==` and `!=` against another instance of the class
+/// use the `PartialEq` implementation.
+/// With `eq_dict`, a `dict` compares equal if it has exactly one key per `#[pyo3(get)]` field,
+/// and each value compares equal (using Python's `==`) to the converted field value.
+/// With `eq_tuple`, a `tuple` compares equal if it has exactly one item per `#[pyo3(get)]` field,
+/// and each item compares equal to the converted field value in declaration order; a tuple of
+/// another length compares unequal. For a tuple struct, the tuple is compared against all fields.
+/// All other operators and operands return `NotImplemented`.
+fn impl_richcmp(
+    cls: &syn::Type,
+    attr: &PyClassArgs,
+    fields: &syn::Fields,
+    descriptors: &[(syn::Field, FieldPyO3Options)],
+) -> syn::Result<GeneratedSlot> {
+    let mut getters = Vec::new();
+    for (field, options) in descriptors {
+        if options.descs.iter().any(|desc| desc.is_getter()) {
+            getters.push((field, options));
+        }
+    }
+    let field_eq = |field_value: TokenStream, value: TokenStream| {
+        quote! {
+            #field_value
+                .as_ref(_py)
+                .rich_compare(#value, pyo3::class::basic::CompareOp::Eq)?
+                .is_true()?
+        }
+    };
+
+    let dict_branch = if attr.has_eq_dict {
+        let mut checks = Vec::new();
+        for (field, options) in &getters {
+            let ident = field.ident.as_ref().ok_or_else(
+                || err_spanned!(field.span() => "`eq_dict` is not supported on tuple struct fields"),
+            )?;
+            let name = options.python_name(ident);
+            let eq = field_eq(field_object(field, ident, &name, options)?, quote!(_value));
+            checks.push(quote! {
+                match _other.get_item(#name) {
+                    Some(_value) => #eq,
+                    None => false,
+                }
+            });
+        }
+        let len = checks.len();
+        quote! {
+            else if let Ok(_other) = _other.downcast::<pyo3::types::PyDict>() {
+                let _slf = _cell.try_borrow()?;
+                _other.len() == #len #(&& #checks)*
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let tuple_branch = if attr.has_eq_tuple {
+        let checks: Vec<_> = tuple_field_objects(fields, descriptors)?
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| field_eq(value, quote!(_other.get_item(#index))))
+            .collect();
+        let len = checks.len();
+        quote! {
+            else if let Ok(_other) = _other.downcast::<pyo3::types::PyTuple>() {
+                let _slf = _cell.try_borrow()?;
+                _other.len() == #len #(&& #checks)*
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let name = syn::Ident::new("__pyo3_richcmp", Span::call_site());
+    let func = quote! {
+        unsafe extern "C" fn #name(
+            _slf: *mut pyo3::ffi::PyObject,
+            _other: *mut pyo3::ffi::PyObject,
+            _op: std::os::raw::c_int,
+        ) -> *mut pyo3::ffi::PyObject
+        {
+            pyo3::callback::handle_panic(|_py| {
+                if _op != pyo3::ffi::Py_EQ && _op != pyo3::ffi::Py_NE {
+                    return pyo3::callback::convert(_py, _py.NotImplemented());
+                }
+                let _cell = _py.from_borrowed_ptr::<pyo3::PyCell<#cls>>(_slf);
+                let _other = _py.from_borrowed_ptr::<pyo3::PyAny>(_other);
+                let _eq = if _slf == pyo3::AsPyPointer::as_ptr(_other) {
+                    true
+                } else if let Ok(_other) = _other.downcast::<pyo3::PyCell<#cls>>() {
+                    *_cell.try_borrow()? == *_other.try_borrow()?
+                } #dict_branch #tuple_branch else {
+                    return pyo3::callback::convert(_py, _py.NotImplemented());
+                };
+                pyo3::callback::convert(_py, _eq == (_op == pyo3::ffi::Py_EQ))
+            })
+        }
+    };
+    Ok(GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_tp_richcompare },
+        name,
+        func,
+    })
+}
+
+/// Generates the `tp_richcompare` slot of `#[pyclass(ord)]`, which implements all six comparison
+/// operators with the Rust `Ord` implementation of the class.
+///
+/// As with `eq`, an object is equal to itself without being borrowed, and comparisons with objects
+/// of other types return `NotImplemented`. A missing `Ord` implementation is reported at the
+/// struct name.
+fn impl_ord(cls: &syn::Type) -> GeneratedSlot {
+    let cmp_call = quote_spanned!(cls.span()=> std::cmp::Ord::cmp(&*_cell.try_borrow()?, &*_other.try_borrow()?));
+    let name = syn::Ident::new("__pyo3_richcmp", Span::call_site());
+    let func = quote! {
+        unsafe extern "C" fn #name(
+            _slf: *mut pyo3::ffi::PyObject,
+            _other: *mut pyo3::ffi::PyObject,
+            _op: std::os::raw::c_int,
+        ) -> *mut pyo3::ffi::PyObject
+        {
+            pyo3::callback::handle_panic(|_py| {
+                let _cell = _py.from_borrowed_ptr::<pyo3::PyCell<#cls>>(_slf);
+                let _other = _py.from_borrowed_ptr::<pyo3::PyAny>(_other);
+                let _ordering = if _slf == pyo3::AsPyPointer::as_ptr(_other) {
+                    std::cmp::Ordering::Equal
+                } else if let Ok(_other) = _other.downcast::<pyo3::PyCell<#cls>>() {
+                    #cmp_call
+                } else {
+                    return pyo3::callback::convert(_py, _py.NotImplemented());
+                };
+                pyo3::callback::convert(_py, pyo3::class::impl_::ordering_matches(_ordering, _op)?)
+            })
+        }
+    };
+    GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_tp_richcompare },
+        name,
+        func,
+    }
+}
+
+/// Generates the `tp_repr` slot of `#[pyclass(repr)]`.
+///
+/// The repr looks like a constructor call with keyword arguments, e.g. `Point(x=1, y=2.0)`: the
+/// name of the object's type followed by the Python repr of each `#[pyo3(get)]` field, in
+/// declaration order. With `repr_maxlen = N`, each field's repr is truncated to at most `N`
+/// characters, ending in `...`, so the total length is bounded by the number of fields.
+fn impl_repr(
+    cls: &syn::Type,
+    attr: &PyClassArgs,
+    descriptors: &[(syn::Field, FieldPyO3Options)],
+) -> syn::Result<GeneratedSlot> {
+    let maxlen = match attr.repr_maxlen {
+        Some(maxlen) => quote!(Some(#maxlen)),
+        None => quote!(None),
+    };
+    let mut fields = Vec::new();
+    for (field, options) in descriptors {
+        if !options.descs.iter().any(|desc| desc.is_getter()) {
+            continue;
+        }
+        let ident = field.ident.as_ref().ok_or_else(
+            || err_spanned!(field.span() => "`repr` is not supported on tuple struct fields"),
+        )?;
+        let name = options.python_name(ident);
+        let first = fields.is_empty();
+        let value = field_object(field, ident, &name, options)?;
+        fields.push(quote! {
+            pyo3::class::impl_::push_field_repr(
+                &mut _repr,
+                #first,
+                #name,
+                #value.as_ref(_py),
+                #maxlen,
+            )?;
+        });
+    }
+
+    let name = syn::Ident::new("__pyo3_tp_repr", Span::call_site());
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let func = quote! {
+        unsafe extern "C" fn #name(_slf: *mut pyo3::ffi::PyObject) -> *mut pyo3::ffi::PyObject {
+            pyo3::callback::handle_panic(|_py| {
+                #slf
+                let mut _repr = format!("{}(", _cell.get_type().name()?);
+                #(#fields)*
+                _repr.push(')');
+                pyo3::callback::convert(_py, _repr)
+            })
+        }
+    };
+    Ok(GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_tp_repr },
+        name,
+        func,
+    })
+}
+
+/// Generates the `tp_repr` slot of `#[pyclass(repr_debug)]` from the `Debug` implementation of the
+/// class, e.g. `Point { x: 1, y: 2.0 }` for `#[derive(Debug)]`.
+fn impl_repr_debug(cls: &syn::Type) -> GeneratedSlot {
+    let name = syn::Ident::new("__pyo3_tp_repr", Span::call_site());
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let func = quote! {
+        unsafe extern "C" fn #name(_slf: *mut pyo3::ffi::PyObject) -> *mut pyo3::ffi::PyObject {
+            pyo3::callback::handle_panic(|_py| {
+                #slf
+                pyo3::callback::convert(_py, pyo3::class::impl_::debug_repr(&**_slf))
+            })
+        }
+    };
+    GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_tp_repr },
+        name,
+        func,
+    }
+}
+
+/// Generates the `tp_str` slot of `#[pyclass(str)]` from the `Display` implementation of the
+/// class. It is independent of the `tp_repr` slot generated by `repr`.
+fn impl_str(cls: &syn::Type) -> GeneratedSlot {
+    let name = syn::Ident::new("__pyo3_tp_str", Span::call_site());
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let func = quote! {
+        unsafe extern "C" fn #name(_slf: *mut pyo3::ffi::PyObject) -> *mut pyo3::ffi::PyObject {
+            pyo3::callback::handle_panic(|_py| {
+                #slf
+                pyo3::callback::convert(_py, std::string::ToString::to_string(&**_slf))
+            })
+        }
+    };
+    GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_tp_str },
+        name,
+        func,
+    }
+}
+
+/// Generates the `tp_hash` slot of `#[pyclass(hash_fields)]`.
+///
+/// The hash is the Python hash of a tuple of the `#[pyo3(get)]` field values (or of all fields of a
+/// tuple struct), in declaration order, so it combines the fields' own Python hashes. The class is
+/// `frozen`, so the fields can't change while the object is alive. A `lock` field is rejected, as
+/// the value behind its lock can still change.
+///
+/// Each hashed field must implement `Hash`, which is checked at the field, so that one which can't
+/// be hashed is reported by name.
+fn impl_hash_fields(
+    cls: &syn::Type,
+    fields: &syn::Fields,
+    descriptors: &[(syn::Field, FieldPyO3Options)],
+) -> syn::Result<GeneratedSlot> {
+    for (field, options) in descriptors {
+        ensure_spanned!(
+            !options.lock,
+            field.span() => "`lock` fields cannot be hashed by `hash_fields`, as the value behind the lock can change while the object is alive"
+        );
+    }
+    let values = tuple_field_objects(fields, descriptors)?;
+    let hashed_fields: Vec<_> = match fields {
+        syn::Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .map(|field| {
+                let ty = &field.ty;
+                (ty.span(), quote!(#ty))
+            })
+            .collect(),
+        _ => descriptors
+            .iter()
+            .filter(|(_, options)| options.descs.iter().any(|desc| desc.is_getter()))
+            .map(|(field, _)| {
+                let ty = &field.ty;
+                (ty.span(), quote!(#ty))
+            })
+            .collect(),
+    };
+    let assertions = hash_field_assertions(hashed_fields);
+
+    Ok(hash_slot(
+        cls,
+        quote! {
+            #assertions
+            let _fields: ::std::vec::Vec<pyo3::PyObject> = vec![#(#values),*];
+            pyo3::types::PyTuple::new(_py, _fields).hash()
+        },
+    ))
+}
+
+/// The values of the fields combined by `eq_tuple` and `hash_fields`, in declaration order, as
+/// built by [`field_object`].
+///
+/// These are the `#[pyo3(get)]` fields of a struct with named fields. Tuple struct fields cannot
+/// have descriptors, so all fields of a tuple struct are used, by position.
+fn tuple_field_objects(
+    fields: &syn::Fields,
+    descriptors: &[(syn::Field, FieldPyO3Options)],
+) -> syn::Result<Vec<TokenStream>> {
+    match fields {
+        syn::Fields::Unnamed(fields) => Ok((0..fields.unnamed.len())
+            .map(|index| {
+                let member = syn::Member::Unnamed(index.into());
+                quote!(pyo3::IntoPy::<pyo3::PyObject>::into_py(_slf.#member.clone(), _py))
+            })
+            .collect()),
+        _ => descriptors
+            .iter()
+            .filter(|(_, options)| options.descs.iter().any(|desc| desc.is_getter()))
+            .filter_map(|(field, options)| Some((field, field.ident.as_ref()?, options)))
+            .map(|(field, ident, options)| {
+                field_object(field, ident, &options.python_name(ident), options)
+            })
+            .collect(),
+    }
+}
+
+/// The property of a field with `#[pyo3(get)]` or `#[pyo3(set)]`, named `name` in Python.
+fn field_property<'a>(
+    field: &'a syn::Field,
+    ident: &'a syn::Ident,
+    name: &'a str,
+    options: &'a FieldPyO3Options,
+) -> PropertyType<'a> {
+    PropertyType::Descriptor {
+        field: ident,
+        name,
+        allow_threads: options.allow_threads,
+        lock: options.lock,
+        scale: options.scale,
+        encoding: options.encoding,
+        as_indices: options.as_indices,
+        as_deque: options.as_deque,
+        as_frozenset: options.as_frozenset,
+        unit: options.unit.as_ref(),
+        ty: &field.ty,
+    }
+}
+
+/// Converts a field of `_slf` with `#[pyo3(get)]` to a `PyObject` for the options which read the
+/// fields, e.g. `to_dict` and `repr`, in the same way as its getter.
+fn field_object(
+    field: &syn::Field,
+    ident: &syn::Ident,
+    name: &str,
+    options: &FieldPyO3Options,
+) -> syn::Result<TokenStream> {
+    // Only used for the `Function` properties of `#[pymethods]`.
+    let cls: syn::Type = syn::parse_quote!(Self);
+    let value = crate::pymethod::getter_value(&cls, &field_property(field, ident, name, options))?;
+    Ok(quote!(pyo3::class::impl_::getter_object(_py, #value)?))
+}
+
+/// Generates the `tp_hash` slot of `#[pyclass(hash)]`, which feeds the object to the Rust `Hash`
+/// implementation of the class with a `DefaultHasher`.
+///
+/// The class must implement `Hash`, which the named `__assert_hash` checks at the struct name, in
+/// the same body as the `Hash::hash` call so that rustc reports a missing implementation once.
+/// `#[derive(Hash)]` reports the fields which aren't `Hash` by name.
+fn impl_hash(cls: &syn::Type) -> GeneratedSlot {
+    let assertion = quote_spanned!(cls.span()=> __assert_hash::<#cls>(););
+    let hash_call = quote_spanned!(cls.span()=> std::hash::Hash::hash(&**_slf, &mut _hasher));
+    hash_slot(
+        cls,
+        quote! {
+            fn __assert_hash<T: std::hash::Hash>() {}
+            #assertion
+            let mut _hasher = std::collections::hash_map::DefaultHasher::new();
+            #hash_call;
+            let _hash = std::hash::Hasher::finish(&_hasher) as pyo3::ffi::Py_hash_t;
+            // -1 signals an error to Python.
+            Ok(if _hash == -1 { -2 } else { _hash })
+        },
+    )
+}
+
+/// Generates the `tp_richcompare` slot of a class with a `#[pyo3(hash)]` field.
+///
+/// Objects of the class compare equal if their key fields are equal by `PartialEq`, regardless of
+/// the other fields. Other comparisons and other types of objects return `NotImplemented`.
+fn impl_key_richcmp(cls: &syn::Type, key: &syn::Member, key_ty: &syn::Type) -> GeneratedSlot {
+    let key_eq =
+        quote_spanned!(key_ty.span()=> _cell.try_borrow()?.#key == _other.try_borrow()?.#key);
+    let name = syn::Ident::new("__pyo3_richcmp", Span::call_site());
+    let func = quote! {
+        unsafe extern "C" fn #name(
+            _slf: *mut pyo3::ffi::PyObject,
+            _other: *mut pyo3::ffi::PyObject,
+            _op: std::os::raw::c_int,
+        ) -> *mut pyo3::ffi::PyObject
+        {
+            pyo3::callback::handle_panic(|_py| {
+                if _op != pyo3::ffi::Py_EQ && _op != pyo3::ffi::Py_NE {
+                    return pyo3::callback::convert(_py, _py.NotImplemented());
+                }
+                let _cell = _py.from_borrowed_ptr::<pyo3::PyCell<#cls>>(_slf);
+                let _other = _py.from_borrowed_ptr::<pyo3::PyAny>(_other);
+                let _eq = if _slf == pyo3::AsPyPointer::as_ptr(_other) {
+                    true
+                } else if let Ok(_other) = _other.downcast::<pyo3::PyCell<#cls>>() {
+                    #key_eq
+                } else {
+                    return pyo3::callback::convert(_py, _py.NotImplemented());
+                };
+                pyo3::callback::convert(_py, _eq == (_op == pyo3::ffi::Py_EQ))
+            })
+        }
+    };
+    GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_tp_richcompare },
+        name,
+        func,
+    }
+}
+
+/// Generates the `tp_hash` slot of a class with a `#[pyo3(hash)]` field, which feeds only that
+/// field to its Rust `Hash` implementation with a `DefaultHasher`.
+///
+/// An `Option` field is hashed by the value it holds, and as `NONE_KEY_HASH` if it is `None`. The
+/// hashed type must implement `Hash`, which is checked at the field.
+fn impl_key_hash(cls: &syn::Type, key: &syn::Member, key_ty: &syn::Type) -> GeneratedSlot {
+    let hash_key = |value: TokenStream, ty: &syn::Type| {
+        let hash_call =
+            quote_spanned!(ty.span()=> <#ty as std::hash::Hash>::hash(#value, &mut _hasher));
+        quote! {{
+            let mut _hasher = std::collections::hash_map::DefaultHasher::new();
+            #hash_call;
+            std::hash::Hasher::finish(&_hasher) as pyo3::ffi::Py_hash_t
+        }}
+    };
+    let hashed_ty = utils::option_type_argument(key_ty).unwrap_or(key_ty);
+    let assertions = hash_field_assertions(vec![(hashed_ty.span(), quote!(#hashed_ty))]);
+    let hash = match utils::option_type_argument(key_ty) {
+        Some(inner_ty) => {
+            let hash_inner = hash_key(quote!(_key), inner_ty);
+            quote! {
+                match &_slf.#key {
+                    Some(_key) => #hash_inner,
+                    None => pyo3::class::impl_::NONE_KEY_HASH,
+                }
+            }
+        }
+        None => hash_key(quote!(&_slf.#key), key_ty),
+    };
+    hash_slot(
+        cls,
+        quote! {
+            #assertions
+            let _hash = #hash;
+            // -1 signals an error to Python.
+            Ok(if _hash == -1 { -2 } else { _hash })
+        },
+    )
+}
+
+/// Asserts that the type hashed for each field of a field-scoped `__hash__` implements `Hash`.
+///
+/// Each assertion is spanned at the type of its field, so that the error shows the field rather
+/// than pointing into the generated slot. The assertions go into the body of the slot, where rustc
+/// merges them with the errors of the `Hash::hash` calls at the same spans.
+fn hash_field_assertions(hashed_fields: Vec<(Span, TokenStream)>) -> TokenStream {
+    let assertions = hashed_fields
+        .into_iter()
+        .map(|(span, ty)| quote_spanned!(span=> _assert_hash::<#ty>();));
+    quote! {
+        fn _assert_hash<T: std::hash::Hash + ?Sized>() {}
+        #(#assertions)*
+    }
+}
+
+/// Wraps the body computing a hash into a `tp_hash` slot, which caches the result if the class
+/// has `cache_hash`.
+fn hash_slot(cls: &syn::Type, compute: TokenStream) -> GeneratedSlot {
+    let name = syn::Ident::new("__pyo3_hash", Span::call_site());
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let func = quote! {
+        unsafe extern "C" fn #name(_slf: *mut pyo3::ffi::PyObject) -> pyo3::ffi::Py_hash_t {
+            pyo3::callback::handle_panic(|_py| {
+                #slf
+                pyo3::class::impl_::cached_hash(_cell, || { #compute })
+            })
+        }
+    };
+    GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_tp_hash },
+        name,
+        func,
+    }
+}
+
+/// Generates the `tp_iter` slot of `#[pyclass(iterator)]`, which returns the object itself.
+fn impl_iter_self() -> GeneratedSlot {
+    let name = syn::Ident::new("__pyo3_tp_iter", Span::call_site());
+    let func = quote! {
+        unsafe extern "C" fn #name(_slf: *mut pyo3::ffi::PyObject) -> *mut pyo3::ffi::PyObject {
+            pyo3::ffi::Py_INCREF(_slf);
+            _slf
+        }
+    };
+    GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_tp_iter },
+        name,
+        func,
+    }
+}
+
+/// Generates the `am_aiter` and `am_anext` slots of `#[pyclass(anext = field)]`.
+///
+/// `__aiter__` returns the object itself. `__anext__` returns an `asyncio` future for the next
+/// message of the `PyChannelReceiver` field, which the receiver's waker polls for again.
+fn impl_anext(cls: &syn::Type, field: &syn::Ident) -> Vec<GeneratedSlot> {
+    let aiter = syn::Ident::new("__pyo3_am_aiter", Span::call_site());
+    let anext = syn::Ident::new("__pyo3_am_anext", Span::call_site());
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    vec![
+        GeneratedSlot {
+            slot: quote! { pyo3::ffi::Py_am_aiter },
+            name: aiter.clone(),
+            func: quote! {
+                unsafe extern "C" fn #aiter(_slf: *mut pyo3::ffi::PyObject) -> *mut pyo3::ffi::PyObject {
+                    pyo3::ffi::Py_INCREF(_slf);
+                    _slf
+                }
+            },
+        },
+        GeneratedSlot {
+            slot: quote! { pyo3::ffi::Py_am_anext },
+            name: anext.clone(),
+            func: quote! {
+                unsafe extern "C" fn #anext(_slf: *mut pyo3::ffi::PyObject) -> *mut pyo3::ffi::PyObject {
+                    static POLL: pyo3::once_cell::GILOnceCell<pyo3::PyObject> =
+                        pyo3::once_cell::GILOnceCell::new();
+                    // Only called by `channel_anext` and the waker, as
+                    // `poll(owner, future, event_loop)`.
+                    unsafe extern "C" fn __poll(
+                        _: *mut pyo3::ffi::PyObject,
+                        _args: *mut pyo3::ffi::PyObject,
+                        _kwargs: *mut pyo3::ffi::PyObject,
+                    ) -> *mut pyo3::ffi::PyObject {
+                        pyo3::callback::handle_panic(|_py| {
+                            let _args = _py.from_borrowed_ptr::<pyo3::types::PyTuple>(_args);
+                            let _owner = _args.get_item(0);
+                            let _slf = pyo3::AsPyPointer::as_ptr(_owner);
+                            #slf
+                            let _poll = POLL.get(_py).expect("set before the first poll");
+                            pyo3::callback::convert(
+                                _py,
+                                pyo3::class::impl_::poll_channel(
+                                    &_slf.#field,
+                                    _owner,
+                                    _args.get_item(1),
+                                    _args.get_item(2),
+                                    _poll,
+                                ),
+                            )
+                        })
+                    }
+                    pyo3::callback::handle_panic(|_py| {
+                        let _owner = _py.from_borrowed_ptr::<pyo3::PyAny>(_slf);
+                        pyo3::callback::convert(
+                            _py,
+                            pyo3::class::impl_::channel_anext(_owner, __poll, &POLL),
+                        )
+                    })
+                }
+            },
+        },
+    ]
+}
+
+/// Generates the `tp_getattro` slot of `#[pyclass(proxy = field)]`, which forwards attributes
+/// not found on the object to the wrapped object in the given field.
+///
+/// The object is only borrowed while the field is converted with `ToPyObject`, so the wrapped
+/// object may freely access the proxy during the forwarded lookup.
+fn impl_proxy(cls: &syn::Type, field: &syn::Ident) -> GeneratedSlot {
+    let name = syn::Ident::new("__pyo3_tp_getattro", Span::call_site());
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let func = quote! {
+        unsafe extern "C" fn #name(
+            _slf: *mut pyo3::ffi::PyObject,
+            _name: *mut pyo3::ffi::PyObject,
+        ) -> *mut pyo3::ffi::PyObject {
+            pyo3::callback::handle_panic(|_py| {
+                let _owner = _py.from_borrowed_ptr::<pyo3::PyAny>(_slf);
+                let _name = _py.from_borrowed_ptr::<pyo3::PyAny>(_name);
+                pyo3::callback::convert(
+                    _py,
+                    pyo3::class::impl_::proxy_getattr(_owner, _name, || {
+                        #slf
+                        Ok(pyo3::ToPyObject::to_object(&_slf.#field, _py))
+                    }),
+                )
+            })
+        }
+    };
+    GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_tp_getattro },
+        name,
+        func,
+    }
+}
+
+/// Generates the `tp_init` slot of `#[pyclass(post_init = path)]`.
+///
+/// Python calls `tp_init` with the constructor arguments after `__new__` returns an instance of
+/// the class, so the validator runs after both generated and user-defined constructors. The
+/// arguments themselves are ignored; an error from the validator is raised from the constructor
+/// call. Objects created from Rust with `Py::new` are not validated.
+fn impl_post_init(cls: &syn::Type, validate: &syn::Path) -> GeneratedSlot {
+    let name = syn::Ident::new("__pyo3_tp_init", Span::call_site());
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let func = quote! {
+        unsafe extern "C" fn #name(
+            _slf: *mut pyo3::ffi::PyObject,
+            _args: *mut pyo3::ffi::PyObject,
+            _kwargs: *mut pyo3::ffi::PyObject,
+        ) -> std::os::raw::c_int {
+            pyo3::callback::handle_panic(|_py| {
+                #slf
+                #validate(_slf)?;
+                pyo3::callback::convert(_py, ())
+            })
+        }
+    };
+    GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_tp_init },
+        name,
+        func,
+    }
+}
+
+/// Generates the `mp_subscript` slot of `#[pyclass(getitem = field)]`.
+///
+/// `obj[i]` indexes into the given `Vec`-like field, counting negative indices from the end. An
+/// out-of-range index raises `IndexError` naming both the index and the length.
+///
+/// No `sq_item` is generated: without `sq_length`, CPython would pass negative indices to it
+/// unchanged, so the class is not a sequence for `reversed` or `PySequence_GetItem`.
+fn impl_getitem(cls: &syn::Type, field: &syn::Ident) -> GeneratedSlot {
+    let name = syn::Ident::new("__pyo3_mp_subscript", Span::call_site());
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let func = quote! {
+        unsafe extern "C" fn #name(
+            _slf: *mut pyo3::ffi::PyObject,
+            _key: *mut pyo3::ffi::PyObject,
+        ) -> *mut pyo3::ffi::PyObject {
+            pyo3::callback::handle_panic(|_py| {
+                #slf
+                let _key = _py.from_borrowed_ptr::<pyo3::PyAny>(_key);
+                let _pos = pyo3::class::impl_::sequence_index(
+                    _key.extract::<pyo3::ffi::Py_ssize_t>()?,
+                    _slf.#field.len(),
+                )?;
+                pyo3::callback::convert(_py, pyo3::ToPyObject::to_object(&_slf.#field[_pos], _py))
+            })
+        }
+    };
+    GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_mp_subscript },
+        name,
+        func,
+    }
+}
+
+/// Generates the `sq_item` slot of `#[pyclass(sequence_from = field)]`, which makes the class a
+/// sequence for e.g. `reversed` and `PySequence_GetItem`.
+///
+/// `obj[i]` goes through `mp_subscript`, which handles negative indices. The class also has
+/// `sq_length`, so CPython calls `sq_item` with negative indices already offset by the length, and
+/// they are not offset again here.
+fn impl_sq_item(cls: &syn::Type, field: &syn::Ident) -> GeneratedSlot {
+    let name = syn::Ident::new("__pyo3_sq_item", Span::call_site());
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let func = quote! {
+        unsafe extern "C" fn #name(
+            _slf: *mut pyo3::ffi::PyObject,
+            _index: pyo3::ffi::Py_ssize_t,
+        ) -> *mut pyo3::ffi::PyObject {
+            pyo3::callback::handle_panic(|_py| {
+                #slf
+                let _pos = pyo3::class::impl_::sequence_item_index(_index, _slf.#field.len())?;
+                pyo3::callback::convert(_py, pyo3::ToPyObject::to_object(&_slf.#field[_pos], _py))
+            })
+        }
+    };
+    GeneratedSlot {
+        slot: quote! { pyo3::ffi::Py_sq_item },
+        name,
+        func,
+    }
+}
+
+/// Generates the slots of `#[pyclass(sequence_from = field)]`, which make the class a sequence
+/// over the given `Vec`-like field.
+///
+/// `len(obj)` is the length of the field, and `obj[i]` behaves as for `getitem`. `obj[a:b:c]`
+/// returns a `list` of the selected elements, and `iter(obj)` iterates over a snapshot of the
+/// elements, so changes to the field during iteration are not seen. Elements are converted with
+/// `ToPyObject`.
+fn impl_sequence_from(cls: &syn::Type, field: &syn::Ident) -> Vec<GeneratedSlot> {
+    let len = syn::Ident::new("__pyo3_sq_length", Span::call_site());
+    let subscript = syn::Ident::new("__pyo3_mp_subscript", Span::call_site());
+    let iter = syn::Ident::new("__pyo3_seq_tp_iter", Span::call_site());
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    vec![
+        GeneratedSlot {
+            slot: quote! { pyo3::ffi::Py_sq_length },
+            name: len.clone(),
+            func: quote! {
+                unsafe extern "C" fn #len(_slf: *mut pyo3::ffi::PyObject) -> pyo3::ffi::Py_ssize_t {
+                    pyo3::callback::handle_panic(|_py| {
+                        #slf
+                        pyo3::callback::convert(_py, _slf.#field.len())
+                    })
+                }
+            },
+        },
+        // `sq_item` makes the class a sequence for e.g. `reversed`, while `mp_subscript` takes
+        // precedence for `obj[key]` and adds slicing.
+        impl_sq_item(cls, field),
+        GeneratedSlot {
+            slot: quote! { pyo3::ffi::Py_mp_subscript },
+            name: subscript.clone(),
+            func: quote! {
+                unsafe extern "C" fn #subscript(
+                    _slf: *mut pyo3::ffi::PyObject,
+                    _key: *mut pyo3::ffi::PyObject,
+                ) -> *mut pyo3::ffi::PyObject {
+                    pyo3::callback::handle_panic(|_py| {
+                        #slf
+                        let _key = _py.from_borrowed_ptr::<pyo3::PyAny>(_key);
+                        if let Ok(_slice) = _key.downcast::<pyo3::types::PySlice>() {
+                            let _items: ::std::vec::Vec<pyo3::PyObject> =
+                                pyo3::class::impl_::slice_positions(_slice, _slf.#field.len())?
+                                    .map(|_pos| pyo3::ToPyObject::to_object(&_slf.#field[_pos], _py))
+                                    .collect();
+                            return pyo3::callback::convert(_py, pyo3::types::PyList::new(_py, _items));
+                        }
+                        let _pos = pyo3::class::impl_::sequence_index(
+                            _key.extract::<pyo3::ffi::Py_ssize_t>()?,
+                            _slf.#field.len(),
+                        )?;
+                        pyo3::callback::convert(_py, pyo3::ToPyObject::to_object(&_slf.#field[_pos], _py))
+                    })
+                }
+            },
+        },
+        GeneratedSlot {
+            slot: quote! { pyo3::ffi::Py_tp_iter },
+            name: iter.clone(),
+            func: quote! {
+                unsafe extern "C" fn #iter(_slf: *mut pyo3::ffi::PyObject) -> *mut pyo3::ffi::PyObject {
+                    pyo3::callback::handle_panic(|_py| {
+                        #slf
+                        let _items: ::std::vec::Vec<pyo3::PyObject> = _slf
+                            .#field
+                            .iter()
+                            .map(|_item| pyo3::ToPyObject::to_object(_item, _py))
+                            .collect();
+                        let _list = pyo3::types::PyList::new(_py, _items);
+                        pyo3::callback::convert(_py, pyo3::PyAny::iter(_list)?)
+                    })
+                }
+            },
+        },
+    ]
+}
+
+/// Generates the `nb_negative`, `nb_positive` and `nb_absolute` slots of `#[pyclass(unary_ops)]`.
+///
+/// The class must have exactly one field. Each operator converts the field to Python, applies the
+/// operator there, and extracts the result back into the field type of a new instance, so that
+/// e.g. `abs(Meters(-3.0))` is `Meters(3.0)`.
+fn impl_unary_ops(
+    cls: &syn::Type,
+    cls_ident: &syn::Ident,
+    fields: &syn::Fields,
+) -> syn::Result<Vec<GeneratedSlot>> {
+    ensure_spanned!(
+        fields.len() == 1,
+        cls_ident.span() => "`unary_ops` requires a struct with exactly one field"
+    );
+    let field = fields.iter().next().unwrap();
+    let member = match &field.ident {
+        Some(ident) => syn::Member::Named(ident.clone()),
+        None => syn::Member::Unnamed(0.into()),
+    };
+
+    let slf = SelfType::Receiver {
+        mutable: false,
+        span: Span::call_site(),
+    }
+    .receiver(cls);
+    let ops = [
+        ("__pyo3_nb_negative", "Py_nb_negative", "PyNumber_Negative"),
+        ("__pyo3_nb_positive", "Py_nb_positive", "PyNumber_Positive"),
+        ("__pyo3_nb_absolute", "Py_nb_absolute", "PyNumber_Absolute"),
+    ];
+    Ok(ops
+        .iter()
+        .map(|(name, slot, number_fn)| {
+            let name = syn::Ident::new(name, Span::call_site());
+            let slot = syn::Ident::new(slot, Span::call_site());
+            let number_fn = syn::Ident::new(number_fn, Span::call_site());
+            let func = quote! {
+                unsafe extern "C" fn #name(_slf: *mut pyo3::ffi::PyObject) -> *mut pyo3::ffi::PyObject {
+                    pyo3::callback::handle_panic(|_py| {
+                        #slf
+                        let _value = pyo3::IntoPy::<pyo3::PyObject>::into_py(_slf.#member.clone(), _py);
+                        let _result: &pyo3::PyAny = _py.from_owned_ptr_or_err(
+                            pyo3::ffi::#number_fn(pyo3::AsPyPointer::as_ptr(&_value)),
+                        )?;
+                        let _new = pyo3::Py::new(_py, #cls { #member: _result.extract()? })?;
+                        pyo3::callback::convert(_py, _new)
+                    })
+                }
+            };
+            GeneratedSlot {
+                slot: quote! { pyo3::ffi::#slot },
+                name,
+                func,
+            }
+        })
+        .collect())
+}
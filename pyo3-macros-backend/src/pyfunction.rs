@@ -211,6 +211,8 @@ pub struct PyFunctionOptions {
     pub name: Option<NameAttribute>,
     pub name_is_deprecated: bool,
     pub signature: Option<PyFunctionSignature>,
+    /// `#[pyo3(overridable)]`, only valid on methods in `#[pymethods]`.
+    pub overridable: Option<attributes::kw::overridable>,
 }
 
 impl Parse for PyFunctionOptions {
@@ -220,6 +222,7 @@ impl Parse for PyFunctionOptions {
             name: None,
             name_is_deprecated: false,
             signature: None,
+            overridable: None,
         };
 
         while !input.is_empty() {
@@ -249,6 +252,7 @@ pub enum PyFunctionOption {
     Name(NameAttribute),
     PassModule(attributes::kw::pass_module),
     Signature(PyFunctionSignature),
+    Overridable(attributes::kw::overridable),
 }
 
 impl Parse for PyFunctionOption {
@@ -260,6 +264,8 @@ impl Parse for PyFunctionOption {
             input.parse().map(PyFunctionOption::PassModule)
         } else if lookahead.peek(attributes::kw::signature) {
             input.parse().map(PyFunctionOption::Signature)
+        } else if lookahead.peek(attributes::kw::overridable) {
+            input.parse().map(PyFunctionOption::Overridable)
         } else {
             Err(lookahead.error())
         }
@@ -312,6 +318,13 @@ impl PyFunctionOptions {
                     );
                     self.signature = Some(signature);
                 }
+                PyFunctionOption::Overridable(kw) => {
+                    ensure_spanned!(
+                        self.overridable.is_none(),
+                        kw.span() => "`overridable` may only be specified once"
+                    );
+                    self.overridable = Some(kw);
+                }
             }
         }
         Ok(())
@@ -348,7 +361,11 @@ pub fn impl_wrap_pyfunction(
     options: PyFunctionOptions,
 ) -> syn::Result<(Ident, TokenStream)> {
     check_generic(&func.sig)?;
+    if let Some(kw) = &options.overridable {
+        bail_spanned!(kw.span() => "`overridable` is only supported for methods in `#[pymethods]`");
+    }
 
+    let name_is_explicit = options.name.is_some();
     let python_name = options
         .name
         .map_or_else(|| func.sig.ident.unraw(), |name| name.0);
@@ -378,7 +395,8 @@ pub fn impl_wrap_pyfunction(
     let ty = method::get_return_info(&func.sig.output);
 
     let text_signature = utils::parse_text_signature_attrs(&mut func.attrs, &python_name)?;
-    let doc = utils::get_doc(&func.attrs, text_signature, true)?;
+    let has_text_signature = text_signature.is_some();
+    let doc = utils::get_doc(&func.attrs, text_signature.clone(), true)?;
 
     let function_wrapper_ident = function_wrapper_ident(&func.sig.ident);
 
@@ -391,7 +409,12 @@ pub fn impl_wrap_pyfunction(
         output: ty,
         doc,
         name_is_deprecated: options.name_is_deprecated,
+        name_is_explicit,
+        has_text_signature,
     };
+    if let Some(text_signature) = &text_signature {
+        spec.check_text_signature(text_signature)?;
+    }
 
     let doc = &spec.doc;
     let python_name = spec.python_name_with_deprecation();
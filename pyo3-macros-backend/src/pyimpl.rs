@@ -6,7 +6,7 @@ use crate::{
     pymethod,
 };
 use proc_macro2::TokenStream;
-use pymethod::GeneratedPyMethod;
+use pymethod::{GeneratedPyMethod, PyMethodName};
 use quote::quote;
 use syn::spanned::Spanned;
 
@@ -39,16 +39,35 @@ pub fn impl_methods(
 ) -> syn::Result<TokenStream> {
     let mut new_impls = Vec::new();
     let mut call_impls = Vec::new();
+    let mut dispatch_impls = Vec::new();
     let mut methods = Vec::new();
+    let mut names = Vec::new();
     for iimpl in impls.iter_mut() {
         match iimpl {
             syn::ImplItem::Method(meth) => {
                 let options = PyFunctionOptions::from_attrs(&mut meth.attrs)?;
-                match pymethod::gen_py_method(ty, &mut meth.sig, &mut meth.attrs, options)? {
-                    GeneratedPyMethod::Method(token_stream) => {
+                match pymethod::gen_py_method(
+                    ty,
+                    &meth.vis,
+                    &mut meth.sig,
+                    &mut meth.attrs,
+                    options,
+                )? {
+                    GeneratedPyMethod::Method(token_stream, name) => {
                         let attrs = get_cfg_attributes(&meth.attrs);
+                        if attrs.is_empty() {
+                            check_duplicate_name(&mut names, name)?;
+                        }
                         methods.push(quote!(#(#attrs)* #token_stream));
                     }
+                    GeneratedPyMethod::Overridable(token_stream, name, dispatch) => {
+                        let attrs = get_cfg_attributes(&meth.attrs);
+                        if attrs.is_empty() {
+                            check_duplicate_name(&mut names, name)?;
+                        }
+                        methods.push(quote!(#(#attrs)* #token_stream));
+                        dispatch_impls.push(quote!(#(#attrs)* #dispatch));
+                    }
                     GeneratedPyMethod::New(token_stream) => {
                         let attrs = get_cfg_attributes(&meth.attrs);
                         new_impls.push(quote!(#(#attrs)* #token_stream));
@@ -67,6 +86,12 @@ pub fn impl_methods(
                         attributes,
                     };
                     let attrs = get_cfg_attributes(&konst.attrs);
+                    if attrs.is_empty() {
+                        check_duplicate_name(
+                            &mut names,
+                            PyMethodName::Attribute(spec.python_name()),
+                        )?;
+                    }
                     let meth = pymethod::gen_py_const(ty, &spec);
                     methods.push(quote!(#(#attrs)* #meth));
                 }
@@ -85,10 +110,29 @@ pub fn impl_methods(
 
         #(#call_impls)*
 
+        #(#dispatch_impls)*
+
         #methods_registration
     })
 }
 
+/// Errors if `name` is already used by another item of the `#[pymethods]` block, which would
+/// silently replace it in the type object.
+///
+/// Items with `#[cfg]` attributes are not checked, since they may be mutually exclusive. Items in
+/// other `#[pymethods]` blocks, with the `multiple-pymethods` feature, cannot be seen here.
+fn check_duplicate_name(names: &mut Vec<PyMethodName>, name: PyMethodName) -> syn::Result<()> {
+    ensure_spanned!(
+        !names.iter().any(|other| other.clashes_with(&name)),
+        name.ident().span() => format!(
+            "`{}` is defined more than once in this `#[pymethods]` block",
+            name.ident()
+        )
+    );
+    names.push(name);
+    Ok(())
+}
+
 fn impl_py_methods(ty: &syn::Type, methods: Vec<TokenStream>) -> TokenStream {
     quote! {
         impl pyo3::class::impl_::PyMethods<#ty>
@@ -11,53 +11,219 @@ use syn::{ext::IdentExt, spanned::Spanned, Result};
 
 #[derive(Clone, Copy)]
 pub enum PropertyType<'a> {
-    Descriptor(&'a syn::Ident),
+    Descriptor {
+        field: &'a syn::Ident,
+        /// The Python name of the property.
+        name: &'a str,
+        allow_threads: bool,
+        /// Whether the field is a `Mutex` whose value is cloned while holding the lock.
+        lock: bool,
+        scale: Option<u32>,
+        encoding: Option<BytesEncoding>,
+        as_indices: bool,
+        as_deque: bool,
+        as_frozenset: bool,
+        /// The `uom` unit in which a quantity field is exposed.
+        unit: Option<&'a syn::Path>,
+        /// The type of the field.
+        ty: &'a syn::Type,
+    },
     Function(&'a FnSpec<'a>),
 }
 
+/// How a byte array field is exposed, e.g. `#[pyo3(get, hex)]`: as an encoded `str`, or with
+/// `as_bytes` as `bytes`.
+#[derive(Clone, Copy)]
+pub enum BytesEncoding {
+    Hex,
+    Base64,
+    Bytes,
+}
+
+impl BytesEncoding {
+    pub fn name(self) -> &'static str {
+        match self {
+            BytesEncoding::Hex => "hex",
+            BytesEncoding::Base64 => "base64",
+            BytesEncoding::Bytes => "as_bytes",
+        }
+    }
+
+    fn encode(self, value: TokenStream) -> TokenStream {
+        match self {
+            BytesEncoding::Hex => quote!(pyo3::class::impl_::bytes_to_hex(&#value)),
+            BytesEncoding::Base64 => quote!(pyo3::class::impl_::bytes_to_base64(&#value)),
+            BytesEncoding::Bytes => quote!(pyo3::class::impl_::bytes_to_pybytes(_py, &#value)),
+        }
+    }
+
+    fn decode(self) -> TokenStream {
+        match self {
+            BytesEncoding::Hex => quote!(pyo3::class::impl_::hex_to_bytes(_value)),
+            BytesEncoding::Base64 => quote!(pyo3::class::impl_::base64_to_bytes(_value)),
+            BytesEncoding::Bytes => quote!(pyo3::class::impl_::bytes_like_to_bytes(_value)),
+        }
+    }
+}
+
 pub enum GeneratedPyMethod {
-    Method(TokenStream),
+    Method(TokenStream, PyMethodName),
+    /// A `#[pyo3(overridable)]` method, with its `dispatch_` helper.
+    Overridable(TokenStream, PyMethodName, TokenStream),
     New(TokenStream),
     Call(TokenStream),
 }
 
+/// The Python name of an item in `#[pymethods]`, used to detect items which would replace each
+/// other in the type object.
+pub enum PyMethodName {
+    /// A method, static method, class method or class attribute.
+    Attribute(syn::Ident),
+    Getter(syn::Ident),
+    Setter(syn::Ident),
+}
+
+impl PyMethodName {
+    pub fn ident(&self) -> &syn::Ident {
+        match self {
+            PyMethodName::Attribute(ident)
+            | PyMethodName::Getter(ident)
+            | PyMethodName::Setter(ident) => ident,
+        }
+    }
+
+    /// A getter and a setter with the same name together make up one property, but any other
+    /// two items with the same name clash.
+    pub fn clashes_with(&self, other: &PyMethodName) -> bool {
+        if self.ident() != other.ident() {
+            return false;
+        }
+        if let (PyMethodName::Attribute(_), _) | (_, PyMethodName::Attribute(_)) = (self, other) {
+            return true;
+        }
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
 pub fn gen_py_method(
     cls: &syn::Type,
+    vis: &syn::Visibility,
     sig: &mut syn::Signature,
     meth_attrs: &mut Vec<syn::Attribute>,
     options: PyFunctionOptions,
 ) -> Result<GeneratedPyMethod> {
     check_generic(sig)?;
+    let overridable = options.overridable;
     let spec = FnSpec::parse(sig, &mut *meth_attrs, options)?;
 
+    let attribute = || PyMethodName::Attribute(spec.python_name.clone());
+    ensure_spanned!(
+        spec.python_name != "__init_subclass__" || spec.tp.is_classmethod(),
+        spec.name.span() => "`__init_subclass__` must be a `#[classmethod]`"
+    );
+    if let Some(kw) = overridable {
+        return match &spec.tp {
+            FnType::Fn(self_ty) => Ok(GeneratedPyMethod::Overridable(
+                impl_py_method_def(cls, &spec, self_ty, None)?,
+                attribute(),
+                impl_dispatch_method(cls, vis, &spec, kw.span),
+            )),
+            _ => bail_spanned!(kw.span => "`overridable` is only supported for instance methods"),
+        };
+    }
     Ok(match &spec.tp {
         FnType::Fn(self_ty) => {
-            GeneratedPyMethod::Method(impl_py_method_def(cls, &spec, self_ty, None)?)
+            GeneratedPyMethod::Method(impl_py_method_def(cls, &spec, self_ty, None)?, attribute())
         }
         FnType::FnNew => GeneratedPyMethod::New(impl_py_method_def_new(cls, &spec)?),
         FnType::FnCall(self_ty) => {
             GeneratedPyMethod::Call(impl_py_method_def_call(cls, &spec, self_ty)?)
         }
-        FnType::FnClass => GeneratedPyMethod::Method(impl_py_method_def_class(cls, &spec)?),
-        FnType::FnStatic => GeneratedPyMethod::Method(impl_py_method_def_static(cls, &spec)?),
+        FnType::FnClass => {
+            GeneratedPyMethod::Method(impl_py_method_def_class(cls, &spec)?, attribute())
+        }
+        FnType::FnStatic => {
+            GeneratedPyMethod::Method(impl_py_method_def_static(cls, &spec)?, attribute())
+        }
         FnType::ClassAttribute => {
-            GeneratedPyMethod::Method(impl_py_method_class_attribute(cls, &spec))
-        }
-        FnType::Getter(self_ty) => GeneratedPyMethod::Method(impl_py_getter_def(
-            cls,
-            PropertyType::Function(&spec),
-            self_ty,
-            &spec.doc,
-        )?),
-        FnType::Setter(self_ty) => GeneratedPyMethod::Method(impl_py_setter_def(
-            cls,
-            PropertyType::Function(&spec),
-            self_ty,
-            &spec.doc,
-        )?),
+            GeneratedPyMethod::Method(impl_py_method_class_attribute(cls, &spec), attribute())
+        }
+        FnType::Getter(self_ty) => GeneratedPyMethod::Method(
+            impl_py_getter_def(cls, PropertyType::Function(&spec), self_ty, &spec.doc)?,
+            PyMethodName::Getter(spec.python_name.clone()),
+        ),
+        FnType::Setter(self_ty) => GeneratedPyMethod::Method(
+            impl_py_setter_def(cls, PropertyType::Function(&spec), self_ty, &spec.doc)?,
+            PyMethodName::Setter(spec.python_name.clone()),
+        ),
     })
 }
 
+/// Generates `dispatch_<name>`, which calls the method through Python attribute lookup, so that
+/// an override in a Python subclass is used in place of the Rust implementation.
+fn impl_dispatch_method(
+    cls: &syn::Type,
+    vis: &syn::Visibility,
+    spec: &FnSpec,
+    span: Span,
+) -> TokenStream {
+    let dispatch = syn::Ident::new(&format!("dispatch_{}", spec.name.unraw()), spec.name.span());
+    let python_name = spec.python_name_for_class(cls, false);
+    let args: Vec<_> = spec.args.iter().filter(|arg| !arg.py).collect();
+    let names = args.iter().map(|arg| arg.name);
+    let types = args.iter().map(|arg| arg.ty);
+    let values = args.iter().map(|arg| arg.name);
+    let doc = format!(
+        "Calls `{}` on `slf`, using the override if `slf` is an instance of a Python subclass.",
+        spec.name.unraw()
+    );
+    let (output, result) = match dispatch_output(&spec.output) {
+        Some(output) => (quote!(#output), quote!(_result.extract())),
+        None => (quote!(()), quote!(Ok(()))),
+    };
+    // Overrides only exist in Python subclasses, so the class must allow them. The unsatisfied
+    // bound is reported at `overridable`.
+    let subclassable = quote_spanned! { span => pyo3::class::impl_::PyClassSubclassable };
+    quote! {
+        impl #cls {
+            #[doc = #doc]
+            #vis fn #dispatch(
+                slf: &pyo3::PyCell<Self>,
+                #(#names: #types),*
+            ) -> pyo3::PyResult<#output>
+            where
+                #cls: #subclassable,
+            {
+                let _result = slf.call_method1(#python_name, (#(#values,)*))?;
+                #result
+            }
+        }
+    }
+}
+
+/// The type returned by a method with output `ty`, without the `PyResult` wrapper, or `None` if the
+/// method returns `()`.
+fn dispatch_output(ty: &syn::Type) -> Option<&syn::Type> {
+    match ty {
+        syn::Type::Infer(_) => None,
+        syn::Type::Tuple(tuple) if tuple.elems.is_empty() => None,
+        syn::Type::Path(path) => {
+            let segment = path.path.segments.last()?;
+            if segment.ident != "PyResult" && segment.ident != "Result" {
+                return Some(ty);
+            }
+            match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+                    Some(syn::GenericArgument::Type(inner)) => dispatch_output(inner),
+                    _ => Some(ty),
+                },
+                _ => Some(ty),
+            }
+        }
+        _ => Some(ty),
+    }
+}
+
 pub(crate) fn check_generic(sig: &syn::Signature) -> syn::Result<()> {
     let err_msg = |typ| format!("Python functions cannot have generic {} parameters", typ);
     for param in &sig.generics.params {
@@ -244,17 +410,92 @@ fn impl_call_getter(cls: &syn::Type, spec: &FnSpec) -> syn::Result<TokenStream>
     Ok(fncall)
 }
 
+/// Generates the value returned by the getter of a property, with `_slf` and `_py` in scope.
+///
+/// For a field, this applies the conversion selected by its `#[pyo3(get, ...)]` options, e.g. `hex`.
+/// The `#[pyclass]` options which read the fields, such as `to_dict` and `repr`, use it too, so that
+/// they see the same values as the getter. The value is either a type that converts to a Python
+/// object, or a `PyResult` of one.
+pub(crate) fn getter_value(
+    cls: &syn::Type,
+    property_type: &PropertyType,
+) -> syn::Result<TokenStream> {
+    Ok(match property_type {
+        PropertyType::Descriptor {
+            field,
+            allow_threads,
+            lock,
+            scale,
+            encoding,
+            as_indices,
+            as_deque,
+            as_frozenset,
+            unit,
+            ty,
+            ..
+        } => {
+            let value = if *allow_threads {
+                // The closure must be `Send`, so it cannot touch any Python objects. The `PyRef`
+                // borrow is held throughout, so the field cannot be mutated while the GIL is
+                // released.
+                quote!({
+                    let _field = &_slf.#field;
+                    _py.allow_threads(move || _field.clone())
+                })
+            } else if *lock {
+                locked_field_value(field, ty)
+            } else if encoding.is_some()
+                || *as_indices
+                || *as_deque
+                || *as_frozenset
+                || unit.is_some()
+            {
+                quote!(_slf.#field)
+            } else if utils::is_py_object(ty) {
+                // The GIL is held, so the reference count can be increased directly.
+                quote!(pyo3::Py::clone_ref(&_slf.#field, _py))
+            } else {
+                quote!(_slf.#field.clone())
+            };
+            match scale {
+                Some(scale) => quote!(pyo3::class::impl_::scaled_to_decimal(_py, #value, #scale)),
+                None => match encoding {
+                    Some(encoding) => encoding.encode(value),
+                    None if *as_indices => {
+                        quote!(pyo3::class::impl_::bits_to_indices(_py, &#value))
+                    }
+                    None if *as_deque => quote!(pyo3::class::impl_::to_deque(_py, &#value)),
+                    None if *as_frozenset => {
+                        quote!(pyo3::class::impl_::to_frozenset(_py, &#value))
+                    }
+                    None => match unit {
+                        Some(unit) => quote_spanned!(unit.span() => #value.get::<#unit>()),
+                        None => value,
+                    },
+                },
+            }
+        }
+        PropertyType::Function(spec) => impl_call_getter(cls, spec)?,
+    })
+}
+
 /// Generate a function wrapper called `__wrap` for a property getter
 pub(crate) fn impl_wrap_getter(
     cls: &syn::Type,
     property_type: PropertyType,
     self_ty: &SelfType,
 ) -> syn::Result<TokenStream> {
-    let getter_impl = match &property_type {
-        PropertyType::Descriptor(ident) => {
-            quote!(_slf.#ident.clone())
+    let getter_impl = getter_value(cls, &property_type)?;
+
+    // A field type which can't be converted is reported at the field.
+    let convert = match &property_type {
+        PropertyType::Descriptor { ty, .. } => {
+            quote_spanned! {ty.span()=>
+                let _value = #getter_impl;
+                pyo3::callback::convert(_py, _value)
+            }
         }
-        PropertyType::Function(spec) => impl_call_getter(cls, spec)?,
+        PropertyType::Function(_) => quote!(pyo3::callback::convert(_py, #getter_impl)),
     };
 
     let slf = self_ty.receiver(cls);
@@ -264,13 +505,23 @@ pub(crate) fn impl_wrap_getter(
         {
             pyo3::callback::handle_panic(|_py| {
                 #slf
-                pyo3::callback::convert(_py, #getter_impl)
+                #convert
             })
         }
         __wrap
     }})
 }
 
+/// Clones the value guarded by the `Mutex` of a `#[pyo3(get, lock)]` field of `_slf`.
+///
+/// Spanned at the field type, so that a field which isn't a mutex is reported there.
+fn locked_field_value(field: &syn::Ident, ty: &syn::Type) -> TokenStream {
+    quote_spanned!(ty.span() => {
+        use pyo3::class::impl_::PyClassLockedField as _;
+        _slf.#field.lock_clone(_py)?
+    })
+}
+
 fn impl_call_setter(cls: &syn::Type, spec: &FnSpec) -> syn::Result<TokenStream> {
     let (py_arg, args) = split_off_python_arg(&spec.args);
 
@@ -293,6 +544,63 @@ fn impl_call_setter(cls: &syn::Type, spec: &FnSpec) -> syn::Result<TokenStream>
     Ok(fncall)
 }
 
+/// Generates the extraction of the value passed to the setter of a property from `_value`, with
+/// `_py` in scope.
+///
+/// For a field, this reverses the conversion of [`getter_value`], e.g. decoding a `hex` string.
+/// `positional_new` uses it too, so that the constructor accepts what the setter accepts. With
+/// `assign`, `_slf` is the object whose field is replaced; an `as_indices` bit set then keeps the
+/// length of the current value.
+pub(crate) fn setter_value(property_type: &PropertyType, assign: bool) -> TokenStream {
+    match property_type {
+        PropertyType::Descriptor {
+            scale: Some(scale), ..
+        } => quote!(pyo3::class::impl_::decimal_to_scaled(_value, #scale)?.extract()?),
+        PropertyType::Descriptor {
+            encoding: Some(encoding),
+            ty,
+            ..
+        } => {
+            let decode = encoding.decode();
+            if let Some(len) = utils::byte_array_len(ty) {
+                quote!({
+                    let mut _array = [0u8; #len];
+                    pyo3::class::impl_::copy_bytes_to_array(#decode?, &mut _array)?;
+                    _array
+                })
+            } else {
+                quote!(#decode?.extract()?)
+            }
+        }
+        PropertyType::Descriptor {
+            field,
+            as_indices: true,
+            ..
+        } => {
+            let len = if assign {
+                quote!(_slf.#field.len())
+            } else {
+                quote!(0)
+            };
+            quote!(pyo3::class::impl_::indices_to_bits(_value, #len)?)
+        }
+        PropertyType::Descriptor {
+            unit: Some(unit),
+            ty,
+            ..
+        } => {
+            quote_spanned!(unit.span() => <#ty>::new::<#unit>(pyo3::FromPyObject::extract(_value)?))
+        }
+        PropertyType::Descriptor { ty, .. } if utils::is_any_py_object(ty) => {
+            quote!(pyo3::PyObject::from(_value))
+        }
+        PropertyType::Descriptor { ty, .. } => {
+            quote_spanned!(ty.span() => pyo3::FromPyObject::extract(_value)?)
+        }
+        _ => quote!(pyo3::FromPyObject::extract(_value)?),
+    }
+}
+
 /// Generate a function wrapper called `__wrap` for a property setter
 pub(crate) fn impl_wrap_setter(
     cls: &syn::Type,
@@ -300,11 +608,25 @@ pub(crate) fn impl_wrap_setter(
     self_ty: &SelfType,
 ) -> syn::Result<TokenStream> {
     let setter_impl = match &property_type {
-        PropertyType::Descriptor(ident) => {
-            quote!({ _slf.#ident = _val; })
+        PropertyType::Descriptor { field, .. } => {
+            quote!({ _slf.#field = _val; })
         }
         PropertyType::Function(spec) => impl_call_setter(cls, spec)?,
     };
+    let extract = setter_value(&property_type, true);
+    // `del obj.attr` calls the setter with a null value. It resets an `Option` field to `None`,
+    // which `None` itself already does through `FromPyObject`; other properties can't be deleted.
+    let delete = match &property_type {
+        PropertyType::Descriptor { field, ty, .. } if utils::option_type_argument(ty).is_some() => {
+            quote! {
+                _slf.#field = None;
+                return pyo3::callback::convert(_py, ());
+            }
+        }
+        _ => quote! {
+            return Err(pyo3::exceptions::PyAttributeError::new_err("can't delete attribute"));
+        },
+    };
 
     let slf = self_ty.receiver(cls);
     Ok(quote! {{
@@ -315,8 +637,11 @@ pub(crate) fn impl_wrap_setter(
         {
             pyo3::callback::handle_panic(|_py| {
                 #slf
+                if _value.is_null() {
+                    #delete
+                }
                 let _value = _py.from_borrowed_ptr::<pyo3::types::PyAny>(_value);
-                let _val = pyo3::FromPyObject::extract(_value)?;
+                let _val = #extract;
 
                 pyo3::callback::convert(_py, #setter_impl)
             })
@@ -408,14 +733,20 @@ pub fn impl_arg_params(
     } else {
         quote! { None }
     };
-    let python_name = &spec.python_name;
+    let func_name = match self_ {
+        Some(cls) => spec.python_name_for_class(cls, false),
+        None => {
+            let python_name = &spec.python_name;
+            quote!(stringify!(#python_name))
+        }
+    };
 
     // create array of arguments, and then parse
     Ok(quote! {
         {
             const DESCRIPTION: pyo3::derive_utils::FunctionDescription = pyo3::derive_utils::FunctionDescription {
                 cls_name: #cls_name,
-                func_name: stringify!(#python_name),
+                func_name: #func_name,
                 positional_parameter_names: &[#(#positional_parameter_names),*],
                 // TODO: https://github.com/PyO3/pyo3/issues/1439 - support specifying these
                 positional_only_parameters: 0,
@@ -578,8 +909,8 @@ pub fn impl_py_method_def(
     flags: Option<TokenStream>,
 ) -> Result<TokenStream> {
     let add_flags = flags.map(|flags| quote!(.flags(#flags)));
-    let python_name = spec.python_name_with_deprecation();
-    let doc = &spec.doc;
+    let python_name = spec.python_name_for_class(cls, true);
+    let doc = spec.doc_for_class(cls);
     if spec.args.is_empty() {
         let wrapper = impl_wrap_noargs(cls, spec, self_ty);
         Ok(quote! {
@@ -610,21 +941,39 @@ pub fn impl_py_method_def(
 
 pub fn impl_py_method_def_new(cls: &syn::Type, spec: &FnSpec) -> Result<TokenStream> {
     let wrapper = impl_wrap_new(cls, &spec)?;
+    let text_signature = spec.generated_text_signature();
     Ok(quote! {
         impl pyo3::class::impl_::PyClassNewImpl<#cls> for pyo3::class::impl_::PyClassImplCollector<#cls> {
             fn new_impl(self) -> Option<pyo3::ffi::newfunc> {
                 Some(#wrapper)
             }
+            fn new_text_signature(self) -> Option<&'static str> {
+                Some(#text_signature)
+            }
         }
     })
 }
 
 pub fn impl_py_method_def_class(cls: &syn::Type, spec: &FnSpec) -> Result<TokenStream> {
     let wrapper = impl_wrap_class(cls, &spec)?;
-    let python_name = spec.python_name_with_deprecation();
-    let doc = &spec.doc;
+    let python_name = spec.python_name_for_class(cls, true);
+    let doc = spec.doc_for_class(cls);
+    // `__init_subclass__` only runs for Python subclasses, so the class must allow them. The
+    // unsatisfied bound is reported at the method.
+    let check_subclassable = if spec.python_name == "__init_subclass__" {
+        quote_spanned! { spec.name.span() =>
+            fn __pyo3_init_subclass_requires_subclass()
+            where
+                #cls: pyo3::class::impl_::PyClassSubclassable,
+            {
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
     Ok(quote! {
         pyo3::class::PyMethodDefType::Class({
+            #check_subclassable
             pyo3::class::PyMethodDef::cfunction_with_keywords(
                 #python_name,
                 pyo3::class::methods::PyCFunctionWithKeywords(#wrapper),
@@ -636,8 +985,8 @@ pub fn impl_py_method_def_class(cls: &syn::Type, spec: &FnSpec) -> Result<TokenS
 
 pub fn impl_py_method_def_static(cls: &syn::Type, spec: &FnSpec) -> Result<TokenStream> {
     let wrapper = impl_wrap_static(cls, &spec)?;
-    let python_name = spec.python_name_with_deprecation();
-    let doc = &spec.doc;
+    let python_name = spec.python_name_for_class(cls, true);
+    let doc = spec.doc_for_class(cls);
     Ok(quote! {
         pyo3::class::PyMethodDefType::Static({
             pyo3::class::PyMethodDef::cfunction_with_keywords(
@@ -698,11 +1047,11 @@ pub(crate) fn impl_py_setter_def(
     doc: &syn::LitStr,
 ) -> Result<TokenStream> {
     let python_name = match property_type {
-        PropertyType::Descriptor(ident) => {
-            let formatted_name = format!("{}\0", ident.unraw());
+        PropertyType::Descriptor { name, .. } => {
+            let formatted_name = format!("{}\0", name);
             quote!(#formatted_name)
         }
-        PropertyType::Function(spec) => spec.python_name_with_deprecation(),
+        PropertyType::Function(spec) => spec.python_name_for_class(cls, true),
     };
     let wrapper = impl_wrap_setter(cls, property_type, self_ty)?;
     Ok(quote! {
@@ -723,11 +1072,11 @@ pub(crate) fn impl_py_getter_def(
     doc: &syn::LitStr,
 ) -> Result<TokenStream> {
     let python_name = match property_type {
-        PropertyType::Descriptor(ident) => {
-            let formatted_name = format!("{}\0", ident.unraw());
+        PropertyType::Descriptor { name, .. } => {
+            let formatted_name = format!("{}\0", name);
             quote!(#formatted_name)
         }
-        PropertyType::Function(spec) => spec.python_name_with_deprecation(),
+        PropertyType::Function(spec) => spec.python_name_for_class(cls, true),
     };
     let wrapper = impl_wrap_getter(cls, property_type, self_ty)?;
     Ok(quote! {
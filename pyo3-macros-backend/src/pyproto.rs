@@ -6,8 +6,8 @@ use crate::proto_method::impl_method_proto;
 use crate::pyfunction::PyFunctionOptions;
 use crate::pymethod;
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
 use quote::ToTokens;
+use quote::{quote, quote_spanned};
 use std::collections::HashSet;
 use syn::spanned::Spanned;
 
@@ -43,6 +43,21 @@ pub fn build_py_proto(ast: &mut syn::ItemImpl) -> syn::Result<TokenStream> {
     Ok(tokens)
 }
 
+/// The marker trait of the slot filled by a protocol method, if `#[pyclass]` can generate that
+/// slot too.
+fn slot_marker(proto: &str, method: &str) -> Option<&'static str> {
+    match (proto, method) {
+        ("Object", "__richcmp__") => Some("PyClassRichcmpSlot"),
+        ("Object", "__repr__") => Some("PyClassReprSlot"),
+        ("Object", "__str__") => Some("PyClassStrSlot"),
+        ("Object", "__hash__") => Some("PyClassHashSlot"),
+        ("Sequence", "__getitem__") => Some("PyClassSqItemSlot"),
+        ("Mapping", "__getitem__") => Some("PyClassMpSubscriptSlot"),
+        ("Iter", "__iter__") => Some("PyClassIterSlot"),
+        _ => None,
+    }
+}
+
 fn impl_proto_impl(
     ty: &syn::Type,
     impls: &mut Vec<syn::ImplItem>,
@@ -57,7 +72,32 @@ fn impl_proto_impl(
         if let syn::ImplItem::Method(met) = iimpl {
             // impl Py~Protocol<'p> { type = ... }
             if let Some(m) = proto.get_proto(&met.sig.ident) {
+                if let Some(span) = mutable_receiver_span(&met.sig) {
+                    // `&mut self` can't be borrowed from a `#[pyclass(frozen)]`; report it at the
+                    // receiver rather than failing with a borrow error at runtime.
+                    let assertion = quote_spanned! { span =>
+                        fn _assert_not_frozen<T: pyo3::PyClass<Frozen = pyo3::pyclass::False>>(
+                            _: std::marker::PhantomData<T>,
+                        ) {
+                        }
+                        _assert_not_frozen(std::marker::PhantomData::<#ty>);
+                    };
+                    quote! {
+                        const _: fn() = || {
+                            #assertion
+                        };
+                    }
+                    .to_tokens(&mut trait_impls);
+                }
                 impl_method_proto(ty, &mut met.sig, &module, m)?.to_tokens(&mut trait_impls);
+                if let Some(marker) = slot_marker(proto.name, &met.sig.ident.to_string()) {
+                    // Conflicts with the same impl from `#[pyclass(eq)]` and the like.
+                    let marker = syn::Ident::new(marker, met.sig.ident.span());
+                    quote_spanned! { met.sig.ident.span() =>
+                        impl pyo3::class::impl_::#marker for #ty {}
+                    }
+                    .to_tokens(&mut trait_impls);
+                }
                 // Insert the method to the HashSet
                 method_names.insert(met.sig.ident.to_string());
             }
@@ -94,6 +134,27 @@ fn impl_proto_impl(
     })
 }
 
+/// The span of the receiver of a protocol method which mutably borrows the object, i.e. `&mut self`
+/// or `slf: PyRefMut<Self>`.
+fn mutable_receiver_span(sig: &syn::Signature) -> Option<Span> {
+    match sig.inputs.first()? {
+        syn::FnArg::Receiver(receiver) if receiver.mutability.is_some() => Some(receiver.span()),
+        syn::FnArg::Typed(arg) => match &*arg.ty {
+            syn::Type::Path(path)
+                if path
+                    .path
+                    .segments
+                    .last()
+                    .map_or(false, |seg| seg.ident == "PyRefMut") =>
+            {
+                Some(arg.span())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 fn impl_normal_methods(
     py_methods: Vec<TokenStream>,
     ty: &syn::Type,
@@ -1,6 +1,6 @@
 // Copyright (c) 2017-present PyO3 Project and Contributors
 use proc_macro2::{Span, TokenStream};
-use quote::quote_spanned;
+use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
 
 /// Macro inspired by `anyhow::anyhow!` to create a compiler error with the given span.
@@ -44,6 +44,52 @@ pub fn is_python(mut ty: &syn::Type) -> bool {
     }
 }
 
+/// Check if the given type `ty` is `Py<T>` or `PyObject`.
+pub fn is_py_object(mut ty: &syn::Type) -> bool {
+    while let syn::Type::Group(group) = ty {
+        ty = &*group.elem;
+    }
+    match ty {
+        syn::Type::Path(typath) => typath
+            .path
+            .segments
+            .last()
+            .map(|seg| seg.ident == "Py" || seg.ident == "PyObject")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Check if the given type `ty` is `PyObject` or `Py<PyAny>`, which any Python object converts to.
+pub fn is_any_py_object(mut ty: &syn::Type) -> bool {
+    while let syn::Type::Group(group) = ty {
+        ty = &*group.elem;
+    }
+    let seg = match ty {
+        syn::Type::Path(typath) => match typath.path.segments.last() {
+            Some(seg) => seg,
+            None => return false,
+        },
+        _ => return false,
+    };
+    if seg.ident == "PyObject" {
+        return true;
+    }
+    match &seg.arguments {
+        syn::PathArguments::AngleBracketed(params) if seg.ident == "Py" => {
+            match params.args.first() {
+                Some(syn::GenericArgument::Type(syn::Type::Path(arg))) => arg
+                    .path
+                    .segments
+                    .last()
+                    .map_or(false, |arg| arg.ident == "PyAny"),
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
 /// If `ty` is Option<T>, return `Some(T)`, else None.
 pub fn option_type_argument(ty: &syn::Type) -> Option<&syn::Type> {
     if let syn::Type::Path(syn::TypePath { path, .. }) = ty {
@@ -57,6 +103,20 @@ pub fn option_type_argument(ty: &syn::Type) -> Option<&syn::Type> {
     None
 }
 
+/// If `ty` is a byte array `[u8; N]`, return `Some(N)`, else None.
+pub fn byte_array_len(ty: &syn::Type) -> Option<&syn::Expr> {
+    match ty {
+        syn::Type::Array(array) => match &*array.elem {
+            syn::Type::Path(elem) if elem.qself.is_none() && elem.path.is_ident("u8") => {
+                Some(&array.len)
+            }
+            _ => None,
+        },
+        syn::Type::Group(group) => byte_array_len(&group.elem),
+        _ => None,
+    }
+}
+
 pub fn is_text_signature_attr(attr: &syn::Attribute) -> bool {
     attr.path.is_ident("text_signature")
 }
@@ -85,6 +145,7 @@ fn parse_text_signature_attr(
                 value.starts_with('(') && value.ends_with(')'),
                 lit.span() => "text_signature must start with \"(\" and end with \")\""
             );
+            TextSignatureParams::parse(&lit)?;
             Ok(Some(syn::LitStr::new(
                 &(python_name_str.to_owned() + &value),
                 lit.span(),
@@ -96,6 +157,122 @@ fn parse_text_signature_attr(
     }
 }
 
+/// The parameters of a `#[text_signature]` which can be checked against the function.
+pub struct TextSignatureParams {
+    /// The names of the keyword-only parameters, which follow `*` or `*args`.
+    pub keyword_only: Vec<String>,
+    /// Whether there is a `*args` parameter.
+    pub has_varargs: bool,
+    /// Whether there is a `**kwargs` parameter.
+    pub has_kwargs: bool,
+}
+
+impl TextSignatureParams {
+    /// Parses the parameter list of a text signature such as `name(a, b=None, /, *, c=42)`.
+    ///
+    /// Checks that brackets and quotes are balanced, and that `/`, `*` (or `*args`) and `**kwargs`
+    /// each appear at most once and in the order Python allows.
+    pub fn parse(signature: &syn::LitStr) -> syn::Result<Self> {
+        let value = signature.value();
+        let span = signature.span();
+        let start = value.find('(').map_or(0, |start| start + 1);
+        let end = value.rfind(')').unwrap_or(value.len()).max(start);
+        let params = split_text_signature_params(&value[start..end]).ok_or_else(
+            || err_spanned!(span => "text_signature has unbalanced brackets or quotes"),
+        )?;
+
+        let mut result = TextSignatureParams {
+            keyword_only: Vec::new(),
+            has_varargs: false,
+            has_kwargs: false,
+        };
+        let mut has_param = false;
+        let mut has_slash = false;
+        let mut has_star = false;
+        let last = params.len().saturating_sub(1);
+        for (index, param) in params.iter().enumerate() {
+            ensure_spanned!(
+                !result.has_kwargs,
+                span => "text_signature must end with `**kwargs`"
+            );
+            if param.is_empty() {
+                // A trailing comma is allowed, like in a `def`.
+                ensure_spanned!(
+                    index == last && index > 0,
+                    span => "text_signature has an empty parameter"
+                );
+            } else if *param == "/" {
+                ensure_spanned!(!has_slash, span => "text_signature has more than one `/`");
+                ensure_spanned!(!has_star, span => "text_signature has `/` after `*`");
+                ensure_spanned!(has_param, span => "text_signature has `/` without a parameter before it");
+                has_slash = true;
+            } else if param.starts_with("**") {
+                result.has_kwargs = true;
+            } else if param.starts_with('*') {
+                ensure_spanned!(
+                    !has_star,
+                    span => "text_signature has more than one `*` or `*args`"
+                );
+                has_star = true;
+                result.has_varargs = *param != "*";
+            } else {
+                has_param = true;
+                if has_star {
+                    let name = param.split(&['=', ':'][..]).next().unwrap();
+                    result.keyword_only.push(name.trim().to_string());
+                }
+            }
+        }
+        ensure_spanned!(
+            !has_star || result.has_varargs || !result.keyword_only.is_empty(),
+            span => "text_signature has a bare `*` without keyword-only parameters after it"
+        );
+        Ok(result)
+    }
+}
+
+/// Splits the parameters of a text signature at the commas outside brackets and quotes, or returns
+/// `None` if they aren't balanced.
+fn split_text_signature_params(params: &str) -> Option<Vec<&str>> {
+    let mut closing = Vec::new();
+    let mut quote = None;
+    let mut escaped = false;
+    let mut split = Vec::new();
+    let mut start = 0;
+    for (index, c) in params.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' => quote = Some(c),
+            '(' => closing.push(')'),
+            '[' => closing.push(']'),
+            '{' => closing.push('}'),
+            ')' | ']' | '}' if closing.pop() != Some(c) => return None,
+            ',' if closing.is_empty() => {
+                split.push(params[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if quote.is_some() || !closing.is_empty() {
+        return None;
+    }
+    let rest = params[start..].trim();
+    if !rest.is_empty() || !split.is_empty() {
+        split.push(rest);
+    }
+    Some(split)
+}
+
 pub fn parse_text_signature_attrs(
     attrs: &mut Vec<syn::Attribute>,
     python_name: &syn::Ident,
@@ -176,3 +353,68 @@ pub fn name_deprecation_token(span: Span, name_is_deprecated: bool) -> Option<To
         None
     }
 }
+
+/// A case conversion of `#[pyclass(rename_all = "...")]`.
+///
+/// The variants are in the same order as those of `pyo3::class::impl_::RenameRule`, after its
+/// `None`, so that `#[pymethods]` can generate the name of a method in every case and let the
+/// class select one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RenameRule {
+    Camel,
+    Pascal,
+    Kebab,
+}
+
+impl RenameRule {
+    pub const ALL: [RenameRule; 3] = [RenameRule::Camel, RenameRule::Pascal, RenameRule::Kebab];
+
+    pub fn from_name(rule: &str) -> Option<Self> {
+        match rule {
+            "camelCase" => Some(RenameRule::Camel),
+            "PascalCase" => Some(RenameRule::Pascal),
+            "kebab-case" => Some(RenameRule::Kebab),
+            _ => None,
+        }
+    }
+
+    /// The path of the matching `pyo3::class::impl_::RenameRule`.
+    pub fn runtime_variant(self) -> TokenStream {
+        match self {
+            RenameRule::Camel => quote!(pyo3::class::impl_::RenameRule::CamelCase),
+            RenameRule::Pascal => quote!(pyo3::class::impl_::RenameRule::PascalCase),
+            RenameRule::Kebab => quote!(pyo3::class::impl_::RenameRule::KebabCase),
+        }
+    }
+
+    /// Converts a `snake_case` name. Leading underscores are kept, and dunder names are left
+    /// alone.
+    pub fn apply(self, name: &str) -> String {
+        if name.len() > 4 && name.starts_with("__") && name.ends_with("__") {
+            return name.to_string();
+        }
+        let body = name.trim_start_matches('_');
+        let mut renamed = name[..name.len() - body.len()].to_string();
+        for (i, word) in body.split('_').filter(|word| !word.is_empty()).enumerate() {
+            match self {
+                RenameRule::Camel | RenameRule::Pascal => {
+                    let mut chars = word.chars();
+                    let first = chars.next().expect("words are not empty");
+                    if i == 0 && self == RenameRule::Camel {
+                        renamed.extend(first.to_lowercase());
+                    } else {
+                        renamed.extend(first.to_uppercase());
+                    }
+                    renamed.push_str(chars.as_str());
+                }
+                RenameRule::Kebab => {
+                    if i > 0 {
+                        renamed.push('-');
+                    }
+                    renamed.push_str(word);
+                }
+            }
+        }
+        renamed
+    }
+}
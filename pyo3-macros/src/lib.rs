@@ -7,9 +7,9 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 use pyo3_macros_backend::{
-    build_derive_from_pyobject, build_py_class, build_py_function, build_py_methods,
-    build_py_proto, get_doc, process_functions_in_module, py_init, PyClassArgs, PyClassMethodsType,
-    PyFunctionOptions,
+    build_derive_from_pyobject, build_py_class, build_py_class_alias, build_py_enum,
+    build_py_function, build_py_methods, build_py_proto, get_doc, process_functions_in_module,
+    py_init, PyClassArgs, PyClassMethodsType, PyFunctionOptions,
 };
 use quote::quote;
 use syn::parse_macro_input;
@@ -68,20 +68,64 @@ pub fn pyproto(_: TokenStream, input: TokenStream) -> TokenStream {
     .into()
 }
 
-/// A proc macro used to expose Rust structs as Python objects.
+/// A proc macro used to expose Rust structs and fieldless enums as Python objects.
 ///
 /// `#[pyclass]` accepts the following [parameters][2]:
 ///
 /// |  Parameter  |  Description |
 /// | :-  | :- |
 /// | <span style="white-space: pre">`name = "python_name"`</span> | Sets the name that Python sees this class as. Defaults to the name of the Rust struct. |
-/// | <span style="white-space: pre">`freelist = N`</span> |  Implements a [free list][10] of size N. This can improve performance for types that are often created and deleted in quick succession. Profile your code to see whether `freelist` is right for you.  |
+/// | <span style="white-space: pre">`freelist = N`</span> |  Implements a [free list][10] of size N, a nonzero constant expression. This can improve performance for types that are often created and deleted in quick succession. Profile your code to see whether `freelist` is right for you.  |
 /// | `gc`  | Participate in Python's [garbage collection][5]. Required if your type contains references to other Python objects. If you don't (or incorrectly) implement this, contained Python objects may be hidden from Python's garbage collector and you may leak memory. Note that leaking memory, while undesirable, [is safe behavior][7].|
 /// | `weakref` | Allows this class to be [weakly referenceable][6]. |
 /// | <span style="white-space: pre">`extends = BaseType`</span>  | Use a custom baseclass. Defaults to [`PyAny`][4] |
 /// | `subclass` | Allows other Python classes and `#[pyclass]` to inherit from this class.  |
 /// | `unsendable` | Required if your struct is not [`Send`][3]. Rather than using `unsendable`, consider implementing your struct in a threadsafe way by e.g. substituting [`Rc`][8] with [`Arc`][9]. By using `unsendable`, your class will panic when accessed by another thread.|
-/// | <span style="white-space: pre">`module = "module_name"`</span> |  Python code will see the class as being defined in this module. Defaults to `builtins`. |
+/// | <span style="white-space: pre">`module = "module_name"`</span> |  Python code will see the class as being defined in this module, which may also be given as a constant `&'static str` expression. Defaults to `builtins`. |
+/// | <span style="white-space: pre">`rename_all = "camelCase"`</span> | Converts the Python names of fields and methods to `camelCase`, `PascalCase` or `kebab-case`, except those given explicitly. |
+/// | `to_dict` | Generates a `to_dict()` method returning a `dict` of the fields annotated with `#[pyo3(get)]`. |
+/// | `eq` | Implements `==` and `!=` using `PartialEq`; an object is always equal to itself. |
+/// | `eq_dict` | Used with `eq`, also compares equal to a `dict` of the `#[pyo3(get)]` fields. |
+/// | `eq_tuple` | Used with `eq`, also compares equal to a `tuple` of the `#[pyo3(get)]` fields (all fields of a tuple struct) in declaration order. |
+/// | `deepcopy` | Generates `__deepcopy__`, deep-copying `Py<T>` fields with `copy.deepcopy` and cloning the others. |
+/// | `getnewargs_ex` | Generates `__getnewargs_ex__` for `pickle`, passing the `#[pyo3(get)]` fields to `__new__` as keyword arguments. |
+/// | `getstate` | Generates `__getstate__` and `__setstate__` for `pickle` from all fields except those marked `#[pyo3(transient)]`, which are reset to `Default`. |
+/// | `positional_new` | Generates a constructor taking the `#[pyo3(set)]` fields in declaration order. |
+/// | `lenient_new` | Like `positional_new`, but unknown keyword arguments are ignored. |
+/// | <span style="white-space: pre">`intern = N`</span> | With `positional_new` or `lenient_new`, caches up to `N` instances keyed by their constructor arguments. |
+/// | <span style="white-space: pre">`instancecheck = check`</span> | Accepts objects for which `check(&PyAny) -> PyResult<bool>` returns `true` in `isinstance`, via a generated metaclass. |
+/// | <span style="white-space: pre">`subclasscheck = check`</span> | Accepts classes for which `check(&PyType) -> PyResult<bool>` returns `true` in `issubclass`, via a generated metaclass. |
+/// | <span style="white-space: pre">`post_init = validate`</span> | Generates an `__init__` that calls `validate(&Self) -> PyResult<()>` after construction, raising its error from the constructor. |
+/// | <span style="white-space: pre">`set_name = field`</span> | Generates `__set_name__`, storing the attribute name in `field`. |
+/// | <span style="white-space: pre">`set_owner = field`</span> | Generates `__set_name__`, storing the owning class in `field`. |
+/// | `unary_ops` | For single-field structs, implements `-`, `+` and `abs()` returning a new instance of the class. |
+/// | <span style="white-space: pre">`bytes = method`</span> | Generates `__bytes__` calling `fn method(&self) -> Vec<u8>` (or `PyResult<Vec<u8>>`). |
+/// | <span style="white-space: pre">`array = method`</span> | Generates `__array__` converting the result of `fn method(&self)` with `numpy.asarray`, for `numpy.array(obj)`. |
+/// | <span style="white-space: pre">`enter = method`</span> | Generates `__enter__` returning the resource created by `fn method(&mut self, py: Python) -> R`. |
+/// | <span style="white-space: pre">`exit = method`</span> | With `enter`, generates `__exit__` calling `fn method(&mut self, py: Python)` to release the resource. |
+/// | `reentrant` | With `enter`, only the outermost of nested `with` blocks acquires and releases the resource. |
+/// | `hash_fields` | Implements `__hash__` by hashing the `#[pyo3(get)]` fields (all fields of a tuple struct) as a tuple. Not allowed with `#[pyo3(set)]` fields. |
+/// | `hash` | Implements `__hash__` with the class's Rust `Hash` implementation. |
+/// | `cache_hash` | Used with `hash` or `hash_fields`, caches the hash in the object after it is first computed. |
+/// | `iterator` | Implements `__iter__` returning `self`, for classes implementing `__next__`. |
+/// | <span style="white-space: pre">`anext = field`</span> | Implements `__aiter__` and `__anext__`, awaiting messages from the given channel receiver field. |
+/// | <span style="white-space: pre">`getitem = field`</span> | Implements sequence indexing into a `Vec`-like field, raising `IndexError` with the index and length when out of range. |
+/// | <span style="white-space: pre">`sequence_from = field`</span> | Implements `__len__`, `__getitem__` (with slicing) and `__iter__` over a `Vec`-like field. |
+/// | `sequence` | Registers the class as a `collections.abc.Sequence`, and sets `Py_TPFLAGS_SEQUENCE` on Python 3.10+. |
+/// | `mapping` | Registers the class as a `collections.abc.Mapping`, and sets `Py_TPFLAGS_MAPPING` on Python 3.10+. |
+/// | <span style="white-space: pre">`proxy = field`</span> | Implements `__getattr__` forwarding attributes not found on the object to the wrapped object in the given field. |
+/// | `repr` | Implements `__repr__` as `Name(field=value, ...)` from the `#[pyo3(get)]` fields. |
+/// | <span style="white-space: pre">`repr_maxlen = N`</span> | Truncates each field's repr generated by `repr` to at most `N` characters. |
+/// | `str` | Implements `__str__` with the `Display` implementation of the class, independently of `repr`. |
+/// | `frozen` | Makes the class immutable from Rust: no `&mut self` receivers, `#[pyo3(set)]` fields or `borrow_mut()`, and borrowing skips the runtime check. |
+/// | `get_all`, `set_all` | Generates a getter / setter for every named field without its own `#[pyo3(get)]` or `#[pyo3(set)]`. |
+///
+/// On an enum, each variant becomes a class attribute, and variants compare equal by their
+/// discriminant, which `int()` returns. A variant marked `#[pyo3(falsy)]` is falsy. Variants
+/// pickle by name, as `getattr(Enum, "Variant")`.
+///
+/// On a type alias of an instantiation of a generic struct, e.g. `type IntWrapper = Wrapper<i64>;`,
+/// the alias becomes a class of its own. The options which use the struct's fields are unavailable.
 ///
 /// For more on creating Python classes,
 /// see the [class section of the guide][1].
@@ -101,20 +145,64 @@ pub fn pyclass(attr: TokenStream, input: TokenStream) -> TokenStream {
     pyclass_impl(attr, input, PyClassMethodsType::Specialization)
 }
 
-/// A proc macro used to expose Rust structs as Python objects.
+/// A proc macro used to expose Rust structs and fieldless enums as Python objects.
 ///
 /// `#[pyclass]` accepts the following [parameters][2]:
 ///
 /// |  Parameter  |  Description |
 /// | :-  | :- |
 /// | <span style="white-space: pre">`name = "python_name"`</span> | Sets the name that Python sees this class as. Defaults to the name of the Rust struct. |
-/// | <span style="white-space: pre">`freelist = N`</span> |  Implements a [free list][10] of size N. This can improve performance for types that are often created and deleted in quick succession. Profile your code to see whether `freelist` is right for you.  |
+/// | <span style="white-space: pre">`freelist = N`</span> |  Implements a [free list][10] of size N, a nonzero constant expression. This can improve performance for types that are often created and deleted in quick succession. Profile your code to see whether `freelist` is right for you.  |
 /// | `gc`  | Participate in Python's [garbage collection][5]. Required if your type contains references to other Python objects. If you don't (or incorrectly) implement this, contained Python objects may be hidden from Python's garbage collector and you may leak memory. Note that leaking memory, while undesirable, [is safe behavior][7].|
 /// | `weakref` | Allows this class to be [weakly referenceable][6]. |
 /// | <span style="white-space: pre">`extends = BaseType`</span> | Use a custom baseclass. Defaults to [`PyAny`][4] |
 /// | `subclass` | Allows other Python classes and `#[pyclass]` to inherit from this class.  |
 /// | `unsendable` | Required if your struct is not [`Send`][3]. Rather than using `unsendable`, consider implementing your struct in a threadsafe way by e.g. substituting [`Rc`][8] with [`Arc`][9]. By using `unsendable`, your class will panic when accessed by another thread.|
-/// | <span style="white-space: pre">`module = "module_name"`</span> |  Python code will see the class as being defined in this module. Defaults to `builtins`. |
+/// | <span style="white-space: pre">`module = "module_name"`</span> |  Python code will see the class as being defined in this module, which may also be given as a constant `&'static str` expression. Defaults to `builtins`. |
+/// | <span style="white-space: pre">`rename_all = "camelCase"`</span> | Converts the Python names of fields and methods to `camelCase`, `PascalCase` or `kebab-case`, except those given explicitly. |
+/// | `to_dict` | Generates a `to_dict()` method returning a `dict` of the fields annotated with `#[pyo3(get)]`. |
+/// | `eq` | Implements `==` and `!=` using `PartialEq`; an object is always equal to itself. |
+/// | `eq_dict` | Used with `eq`, also compares equal to a `dict` of the `#[pyo3(get)]` fields. |
+/// | `eq_tuple` | Used with `eq`, also compares equal to a `tuple` of the `#[pyo3(get)]` fields (all fields of a tuple struct) in declaration order. |
+/// | `deepcopy` | Generates `__deepcopy__`, deep-copying `Py<T>` fields with `copy.deepcopy` and cloning the others. |
+/// | `getnewargs_ex` | Generates `__getnewargs_ex__` for `pickle`, passing the `#[pyo3(get)]` fields to `__new__` as keyword arguments. |
+/// | `getstate` | Generates `__getstate__` and `__setstate__` for `pickle` from all fields except those marked `#[pyo3(transient)]`, which are reset to `Default`. |
+/// | `positional_new` | Generates a constructor taking the `#[pyo3(set)]` fields in declaration order. |
+/// | `lenient_new` | Like `positional_new`, but unknown keyword arguments are ignored. |
+/// | <span style="white-space: pre">`intern = N`</span> | With `positional_new` or `lenient_new`, caches up to `N` instances keyed by their constructor arguments. |
+/// | <span style="white-space: pre">`instancecheck = check`</span> | Accepts objects for which `check(&PyAny) -> PyResult<bool>` returns `true` in `isinstance`, via a generated metaclass. |
+/// | <span style="white-space: pre">`subclasscheck = check`</span> | Accepts classes for which `check(&PyType) -> PyResult<bool>` returns `true` in `issubclass`, via a generated metaclass. |
+/// | <span style="white-space: pre">`post_init = validate`</span> | Generates an `__init__` that calls `validate(&Self) -> PyResult<()>` after construction, raising its error from the constructor. |
+/// | <span style="white-space: pre">`set_name = field`</span> | Generates `__set_name__`, storing the attribute name in `field`. |
+/// | <span style="white-space: pre">`set_owner = field`</span> | Generates `__set_name__`, storing the owning class in `field`. |
+/// | `unary_ops` | For single-field structs, implements `-`, `+` and `abs()` returning a new instance of the class. |
+/// | <span style="white-space: pre">`bytes = method`</span> | Generates `__bytes__` calling `fn method(&self) -> Vec<u8>` (or `PyResult<Vec<u8>>`). |
+/// | <span style="white-space: pre">`array = method`</span> | Generates `__array__` converting the result of `fn method(&self)` with `numpy.asarray`, for `numpy.array(obj)`. |
+/// | <span style="white-space: pre">`enter = method`</span> | Generates `__enter__` returning the resource created by `fn method(&mut self, py: Python) -> R`. |
+/// | <span style="white-space: pre">`exit = method`</span> | With `enter`, generates `__exit__` calling `fn method(&mut self, py: Python)` to release the resource. |
+/// | `reentrant` | With `enter`, only the outermost of nested `with` blocks acquires and releases the resource. |
+/// | `hash_fields` | Implements `__hash__` by hashing the `#[pyo3(get)]` fields (all fields of a tuple struct) as a tuple. Not allowed with `#[pyo3(set)]` fields. |
+/// | `hash` | Implements `__hash__` with the class's Rust `Hash` implementation. |
+/// | `cache_hash` | Used with `hash` or `hash_fields`, caches the hash in the object after it is first computed. |
+/// | `iterator` | Implements `__iter__` returning `self`, for classes implementing `__next__`. |
+/// | <span style="white-space: pre">`anext = field`</span> | Implements `__aiter__` and `__anext__`, awaiting messages from the given channel receiver field. |
+/// | <span style="white-space: pre">`getitem = field`</span> | Implements sequence indexing into a `Vec`-like field, raising `IndexError` with the index and length when out of range. |
+/// | <span style="white-space: pre">`sequence_from = field`</span> | Implements `__len__`, `__getitem__` (with slicing) and `__iter__` over a `Vec`-like field. |
+/// | `sequence` | Registers the class as a `collections.abc.Sequence`, and sets `Py_TPFLAGS_SEQUENCE` on Python 3.10+. |
+/// | `mapping` | Registers the class as a `collections.abc.Mapping`, and sets `Py_TPFLAGS_MAPPING` on Python 3.10+. |
+/// | <span style="white-space: pre">`proxy = field`</span> | Implements `__getattr__` forwarding attributes not found on the object to the wrapped object in the given field. |
+/// | `repr` | Implements `__repr__` as `Name(field=value, ...)` from the `#[pyo3(get)]` fields. |
+/// | <span style="white-space: pre">`repr_maxlen = N`</span> | Truncates each field's repr generated by `repr` to at most `N` characters. |
+/// | `str` | Implements `__str__` with the `Display` implementation of the class, independently of `repr`. |
+/// | `frozen` | Makes the class immutable from Rust: no `&mut self` receivers, `#[pyo3(set)]` fields or `borrow_mut()`, and borrowing skips the runtime check. |
+/// | `get_all`, `set_all` | Generates a getter / setter for every named field without its own `#[pyo3(get)]` or `#[pyo3(set)]`. |
+///
+/// On an enum, each variant becomes a class attribute, and variants compare equal by their
+/// discriminant, which `int()` returns. A variant marked `#[pyo3(falsy)]` is falsy. Variants
+/// pickle by name, as `getattr(Enum, "Variant")`.
+///
+/// On a type alias of an instantiation of a generic struct, e.g. `type IntWrapper = Wrapper<i64>;`,
+/// the alias becomes a class of its own. The options which use the struct's fields are unavailable.
 ///
 /// For more on creating Python classes,
 /// see the [class section of the guide][1].
@@ -250,10 +338,18 @@ fn pyclass_impl(
     input: TokenStream,
     methods_type: PyClassMethodsType,
 ) -> TokenStream {
-    let mut ast = parse_macro_input!(input as syn::ItemStruct);
+    let mut ast = parse_macro_input!(input as syn::Item);
     let args = parse_macro_input!(attr as PyClassArgs);
-    let expanded =
-        build_py_class(&mut ast, &args, methods_type).unwrap_or_else(|e| e.to_compile_error());
+    let expanded = match &mut ast {
+        syn::Item::Struct(class) => build_py_class(class, &args, methods_type),
+        syn::Item::Enum(enum_) => build_py_enum(enum_, &args, methods_type),
+        syn::Item::Type(alias) => build_py_class_alias(alias, &args, methods_type),
+        unsupported => Err(syn::Error::new_spanned(
+            unsupported,
+            "#[pyclass] only supports structs, enums and type aliases",
+        )),
+    }
+    .unwrap_or_else(|e| e.to_compile_error());
 
     quote!(
         #ast
@@ -44,7 +44,7 @@ where
 {
     crate::callback_body!(py, {
         let slf = py.from_borrowed_ptr::<PyCell<T>>(slf);
-        T::bf_getbuffer(slf.try_borrow_mut()?, arg1, arg2).convert(py)
+        T::bf_getbuffer(slf.try_borrow_mut_checked()?, arg1, arg2).convert(py)
     })
 }
 
@@ -55,6 +55,6 @@ where
 {
     crate::callback_body!(py, {
         let slf = py.from_borrowed_ptr::<crate::PyCell<T>>(slf);
-        T::bf_releasebuffer(slf.try_borrow_mut()?, arg1).convert(py)
+        T::bf_releasebuffer(slf.try_borrow_mut_checked()?, arg1).convert(py)
     })
 }
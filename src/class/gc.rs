@@ -35,6 +35,17 @@ where
     let py = pool.python();
     let slf = py.from_borrowed_ptr::<PyCell<T>>(slf);
 
+    // `__traverse__` can't reach the managed dict of `#[pyclass(dict)]`.
+    #[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+    {
+        if has_managed_dict(slf.as_ptr()) {
+            let result = ffi::PyObject_VisitManagedDict(slf.as_ptr(), visit, arg);
+            if result != 0 {
+                return result;
+            }
+        }
+    }
+
     let visit = PyVisit {
         visit,
         arg,
@@ -59,10 +70,25 @@ where
     let pool = crate::GILPool::new();
     let slf = pool.python().from_borrowed_ptr::<PyCell<T>>(slf);
 
-    slf.borrow_mut().__clear__();
+    slf.try_borrow_mut_checked()
+        .expect("Already borrowed")
+        .__clear__();
+    #[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+    {
+        if has_managed_dict(slf.as_ptr()) {
+            ffi::PyObject_ClearManagedDict(slf.as_ptr());
+        }
+    }
     0
 }
 
+/// Whether the interpreter manages the `__dict__` of the object, which is not the case when the
+/// base type of a `#[pyclass(dict)]` provides the dict.
+#[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+pub(crate) unsafe fn has_managed_dict(slf: *mut ffi::PyObject) -> bool {
+    ffi::PyType_HasFeature(ffi::Py_TYPE(slf), ffi::Py_TPFLAGS_MANAGED_DICT) != 0
+}
+
 /// Object visitor for GC.
 #[derive(Clone)]
 pub struct PyVisit<'p> {
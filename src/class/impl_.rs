@@ -1,13 +1,35 @@
 // Copyright (c) 2017-present PyO3 Project and Contributors
 
 use crate::{
+    callback::IntoPyCallbackOutput,
+    class::pyasync::PyChannelReceiver,
+    exceptions::{
+        PyAttributeError, PyIndexError, PyRecursionError, PyRuntimeError, PyStopAsyncIteration,
+        PyValueError,
+    },
     ffi,
+    once_cell::GILOnceCell,
     pycell::PyCellLayout,
     pyclass_init::PyObjectInit,
+    pyclass_slots::{PyClassEnterDepth, PyClassHashCache},
     type_object::{PyLayout, PyTypeObject},
-    PyClass, PyMethodDefType, PyNativeType, PyTypeInfo,
+    types::{
+        PyByteArray, PyBytes, PyCFunction, PyDict, PyFloat, PyLong, PySlice, PyString, PyTuple,
+        PyType,
+    },
+    AsPyPointer, IntoPy, Py, PyAny, PyCell, PyClass, PyDowncastError, PyErr, PyMethodDefType,
+    PyNativeType, PyObject, PyResult, PyTryFrom, PyTypeInfo, Python, ToPyObject,
+};
+use std::{
+    cell::Cell,
+    cmp::Ordering,
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    os::raw::{c_int, c_long},
+    sync::Arc,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+    thread,
 };
-use std::{marker::PhantomData, thread};
 
 /// This type is used as a "dummy" type on which dtolnay specializations are
 /// applied to apply implementations from `#[pymethods]` & `#[pyproto]`
@@ -33,6 +55,18 @@ impl<T> Clone for PyClassImplCollector<T> {
 
 impl<T> Copy for PyClassImplCollector<T> {}
 
+/// The case conversion of `#[pyclass(rename_all = "...")]`.
+///
+/// `#[pymethods]` generates the Python name of each method for every variant, in declaration
+/// order, and selects the one of the class with `PyClassImpl::RENAME_ALL as usize`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenameRule {
+    None,
+    CamelCase,
+    PascalCase,
+    KebabCase,
+}
+
 /// Implements the underlying functionality of `#[pyclass]`, assembled by various proc macros.
 ///
 /// Users are discouraged from implementing this trait manually; it is a PyO3 implementation detail
@@ -50,6 +84,15 @@ pub trait PyClassImpl: Sized {
     /// #[pyclass(extends=...)]
     const IS_SUBCLASS: bool = false;
 
+    /// #[pyclass(sequence)]
+    const IS_SEQUENCE: bool = false;
+
+    /// #[pyclass(mapping)]
+    const IS_MAPPING: bool = false;
+
+    /// #[pyclass(rename_all = "...")]
+    const RENAME_ALL: RenameRule = RenameRule::None;
+
     /// Layout
     type Layout: PyLayout<Self>;
 
@@ -66,9 +109,18 @@ pub trait PyClassImpl: Sized {
     type ThreadChecker: PyClassThreadChecker<Self>;
 
     fn for_each_method_def(_visitor: &mut dyn FnMut(&PyMethodDefType)) {}
+    /// Methods of the class's metaclass, e.g. from `#[pyclass(instancecheck = ...)]`.
+    fn for_each_metaclass_method_def(_visitor: &mut dyn FnMut(&PyMethodDefType)) {}
+    /// Slots of the class's metaclass, e.g. `sq_contains` of a `#[pyclass]` enum.
+    fn for_each_metaclass_slot(_visitor: &mut dyn FnMut(&ffi::PyType_Slot)) {}
     fn get_new() -> Option<ffi::newfunc> {
         None
     }
+    /// The text signature generated from `#[new]`, e.g. `(a, b=0)`, unless the class has a
+    /// `#[text_signature]` of its own.
+    fn get_new_text_signature() -> Option<&'static str> {
+        None
+    }
     fn get_call() -> Option<ffi::PyCFunctionWithKeywords> {
         None
     }
@@ -82,12 +134,16 @@ pub trait PyClassImpl: Sized {
 
 pub trait PyClassNewImpl<T> {
     fn new_impl(self) -> Option<ffi::newfunc>;
+    fn new_text_signature(self) -> Option<&'static str>;
 }
 
 impl<T> PyClassNewImpl<T> for &'_ PyClassImplCollector<T> {
     fn new_impl(self) -> Option<ffi::newfunc> {
         None
     }
+    fn new_text_signature(self) -> Option<&'static str> {
+        None
+    }
 }
 
 pub trait PyClassCallImpl<T> {
@@ -140,6 +196,12 @@ pub trait HasMethodsInventory {
 // Methods from #[pyo3(get, set)] on struct fields.
 methods_trait!(PyClassDescriptors, py_class_descriptors);
 
+// Methods generated by #[pyclass] options, such as `to_dict`.
+methods_trait!(PyClassGeneratedMethods, py_class_generated_methods);
+
+// Metaclass methods generated by #[pyclass] options, such as `instancecheck`.
+methods_trait!(PyClassMetaclassMethods, py_class_metaclass_methods);
+
 // Methods from #[pymethods] if not using inventory.
 #[cfg(not(feature = "multiple-pymethods"))]
 methods_trait!(PyMethods, py_methods);
@@ -175,6 +237,39 @@ slots_trait!(PyAsyncProtocolSlots, async_protocol_slots);
 slots_trait!(PySequenceProtocolSlots, sequence_protocol_slots);
 slots_trait!(PyBufferProtocolSlots, buffer_protocol_slots);
 
+// Slots generated by #[pyclass] options, such as `eq`.
+slots_trait!(PyClassGeneratedSlots, py_class_generated_slots);
+
+// Metaclass slots generated by #[pyclass], such as `sq_contains` for enums.
+slots_trait!(PyClassMetaclassSlots, py_class_metaclass_slots);
+
+/// Implemented for a class both where `#[pyclass]` generates `tp_richcompare` (e.g. for `eq`) and
+/// by `__richcmp__` in `#[pyproto]`, so that defining comparisons both ways fails to compile with
+/// conflicting implementations, instead of one slot silently replacing the other.
+pub trait PyClassRichcmpSlot {}
+
+/// Like [`PyClassRichcmpSlot`], for `tp_repr` and `__repr__`.
+pub trait PyClassReprSlot {}
+
+/// Like [`PyClassRichcmpSlot`], for `tp_str` and `__str__`.
+pub trait PyClassStrSlot {}
+
+/// Like [`PyClassRichcmpSlot`], for `tp_hash` and `__hash__`.
+pub trait PyClassHashSlot {}
+
+/// Like [`PyClassRichcmpSlot`], for `sq_item` and `__getitem__` in `PySequenceProtocol`.
+pub trait PyClassSqItemSlot {}
+
+/// Like [`PyClassRichcmpSlot`], for `mp_subscript` and `__getitem__` in `PyMappingProtocol`.
+pub trait PyClassMpSubscriptSlot {}
+
+/// Like [`PyClassRichcmpSlot`], for `tp_iter` and `__iter__`.
+pub trait PyClassIterSlot {}
+
+/// Implemented for a class with `#[pyclass(subclass)]`. A `__init_subclass__` classmethod in
+/// `#[pymethods]` requires it, since it only runs when Python code subclasses the class.
+pub trait PyClassSubclassable {}
+
 methods_trait!(PyObjectProtocolMethods, object_protocol_methods);
 methods_trait!(PyAsyncProtocolMethods, async_protocol_methods);
 methods_trait!(PyContextProtocolMethods, context_protocol_methods);
@@ -201,6 +296,164 @@ impl<T> PyBufferProtocolProcs<T> for &'_ PyClassImplCollector<T> {
     }
 }
 
+/// Implemented by `#[pyclass(buffer(data = ..., shape = ...))]` to export its `data` field with the
+/// shape held in its `shape` field.
+#[cfg(not(Py_LIMITED_API))]
+pub trait PyClassShapedBuffer: PyClass {
+    /// Fills `view` with `fill_shaped_buffer`, except for `obj`.
+    ///
+    /// # Safety
+    /// `view` must point to a `Py_buffer` to fill.
+    unsafe fn fill_buffer(&self, view: *mut ffi::Py_buffer, flags: c_int) -> PyResult<()>;
+}
+
+/// The element types of `#[pyclass(buffer(...))]`, with their `struct` module format characters.
+#[cfg(not(Py_LIMITED_API))]
+pub trait PyBufferElement: Copy {
+    /// The nul-terminated format string.
+    const FORMAT: &'static [u8];
+}
+
+macro_rules! buffer_element {
+    ($($ty:ty => $format:literal),*) => {
+        $(
+            #[cfg(not(Py_LIMITED_API))]
+            impl PyBufferElement for $ty {
+                const FORMAT: &'static [u8] = $format;
+            }
+        )*
+    };
+}
+
+buffer_element!(
+    bool => b"?\0", u8 => b"B\0", i8 => b"b\0", u16 => b"H\0", i16 => b"h\0", u32 => b"I\0",
+    i32 => b"i\0", u64 => b"Q\0", i64 => b"q\0", usize => b"N\0", isize => b"n\0",
+    f32 => b"f\0", f64 => b"d\0"
+);
+
+/// Fills `view` to export `data` as a read-only, C-contiguous buffer with the given `shape`.
+///
+/// The strides are row-major: the last dimension is contiguous, and each earlier dimension steps
+/// over a whole row of the ones after it. So for `f64` items with shape `[2, 3]` the strides are
+/// `[24, 8]`. The shape and strides are allocated in `view.internal`, which
+/// `shaped_releasebuffer` frees.
+///
+/// Raises `BufferError` if the shape doesn't match the number of items in `data`, or if a writable
+/// or Fortran-contiguous buffer is requested.
+///
+/// # Safety
+/// `view` must point to a `Py_buffer` to fill, and `data` must stay alive and unchanged until the
+/// buffer is released.
+#[cfg(not(Py_LIMITED_API))]
+pub unsafe fn fill_shaped_buffer<E: PyBufferElement>(
+    data: &[E],
+    shape: &[usize],
+    view: *mut ffi::Py_buffer,
+    flags: c_int,
+) -> PyResult<()> {
+    use crate::exceptions::PyBufferError;
+    use std::convert::TryFrom;
+
+    let items = shape
+        .iter()
+        .try_fold(1usize, |items, &dim| items.checked_mul(dim));
+    if items != Some(data.len()) {
+        return Err(PyBufferError::new_err(format!(
+            "buffer shape {:?} does not match the {} items of the data",
+            shape,
+            data.len()
+        )));
+    }
+    if flags & ffi::PyBUF_WRITABLE == ffi::PyBUF_WRITABLE {
+        return Err(PyBufferError::new_err("buffer is not writable"));
+    }
+    if flags & ffi::PyBUF_F_CONTIGUOUS == ffi::PyBUF_F_CONTIGUOUS && shape.len() > 1 {
+        return Err(PyBufferError::new_err("buffer is not Fortran contiguous"));
+    }
+    let ndim = c_int::try_from(shape.len())
+        .map_err(|_| PyBufferError::new_err("buffer has too many dimensions"))?;
+
+    let itemsize = std::mem::size_of::<E>() as ffi::Py_ssize_t;
+    let mut dims: Vec<ffi::Py_ssize_t> = shape.iter().map(|&dim| dim as ffi::Py_ssize_t).collect();
+    let mut stride = itemsize;
+    let mut strides = vec![0; shape.len()];
+    for (stride_out, &dim) in strides.iter_mut().zip(&dims).rev() {
+        *stride_out = stride;
+        stride *= dim;
+    }
+    dims.extend(strides);
+    let dims = Box::into_raw(dims.into_boxed_slice()) as *mut ffi::Py_ssize_t;
+
+    let view = &mut *view;
+    view.buf = data.as_ptr() as *mut std::os::raw::c_void;
+    view.len = data.len() as ffi::Py_ssize_t * itemsize;
+    view.itemsize = itemsize;
+    view.readonly = 1;
+    view.ndim = ndim;
+    view.format = if flags & ffi::PyBUF_FORMAT == ffi::PyBUF_FORMAT {
+        E::FORMAT.as_ptr() as *mut _
+    } else {
+        std::ptr::null_mut()
+    };
+    view.shape = if flags & ffi::PyBUF_ND == ffi::PyBUF_ND {
+        dims
+    } else {
+        std::ptr::null_mut()
+    };
+    view.strides = if flags & ffi::PyBUF_STRIDES == ffi::PyBUF_STRIDES {
+        dims.add(shape.len())
+    } else {
+        std::ptr::null_mut()
+    };
+    view.suboffsets = std::ptr::null_mut();
+    view.internal = dims as *mut std::os::raw::c_void;
+    Ok(())
+}
+
+/// The `bf_getbuffer` slot of `#[pyclass(buffer(...))]`.
+///
+/// The object stays borrowed until the buffer is released, so that methods taking `&mut self`
+/// cannot move or resize the data while it is exported; they raise an error instead.
+#[cfg(not(Py_LIMITED_API))]
+pub unsafe extern "C" fn shaped_getbuffer<T: PyClassShapedBuffer>(
+    slf: *mut ffi::PyObject,
+    view: *mut ffi::Py_buffer,
+    flags: c_int,
+) -> c_int {
+    crate::callback::handle_panic(|py| {
+        if view.is_null() {
+            return Err(crate::exceptions::PyBufferError::new_err("View is null"));
+        }
+        let cell = py.from_borrowed_ptr::<PyCell<T>>(slf);
+        let borrow = cell.try_borrow()?;
+        borrow.fill_buffer(view, flags)?;
+        std::mem::forget(borrow);
+        ffi::Py_INCREF(slf);
+        (*view).obj = slf;
+        Ok(0)
+    })
+}
+
+/// The `bf_releasebuffer` slot of `#[pyclass(buffer(...))]`, which ends the borrow taken by
+/// `shaped_getbuffer` and frees the shape and strides.
+#[cfg(not(Py_LIMITED_API))]
+pub unsafe extern "C" fn shaped_releasebuffer<T: PyClassShapedBuffer>(
+    slf: *mut ffi::PyObject,
+    view: *mut ffi::Py_buffer,
+) {
+    crate::callback::handle_panic(|py| {
+        let cell = py.from_borrowed_ptr::<PyCell<T>>(slf);
+        cell.release_leaked_borrow();
+        let internal = (*view).internal as *mut ffi::Py_ssize_t;
+        if !internal.is_null() {
+            let len = 2 * (*view).ndim as usize;
+            drop(Box::from_raw(std::slice::from_raw_parts_mut(internal, len)));
+            (*view).internal = std::ptr::null_mut();
+        }
+        Ok(())
+    })
+}
+
 // Thread checkers
 
 #[doc(hidden)]
@@ -284,3 +537,724 @@ impl<T: PyClass> PyClassBaseType for T {
     type ThreadChecker = T::ThreadChecker;
     type Initializer = crate::pyclass_init::PyClassInitializer<Self>;
 }
+
+/// The return types accepted for the method of `#[pyclass(bytes = method)]`.
+pub trait PyClassBytesResult {
+    fn into_bytes_result(self) -> PyResult<Vec<u8>>;
+}
+
+impl PyClassBytesResult for Vec<u8> {
+    fn into_bytes_result(self) -> PyResult<Vec<u8>> {
+        Ok(self)
+    }
+}
+
+impl<E: Into<PyErr>> PyClassBytesResult for Result<Vec<u8>, E> {
+    fn into_bytes_result(self) -> PyResult<Vec<u8>> {
+        self.map_err(Into::into)
+    }
+}
+
+/// The mutex types accepted for a field with `#[pyo3(get, lock)]`: `std::sync::Mutex` and
+/// `parking_lot::Mutex`, which may also be behind an `Arc`.
+///
+/// The getter first tries to take the lock while holding the GIL. If another thread holds the
+/// lock, the GIL is released while waiting for it, so that a thread which holds the lock and
+/// then waits for the GIL cannot deadlock with the getter.
+pub trait PyClassLockedField {
+    type Value;
+    fn lock_clone(&self, py: Python) -> PyResult<Self::Value>;
+}
+
+impl<T: Clone + Send> PyClassLockedField for std::sync::Mutex<T> {
+    type Value = T;
+
+    fn lock_clone(&self, py: Python) -> PyResult<T> {
+        let value = match self.try_lock() {
+            Ok(guard) => Ok(guard.clone()),
+            Err(std::sync::TryLockError::WouldBlock) => {
+                py.allow_threads(|| self.lock().map(|guard| guard.clone()).map_err(|_| ()))
+            }
+            Err(std::sync::TryLockError::Poisoned(_)) => Err(()),
+        };
+        value.map_err(|_| {
+            PyRuntimeError::new_err("the mutex was poisoned by a thread which panicked")
+        })
+    }
+}
+
+impl<T: Clone + Send> PyClassLockedField for parking_lot::Mutex<T> {
+    type Value = T;
+
+    fn lock_clone(&self, py: Python) -> PyResult<T> {
+        match self.try_lock() {
+            Some(guard) => Ok(guard.clone()),
+            None => Ok(py.allow_threads(|| self.lock().clone())),
+        }
+    }
+}
+
+impl<L: PyClassLockedField + ?Sized> PyClassLockedField for std::sync::Arc<L> {
+    type Value = L::Value;
+
+    fn lock_clone(&self, py: Python) -> PyResult<L::Value> {
+        (**self).lock_clone(py)
+    }
+}
+
+/// Implements the `__array__` generated by `#[pyclass(array = method)]`, converting the `data`
+/// returned by the method with `numpy.asarray`.
+///
+/// numpy derives the shape from the nesting of `data` and the dtype from its elements, unless a
+/// `dtype` is requested. The array never shares memory with the object, so `copy=False` raises
+/// `ValueError` as numpy expects.
+pub fn to_ndarray(
+    py: Python,
+    data: PyObject,
+    dtype: Option<&PyAny>,
+    copy: Option<&PyAny>,
+) -> PyResult<PyObject> {
+    if let Some(copy) = copy {
+        if !copy.is_none() && !copy.is_true()? {
+            return Err(PyValueError::new_err(
+                "unable to avoid a copy while creating an array as requested",
+            ));
+        }
+    }
+    let array = py
+        .import("numpy")?
+        .getattr("asarray")?
+        .call1((data, dtype))?;
+    Ok(array.into())
+}
+
+/// Computes the `__hash__` generated by `#[pyclass(hash)]` or `#[pyclass(hash_fields)]`.
+///
+/// With `#[pyclass(cache_hash)]` the hash is stored in the object the first time and returned
+/// from there afterwards. The GIL serializes access to the cache; if `compute` releases the GIL,
+/// two threads may both compute the hash, which is harmless as they get the same value.
+pub fn cached_hash<T: PyClass>(
+    cell: &PyCell<T>,
+    compute: impl FnOnce() -> PyResult<ffi::Py_hash_t>,
+) -> PyResult<ffi::Py_hash_t> {
+    let cache = cell.hash_cache();
+    if let Some(hash) = cache.get() {
+        return Ok(hash);
+    }
+    let hash = compute()?;
+    cache.set(hash);
+    Ok(hash)
+}
+
+/// The `__hash__` of an object whose `Option` field with `#[pyo3(hash)]` is `None`.
+///
+/// `Some(x)` hashes like `x` alone, so all objects with a `None` key share this hash, and compare
+/// equal to each other. It is the ASCII encoding of `"None"`.
+pub const NONE_KEY_HASH: ffi::Py_hash_t = 0x4e6f_6e65;
+
+/// Implements the `__enter__` generated by `#[pyclass(enter = ..., reentrant)]`.
+///
+/// Only the outermost `with` block calls `acquire`; nested ones return the same resource again and
+/// just increase the depth. The depth is only changed once `acquire` has succeeded.
+pub fn reentrant_enter<T: PyClass>(
+    cell: &PyCell<T>,
+    acquire: impl FnOnce() -> PyResult<PyObject>,
+) -> PyResult<PyObject> {
+    let py = cell.py();
+    let slot = cell.enter_depth();
+    // The resource is only held while the object is entered.
+    if let Some(resource) = slot.resource(py) {
+        slot.set_depth(slot.depth() + 1);
+        return Ok(resource);
+    }
+    let resource = acquire()?;
+    slot.set_resource(Some(resource.clone_ref(py)));
+    slot.set_depth(1);
+    Ok(resource)
+}
+
+/// Implements the `__exit__` generated by `#[pyclass(enter = ..., reentrant)]`.
+///
+/// `release` is only called when the outermost `with` block exits, after the resource has been
+/// dropped from the object. An `__exit__` without a matching `__enter__` raises `RuntimeError`.
+pub fn reentrant_exit<T: PyClass>(
+    cell: &PyCell<T>,
+    release: impl FnOnce() -> PyResult<()>,
+) -> PyResult<()> {
+    let slot = cell.enter_depth();
+    match slot.depth() {
+        0 => Err(PyRuntimeError::new_err(
+            "__exit__ called without a matching __enter__",
+        )),
+        1 => {
+            slot.set_depth(0);
+            slot.set_resource(None);
+            release()
+        }
+        depth => {
+            slot.set_depth(depth - 1);
+            Ok(())
+        }
+    }
+}
+
+/// Converts an integer field of `#[pyo3(get, scale = N)]` to a `decimal.Decimal` with `N` decimal
+/// places, e.g. `1234` to `Decimal('12.34')` for `N = 2`. The conversion is exact.
+pub fn scaled_to_decimal<T: ToPyObject>(py: Python, value: T, scale: u32) -> PyResult<PyObject> {
+    let decimal = py.import("decimal")?.getattr("Decimal")?;
+    let value = decimal.call1((value.to_object(py),))?;
+    let (sign, digits, exponent): (&PyAny, &PyAny, i64) =
+        value.call_method0("as_tuple")?.extract()?;
+    Ok(decimal
+        .call1(((sign, digits, exponent - i64::from(scale)),))?
+        .into())
+}
+
+/// Converts the value assigned to a field with `#[pyo3(set, scale = N)]` to the integer to store,
+/// e.g. `12.345` to `1234` for `N = 2`.
+///
+/// Accepts `int`, `float` and `decimal.Decimal`. The value is scaled exactly and then rounded to
+/// the nearest integer, with ties going to the even integer (`decimal.ROUND_HALF_EVEN`).
+pub fn decimal_to_scaled(value: &PyAny, scale: u32) -> PyResult<&PyAny> {
+    let module = value.py().import("decimal")?;
+    let decimal = module.getattr("Decimal")?;
+    let is_decimal = <PyType as PyTryFrom>::try_from(decimal)?.is_instance(value)?;
+    if !(is_decimal || value.is_instance::<PyLong>()? || value.is_instance::<PyFloat>()?) {
+        return Err(PyDowncastError::new(value, "Decimal, int or float").into());
+    }
+    let value = decimal.call1((value,))?;
+    if !value.call_method0("is_finite")?.is_true()? {
+        return Err(PyValueError::new_err(format!(
+            "cannot store {} in a fixed-point field",
+            value.str()?
+        )));
+    }
+    let (sign, digits, exponent): (&PyAny, &PyAny, i64) =
+        value.call_method0("as_tuple")?.extract()?;
+    let scaled = decimal.call1(((sign, digits, exponent + i64::from(scale)),))?;
+    let rounding = module.getattr("ROUND_HALF_EVEN")?;
+    scaled
+        .call_method1("to_integral_value", (rounding,))?
+        .call_method0("__int__")
+}
+
+/// The instances cached by `#[pyclass(intern = N)]`, keyed by the tuple of constructor arguments.
+///
+/// The cache holds strong references, so cached instances live until the interpreter exits. Once
+/// it holds `N` instances, further instances are created as usual but not cached.
+pub struct InternedInstances(GILOnceCell<Py<PyDict>>);
+
+impl InternedInstances {
+    pub const fn new() -> Self {
+        InternedInstances(GILOnceCell::new())
+    }
+
+    fn dict<'py>(&'py self, py: Python<'py>) -> &'py PyDict {
+        self.0.get_or_init(py, || PyDict::new(py).into()).as_ref(py)
+    }
+
+    /// Returns the cached instance for `key`, or a `TypeError` if `key` is not hashable.
+    pub fn get<'py>(&'py self, py: Python<'py>, key: &PyTuple) -> PyResult<Option<&'py PyAny>> {
+        // `PyDict::get_item` swallows the error of an unhashable key, so check it first.
+        key.hash()?;
+        Ok(self.dict(py).get_item(key))
+    }
+
+    /// Caches `instance` for `key`, unless `capacity` instances are already cached.
+    pub fn insert(
+        &self,
+        py: Python,
+        key: &PyTuple,
+        instance: &PyAny,
+        capacity: usize,
+    ) -> PyResult<()> {
+        let dict = self.dict(py);
+        if dict.len() < capacity {
+            dict.set_item(key, instance)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for InternedInstances {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Encodes a byte array field of `#[pyo3(get, hex)]` as a lowercase hexadecimal string.
+pub fn bytes_to_hex(bytes: &impl AsRef<[u8]>) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let bytes = bytes.as_ref();
+    let mut hex = String::with_capacity(2 * bytes.len());
+    for byte in bytes {
+        hex.push(DIGITS[usize::from(byte >> 4)] as char);
+        hex.push(DIGITS[usize::from(byte & 0xf)] as char);
+    }
+    hex
+}
+
+/// Decodes the hexadecimal `str` assigned to a field with `#[pyo3(set, hex)]` into `bytes`, which
+/// are then extracted as the field type, e.g. `[u8; 32]`.
+///
+/// Upper and lower case digits are accepted. Raises `TypeError` for objects other than `str`, and
+/// `ValueError` for an odd number of digits or a character that is not a hexadecimal digit.
+pub fn hex_to_bytes(value: &PyAny) -> PyResult<&PyAny> {
+    let hex = <PyString as PyTryFrom>::try_from(value)?.to_str()?;
+    if hex.len() % 2 != 0 {
+        return Err(PyValueError::new_err(format!(
+            "hex string must have an even number of digits (got {})",
+            hex.len()
+        )));
+    }
+    fn digit(c: u8) -> Option<u8> {
+        (c as char).to_digit(16).map(|d| d as u8)
+    }
+    let bytes = hex
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| match (digit(pair[0]), digit(pair[1])) {
+            (Some(high), Some(low)) => Ok(high << 4 | low),
+            _ => Err(PyValueError::new_err(format!(
+                "invalid hex string {:?}",
+                hex
+            ))),
+        })
+        .collect::<PyResult<Vec<u8>>>()?;
+    Ok(PyBytes::new(value.py(), &bytes))
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes a byte array field of `#[pyo3(get, base64)]` as standard base64 with padding
+/// (RFC 4648, section 4).
+pub fn bytes_to_base64(bytes: &impl AsRef<[u8]>) -> String {
+    let bytes = bytes.as_ref();
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decodes the base64 `str` assigned to a field with `#[pyo3(set, base64)]` into `bytes`, which
+/// are then extracted as the field type.
+///
+/// Only the padded standard alphabet is accepted, without whitespace. Raises `TypeError` for
+/// objects other than `str`, and `ValueError` for invalid base64.
+pub fn base64_to_bytes(value: &PyAny) -> PyResult<&PyAny> {
+    let encoded = <PyString as PyTryFrom>::try_from(value)?.to_str()?;
+    let invalid = || PyValueError::new_err(format!("invalid base64 string {:?}", encoded));
+    if encoded.len() % 4 != 0 {
+        return Err(invalid());
+    }
+    let num_chunks = encoded.len() / 4;
+    let mut bytes = Vec::with_capacity(num_chunks * 3);
+    for (index, chunk) in encoded.as_bytes().chunks(4).enumerate() {
+        // Up to two `=` may pad the last chunk.
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 || (padding > 0 && index + 1 != num_chunks) {
+            return Err(invalid());
+        }
+        let mut n = 0u32;
+        for &c in &chunk[..4 - padding] {
+            let digit = BASE64_ALPHABET
+                .iter()
+                .position(|&a| a == c)
+                .ok_or_else(invalid)?;
+            n = n << 6 | digit as u32;
+        }
+        n <<= 6 * padding as u32;
+        bytes.extend_from_slice(&[(n >> 16) as u8, (n >> 8) as u8, n as u8][..3 - padding]);
+    }
+    Ok(PyBytes::new(value.py(), &bytes))
+}
+
+/// Converts a byte array field of `#[pyo3(get, as_bytes)]` to `bytes`, rather than to a `list` of
+/// `int` as the `Vec<u8>` conversion would.
+pub fn bytes_to_pybytes<'p>(py: Python<'p>, bytes: &impl AsRef<[u8]>) -> &'p PyBytes {
+    PyBytes::new(py, bytes.as_ref())
+}
+
+/// Checks the value assigned to a field with `#[pyo3(set, as_bytes)]`, returning `bytes` which are
+/// then extracted as the field type.
+///
+/// A `bytearray` is copied into `bytes`. Raises `TypeError` for all other objects, including `str`
+/// and sequences of `int`.
+pub fn bytes_like_to_bytes(value: &PyAny) -> PyResult<&PyAny> {
+    if <PyBytes as PyTryFrom>::try_from(value).is_ok() {
+        Ok(value)
+    } else if let Ok(bytearray) = <PyByteArray as PyTryFrom>::try_from(value) {
+        Ok(PyBytes::new(value.py(), &bytearray.to_vec()))
+    } else {
+        Err(PyDowncastError::new(value, "bytes or bytearray").into())
+    }
+}
+
+/// Copies the `bytes` decoded for a `[u8; N]` field with `#[pyo3(set, hex)]`, `base64` or
+/// `as_bytes` into the array, rather than extracting them one `int` at a time as the generic array
+/// conversion would.
+///
+/// Raises `ValueError` unless there are exactly `N` bytes.
+pub fn copy_bytes_to_array(value: &PyAny, array: &mut [u8]) -> PyResult<()> {
+    let bytes = <PyBytes as PyTryFrom>::try_from(value)?.as_bytes();
+    if bytes.len() != array.len() {
+        return Err(PyValueError::new_err(format!(
+            "expected {} bytes (got {})",
+            array.len(),
+            bytes.len()
+        )));
+    }
+    array.copy_from_slice(bytes);
+    Ok(())
+}
+
+/// Whether the `Ord` result of `#[pyclass(ord)]` satisfies the `tp_richcompare` operator `op`.
+pub fn ordering_matches(ordering: Ordering, op: c_int) -> PyResult<bool> {
+    Ok(match op {
+        ffi::Py_LT => ordering == Ordering::Less,
+        ffi::Py_LE => ordering != Ordering::Greater,
+        ffi::Py_EQ => ordering == Ordering::Equal,
+        ffi::Py_NE => ordering != Ordering::Equal,
+        ffi::Py_GT => ordering == Ordering::Greater,
+        ffi::Py_GE => ordering != Ordering::Less,
+        _ => {
+            return Err(PyValueError::new_err(
+                "tp_richcompare called with invalid comparison operator",
+            ))
+        }
+    })
+}
+
+/// Returns the awaitable of `__anext__` of `#[pyclass(anext = field)]`: an `asyncio` future of the
+/// running event loop, which resolves to the next message of the receiver field of `owner`.
+///
+/// `poll` wraps [`poll_channel`] for the receiver field of the class, and is turned into a Python
+/// function once per class and cached in `poll_function`. It is called once right away; if the
+/// channel is empty, it is called again on the event loop when the receiver wakes its waker, so an
+/// idle `async for` doesn't use the CPU.
+pub fn channel_anext<'py>(
+    owner: &'py PyAny,
+    poll: ffi::PyCFunctionWithKeywords,
+    poll_function: &'static GILOnceCell<PyObject>,
+) -> PyResult<&'py PyAny> {
+    let py = owner.py();
+    let poll = match poll_function.get(py) {
+        Some(function) => function,
+        None => {
+            let function = PyCFunction::new_with_keywords(poll, "poll\0", "\0", py.into())?.into();
+            poll_function.get_or_init(py, || function)
+        }
+    };
+    // `__anext__` is awaited in a coroutine, so there is a running loop. `get_running_loop` is new
+    // in Python 3.7; before that, `get_event_loop` returns the running loop without a warning.
+    #[cfg(Py_3_7)]
+    let get_loop = "get_running_loop";
+    #[cfg(not(Py_3_7))]
+    let get_loop = "get_event_loop";
+    let event_loop = py.import("asyncio")?.getattr(get_loop)?.call0()?;
+    let future = event_loop.call_method0("create_future")?;
+    poll.call1(py, (owner, future, event_loop))?;
+    Ok(future)
+}
+
+/// Polls the receiver of `#[pyclass(anext = field)]` for the future created by [`channel_anext`].
+///
+/// Sets the result of `future` to the next message, or its exception to `StopAsyncIteration` once
+/// the channel is closed. If the channel is empty, the receiver keeps a waker which schedules
+/// `poll(owner, future, event_loop)` on the event loop with `call_soon_threadsafe`. Does nothing if
+/// `future` is already done, e.g. cancelled, or woken by an outdated waker.
+pub fn poll_channel<R: PyChannelReceiver>(
+    receiver: &R,
+    owner: &PyAny,
+    future: &PyAny,
+    event_loop: &PyAny,
+    poll: &PyObject,
+) -> PyResult<()> {
+    let py = owner.py();
+    if future.call_method0("done")?.is_true()? {
+        return Ok(());
+    }
+    let waker = EventLoopWaker::new(EventLoopWake {
+        poll: poll.clone_ref(py),
+        owner: owner.into(),
+        future: future.into(),
+        event_loop: event_loop.into(),
+    });
+    match receiver.poll_recv(&mut Context::from_waker(&waker)) {
+        Poll::Ready(Some(message)) => {
+            future.call_method1("set_result", (message.into_py(py),))?;
+        }
+        Poll::Ready(None) => {
+            let stop = PyStopAsyncIteration::new_err(());
+            future.call_method1("set_exception", (stop.instance(py),))?;
+        }
+        Poll::Pending => {}
+    }
+    Ok(())
+}
+
+/// What the waker of [`poll_channel`] does: call `poll(owner, future, event_loop)` on
+/// `event_loop`.
+struct EventLoopWake {
+    poll: PyObject,
+    owner: PyObject,
+    future: PyObject,
+    event_loop: PyObject,
+}
+
+impl EventLoopWake {
+    fn wake(&self) {
+        Python::with_gil(|py| {
+            let args = (&self.poll, &self.owner, &self.future, &self.event_loop);
+            // Fails only if the event loop is closed, in which case nobody awaits the future.
+            let _ = self
+                .event_loop
+                .call_method1(py, "call_soon_threadsafe", args);
+        })
+    }
+}
+
+/// A `Waker` around an `Arc<EventLoopWake>`.
+///
+/// `EventLoopWake` only holds `Py` references, which are `Send` and `Sync`, so the waker may be
+/// woken and dropped on any thread.
+struct EventLoopWaker;
+
+impl EventLoopWaker {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(
+        Self::clone_raw,
+        Self::wake_raw,
+        Self::wake_by_ref_raw,
+        Self::drop_raw,
+    );
+
+    #[allow(clippy::new_ret_no_self)]
+    fn new(wake: EventLoopWake) -> Waker {
+        let data = Arc::into_raw(Arc::new(wake)) as *const ();
+        unsafe { Waker::from_raw(RawWaker::new(data, &Self::VTABLE)) }
+    }
+
+    unsafe fn clone_raw(data: *const ()) -> RawWaker {
+        let wake = ManuallyDrop::new(Arc::from_raw(data as *const EventLoopWake));
+        let data = Arc::into_raw(Arc::clone(&wake)) as *const ();
+        RawWaker::new(data, &Self::VTABLE)
+    }
+
+    unsafe fn wake_raw(data: *const ()) {
+        Arc::from_raw(data as *const EventLoopWake).wake();
+    }
+
+    unsafe fn wake_by_ref_raw(data: *const ()) {
+        (*(data as *const EventLoopWake)).wake();
+    }
+
+    unsafe fn drop_raw(data: *const ()) {
+        drop(Arc::from_raw(data as *const EventLoopWake));
+    }
+}
+
+/// Converts a possibly negative `index` into a sequence of length `len` to a position, for
+/// `#[pyclass(getitem = field)]` and `#[pyclass(sequence_from = field)]`.
+///
+/// Raises `IndexError` naming both the index and the length if it is out of range.
+pub fn sequence_index(index: isize, len: usize) -> PyResult<usize> {
+    let pos = if index < 0 {
+        index + len as isize
+    } else {
+        index
+    };
+    if pos < 0 || pos as usize >= len {
+        return Err(PyIndexError::new_err(format!(
+            "index {} out of range for sequence of length {}",
+            index, len
+        )));
+    }
+    Ok(pos as usize)
+}
+
+/// Converts the value of a field, as built for its `#[pyo3(get)]` getter, to a `PyObject` for the
+/// `#[pyclass]` options which read the fields, e.g. `to_dict`.
+pub fn getter_object<T>(py: Python, value: T) -> PyResult<PyObject>
+where
+    T: IntoPyCallbackOutput<*mut ffi::PyObject>,
+{
+    let ptr = value.convert(py)?;
+    Ok(unsafe { PyObject::from_owned_ptr(py, ptr) })
+}
+
+/// Extracts the argument `name` of `#[pyclass(positional_new)]` for a field, in the same way as the
+/// field's setter, reporting an error as for any other function argument.
+pub fn extract_field_argument<T>(
+    py: Python,
+    name: &str,
+    extract: impl FnOnce() -> PyResult<T>,
+) -> PyResult<T> {
+    extract().map_err(|e| crate::derive_utils::argument_extraction_error(py, name, e))
+}
+
+/// Converts an `index` passed to the `sq_item` slot of `#[pyclass(sequence_from = field)]` to a
+/// position in a sequence of length `len`.
+///
+/// CPython has already added the length to a negative index, as the class has `sq_length`, so a
+/// negative index is out of range rather than counted from the end a second time.
+pub fn sequence_item_index(index: isize, len: usize) -> PyResult<usize> {
+    if index < 0 || index as usize >= len {
+        return Err(PyIndexError::new_err(format!(
+            "index {} out of range for sequence of length {}",
+            index, len
+        )));
+    }
+    Ok(index as usize)
+}
+
+/// Returns the positions selected by `slice` in a sequence of length `len`, for
+/// `#[pyclass(sequence_from = field)]`.
+pub fn slice_positions(slice: &PySlice, len: usize) -> PyResult<impl Iterator<Item = usize>> {
+    let indices = slice.indices(len as c_long)?;
+    Ok((0..indices.slicelength).map(move |i| (indices.start + i * indices.step) as usize))
+}
+
+/// How many attribute lookups `#[pyclass(proxy = field)]` may forward at once on one thread.
+///
+/// Each forwarded lookup nests native stack frames, so a cycle of proxies must fail before
+/// exhausting the stack rather than when Python's recursion limit is reached.
+const PROXY_DEPTH_LIMIT: usize = 64;
+
+thread_local! {
+    static PROXY_DEPTH: Cell<usize> = Cell::new(0);
+}
+
+/// Looks up the attribute `name` of an instance of `#[pyclass(proxy = field)]`.
+///
+/// Normal attribute lookup on `owner` is tried first, so its own methods and descriptors take
+/// precedence. Only if that raises `AttributeError` is the lookup forwarded to the wrapped object
+/// returned by `target`. A proxy wrapping itself raises the original `AttributeError`, and a
+/// longer cycle of proxies raises `RecursionError`.
+pub fn proxy_getattr(
+    owner: &PyAny,
+    name: &PyAny,
+    target: impl FnOnce() -> PyResult<PyObject>,
+) -> PyResult<PyObject> {
+    let py = owner.py();
+    let err = match unsafe {
+        py.from_owned_ptr_or_err::<PyAny>(ffi::PyObject_GenericGetAttr(
+            owner.as_ptr(),
+            name.as_ptr(),
+        ))
+    } {
+        Ok(value) => return Ok(value.into()),
+        Err(err) => err,
+    };
+    if !err.is_instance::<PyAttributeError>(py) {
+        return Err(err);
+    }
+    let target = target()?;
+    if target.as_ptr() == owner.as_ptr() {
+        return Err(err);
+    }
+    let depth = PROXY_DEPTH.with(Cell::get);
+    if depth >= PROXY_DEPTH_LIMIT {
+        return Err(PyRecursionError::new_err(format!(
+            "maximum proxy depth exceeded while looking up attribute '{}'",
+            name
+        )));
+    }
+    PROXY_DEPTH.with(|d| d.set(depth + 1));
+    let result = target.getattr(py, name);
+    PROXY_DEPTH.with(|d| d.set(depth));
+    result
+}
+
+/// Converts a `FixedBitSet` field of `#[pyo3(get, as_indices)]` to a `frozenset` of the indices
+/// of its set bits.
+#[cfg(feature = "fixedbitset")]
+pub fn bits_to_indices(py: Python, bits: &::fixedbitset::FixedBitSet) -> PyResult<PyObject> {
+    let indices: Vec<usize> = bits.ones().collect();
+    Ok(crate::types::PyFrozenSet::new(py, &indices)?.into())
+}
+
+/// Converts an iterable of indices to the `FixedBitSet` of `#[pyo3(set, as_indices)]`.
+///
+/// The result keeps the length `len` of the current value, growing it to fit larger indices.
+#[cfg(feature = "fixedbitset")]
+pub fn indices_to_bits(value: &PyAny, len: usize) -> PyResult<::fixedbitset::FixedBitSet> {
+    let mut bits = ::fixedbitset::FixedBitSet::with_capacity(len);
+    for index in value.iter()? {
+        let index: usize = index?.extract()?;
+        if index >= bits.len() {
+            bits.grow(index + 1);
+        }
+        bits.insert(index);
+    }
+    Ok(bits)
+}
+
+/// Converts a `VecDeque` field of `#[pyo3(get, as_deque)]` to a `collections.deque`.
+pub fn to_deque<T: ToPyObject>(
+    py: Python,
+    items: &std::collections::VecDeque<T>,
+) -> PyResult<PyObject> {
+    let deque = py.import("collections")?.getattr("deque")?;
+    Ok(deque.call1((items.to_object(py),))?.into())
+}
+
+/// Converts a collection field of `#[pyo3(get, as_frozenset)]` to a `frozenset`.
+pub fn to_frozenset<'a, I, T>(py: Python, items: &'a I) -> PyResult<PyObject>
+where
+    &'a I: IntoIterator<Item = &'a T>,
+    T: ToPyObject + 'a,
+{
+    let items: Vec<PyObject> = items.into_iter().map(|item| item.to_object(py)).collect();
+    Ok(crate::types::PyFrozenSet::new(py, &items)?.into())
+}
+
+/// Formats the `__repr__` of `#[pyclass(repr_debug)]`, which requires the class to implement
+/// `Debug`.
+pub fn debug_repr<T: std::fmt::Debug>(value: &T) -> String {
+    format!("{:?}", value)
+}
+
+/// Appends `name=repr(value)` to the `__repr__` of `#[pyclass(repr)]`, separated by a comma
+/// unless it is the `first` field.
+///
+/// With `#[pyclass(repr_maxlen = N)]`, a repr longer than `N` characters is cut short so that,
+/// including the trailing `...`, it is `N` characters long.
+pub fn push_field_repr(
+    repr: &mut String,
+    first: bool,
+    name: &str,
+    value: &PyAny,
+    maxlen: Option<usize>,
+) -> PyResult<()> {
+    if !first {
+        repr.push_str(", ");
+    }
+    repr.push_str(name);
+    repr.push('=');
+    let value = value.repr()?;
+    let value = value.to_str()?;
+    match maxlen {
+        Some(maxlen) if value.chars().count() > maxlen => {
+            let ellipsis = "...";
+            let keep = maxlen.saturating_sub(ellipsis.len());
+            repr.extend(value.chars().take(keep));
+            repr.push_str(&ellipsis[..maxlen.min(ellipsis.len())]);
+        }
+        _ => repr.push_str(value),
+    }
+    Ok(())
+}
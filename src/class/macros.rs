@@ -270,7 +270,7 @@ macro_rules! py_func_del {
                     let name = py
                         .from_borrowed_ptr::<$crate::types::PyAny>(name)
                         .extract()?;
-                    slf.try_borrow_mut()?.$fn_del(name).convert(py)
+                    slf.try_borrow_mut_checked()?.$fn_del(name).convert(py)
                 } else {
                     Err(exceptions::PyNotImplementedError::new_err(
                         "Subscript assignment not supported",
@@ -355,15 +355,15 @@ macro_rules! call_ref {
     };
 }
 
-/// Call `slf.try_borrow_mut()?.$fn(...)`
+/// Call `slf.try_borrow_mut_checked()?.$fn(...)`
 macro_rules! call_mut {
     ($slf: expr, $fn: ident $(,$raw_args: expr)* $(; $args: expr)*) => {
-        _call_impl!($slf.try_borrow_mut()?, $fn $(,$raw_args)* $(;$args)*)
+        _call_impl!($slf.try_borrow_mut_checked()?, $fn $(,$raw_args)* $(;$args)*)
     };
 }
 
 macro_rules! call_operator_mut {
     ($py:ident, $slf: expr, $fn: ident $(,$raw_args: expr)* $(; $args: expr)*) => {
-        _call_impl!(op $py; $slf.try_borrow_mut()?, $fn $(,$raw_args)* $(;$args)*)
+        _call_impl!(op $py; $slf.try_borrow_mut_checked()?, $fn $(,$raw_args)* $(;$args)*)
     };
 }
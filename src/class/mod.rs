@@ -36,5 +36,5 @@ pub use self::methods::{
     PyClassAttributeDef, PyGetterDef, PyMethodDef, PyMethodDefType, PyMethodType, PySetterDef,
 };
 pub use self::number::PyNumberProtocol;
-pub use self::pyasync::PyAsyncProtocol;
+pub use self::pyasync::{PyAsyncProtocol, PyChannelReceiver};
 pub use self::sequence::PySequenceProtocol;
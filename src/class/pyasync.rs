@@ -12,6 +12,10 @@ use crate::callback::IntoPyCallbackOutput;
 use crate::derive_utils::TryFromPyCell;
 use crate::err::PyResult;
 use crate::{ffi, IntoPy, IntoPyPointer, PyClass, PyObject, Python};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
 
 /// Python Async/Await support interface.
 ///
@@ -140,3 +144,112 @@ where
         }
     }
 }
+
+/// A channel receiver whose messages `#[pyclass(anext = field)]` yields in `async for`.
+///
+/// `poll_recv` must not block: it returns `Poll::Ready(Some(message))` if a message is available,
+/// `Poll::Pending` if the channel is empty and `Poll::Ready(None)` once the channel is closed and
+/// drained, which ends the `async for` loop. As for `futures::Stream::poll_next`, returning
+/// `Poll::Pending` means the receiver has stored the waker of `cx` and will wake it once a message
+/// arrives or the channel closes; `async for` waits for that wake-up without polling again.
+///
+/// [`channel`] creates a channel whose receiver implements this trait.
+pub trait PyChannelReceiver {
+    type Item: IntoPy<PyObject>;
+
+    fn poll_recv(&self, cx: &mut Context) -> Poll<Option<Self::Item>>;
+}
+
+/// Creates an unbounded channel for `#[pyclass(anext = field)]`, like `std::sync::mpsc::channel`.
+///
+/// Sending a message, or dropping the last sender, wakes the `async for` loop waiting on the
+/// receiver, from any thread.
+pub fn channel<T>() -> (ChannelSender<T>, ChannelReceiver<T>) {
+    let shared = Arc::new(Mutex::new(ChannelState {
+        messages: VecDeque::new(),
+        senders: 1,
+        receiver_alive: true,
+        waker: None,
+    }));
+    (ChannelSender(shared.clone()), ChannelReceiver(shared))
+}
+
+struct ChannelState<T> {
+    messages: VecDeque<T>,
+    senders: usize,
+    receiver_alive: bool,
+    waker: Option<Waker>,
+}
+
+/// The sending half of a [`channel`], which can be cloned and sent to other threads.
+pub struct ChannelSender<T>(Arc<Mutex<ChannelState<T>>>);
+
+impl<T> ChannelSender<T> {
+    /// Sends `message`, or returns it if the receiver has been dropped.
+    pub fn send(&self, message: T) -> Result<(), T> {
+        let waker = {
+            let mut state = self.0.lock();
+            if !state.receiver_alive {
+                return Err(message);
+            }
+            state.messages.push_back(message);
+            state.waker.take()
+        };
+        // Woken outside the lock, as the waker may run arbitrary code.
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Ok(())
+    }
+}
+
+impl<T> Clone for ChannelSender<T> {
+    fn clone(&self) -> Self {
+        self.0.lock().senders += 1;
+        ChannelSender(self.0.clone())
+    }
+}
+
+impl<T> Drop for ChannelSender<T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut state = self.0.lock();
+            state.senders -= 1;
+            if state.senders == 0 {
+                state.waker.take()
+            } else {
+                None
+            }
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// The receiving half of a [`channel`].
+pub struct ChannelReceiver<T>(Arc<Mutex<ChannelState<T>>>);
+
+impl<T> Drop for ChannelReceiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.0.lock();
+        state.receiver_alive = false;
+        state.messages.clear();
+    }
+}
+
+impl<T: IntoPy<PyObject>> PyChannelReceiver for ChannelReceiver<T> {
+    type Item = T;
+
+    fn poll_recv(&self, cx: &mut Context) -> Poll<Option<T>> {
+        let mut state = self.0.lock();
+        if let Some(message) = state.messages.pop_front() {
+            Poll::Ready(Some(message))
+        } else if state.senders == 0 {
+            Poll::Ready(None)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
@@ -152,7 +152,7 @@ where
             )));
         }
 
-        let mut slf = slf.try_borrow_mut()?;
+        let mut slf = slf.try_borrow_mut_checked()?;
         let value = py.from_borrowed_ptr::<PyAny>(value);
         let value = value.extract()?;
         crate::callback::convert(py, slf.__setitem__(key.into(), value))
@@ -172,7 +172,7 @@ where
         let slf = py.from_borrowed_ptr::<PyCell<T>>(slf);
 
         if value.is_null() {
-            crate::callback::convert(py, slf.borrow_mut().__delitem__(key.into()))
+            crate::callback::convert(py, slf.try_borrow_mut_checked()?.__delitem__(key.into()))
         } else {
             Err(PyErr::new::<exceptions::PyNotImplementedError, _>(format!(
                 "Item assignment not supported by {:?}",
@@ -198,7 +198,7 @@ where
             call_mut!(slf, __delitem__; key.into()).convert(py)
         } else {
             let value = py.from_borrowed_ptr::<PyAny>(value);
-            let mut slf_ = slf.try_borrow_mut()?;
+            let mut slf_ = slf.try_borrow_mut_checked()?;
             let value = value.extract()?;
             slf_.__setitem__(key.into(), value).convert(py)
         }
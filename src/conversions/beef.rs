@@ -0,0 +1,120 @@
+//! Conversions for the compact copy-on-write pointers [`beef::Cow`](https://docs.rs/beef/0.5/beef/type.Cow.html)
+//! and `beef::lean::Cow`, which convert like `std::borrow::Cow`.
+//!
+//! `Cow<str>` converts to and from Python `str`, and `Cow<[u8]>` to Python `bytes`, accepting
+//! `bytes` and `bytearray` on extraction. Extraction always gives an owned `Cow`, so that any
+//! lifetime can be extracted, e.g. for a `Cow<'static, str>` field of a `#[pyclass]`.
+
+use crate::types::{PyByteArray, PyBytes, PyString};
+use crate::{
+    FromPyObject, IntoPy, PyAny, PyDowncastError, PyObject, PyResult, PyTryFrom, Python, ToPyObject,
+};
+
+macro_rules! beef_cow_impls {
+    ($cow:ident) => {
+        impl ToPyObject for $cow<'_, str> {
+            #[inline]
+            fn to_object(&self, py: Python) -> PyObject {
+                PyString::new(py, self).into()
+            }
+        }
+
+        impl IntoPy<PyObject> for $cow<'_, str> {
+            #[inline]
+            fn into_py(self, py: Python) -> PyObject {
+                self.to_object(py)
+            }
+        }
+
+        impl FromPyObject<'_> for $cow<'_, str> {
+            fn extract(obj: &PyAny) -> PyResult<Self> {
+                <PyString as PyTryFrom>::try_from(obj)?
+                    .to_str()
+                    .map(|s| $cow::owned(s.to_owned()))
+            }
+        }
+
+        impl ToPyObject for $cow<'_, [u8]> {
+            #[inline]
+            fn to_object(&self, py: Python) -> PyObject {
+                PyBytes::new(py, self).into()
+            }
+        }
+
+        impl IntoPy<PyObject> for $cow<'_, [u8]> {
+            #[inline]
+            fn into_py(self, py: Python) -> PyObject {
+                self.to_object(py)
+            }
+        }
+
+        impl FromPyObject<'_> for $cow<'_, [u8]> {
+            fn extract(obj: &PyAny) -> PyResult<Self> {
+                if let Ok(bytes) = <PyBytes as PyTryFrom>::try_from(obj) {
+                    Ok($cow::owned(bytes.as_bytes().to_vec()))
+                } else if let Ok(bytearray) = <PyByteArray as PyTryFrom>::try_from(obj) {
+                    Ok($cow::owned(bytearray.to_vec()))
+                } else {
+                    Err(PyDowncastError::new(obj, "bytes or bytearray").into())
+                }
+            }
+        }
+    };
+}
+
+mod wide {
+    use super::*;
+    use ::beef::Cow;
+
+    beef_cow_impls!(Cow);
+}
+
+// On other targets `beef::lean::Cow` is the same type as `beef::Cow`.
+#[cfg(target_pointer_width = "64")]
+mod lean {
+    use super::*;
+    use ::beef::lean::Cow;
+
+    beef_cow_impls!(Cow);
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::PyBytes;
+    use crate::{Python, ToPyObject};
+
+    #[test]
+    fn test_beef_str_roundtrip() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+
+        let borrowed = beef::Cow::borrowed("café");
+        let obj = borrowed.to_object(py);
+        assert_eq!(obj.extract::<&str>(py).unwrap(), "café");
+        assert_eq!(obj.extract::<beef::Cow<str>>(py).unwrap(), "café");
+        assert_eq!(obj.extract::<beef::lean::Cow<str>>(py).unwrap(), "café");
+
+        let owned: beef::lean::Cow<str> = beef::lean::Cow::owned("owned".to_string());
+        assert_eq!(owned.to_object(py).extract::<String>(py).unwrap(), "owned");
+
+        assert!(42.to_object(py).extract::<beef::Cow<str>>(py).is_err());
+    }
+
+    #[test]
+    fn test_beef_bytes_roundtrip() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+
+        let borrowed = beef::Cow::borrowed(&b"\x00\xff"[..]);
+        let obj = borrowed.to_object(py);
+        assert_eq!(obj.cast_as::<PyBytes>(py).unwrap().as_bytes(), b"\x00\xff");
+        assert_eq!(&*obj.extract::<beef::Cow<[u8]>>(py).unwrap(), b"\x00\xff");
+
+        let bytearray = py.eval("bytearray(b'ok')", None, None).unwrap();
+        assert_eq!(
+            &*bytearray.extract::<beef::lean::Cow<[u8]>>().unwrap(),
+            b"ok"
+        );
+        assert!("ok".to_object(py).extract::<beef::Cow<[u8]>>(py).is_err());
+    }
+}
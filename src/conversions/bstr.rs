@@ -0,0 +1,91 @@
+//! Conversions for [`bstr`](https://docs.rs/bstr/1) byte strings.
+//!
+//! A `BString` is not necessarily valid UTF-8, so it converts to Python `bytes` rather than
+//! `str`. Extraction accepts `bytes` and `bytearray`, and `&BStr` borrows from a `bytes` object
+//! like `&[u8]` does. For a lossy `str` view, expose `BString::to_str_lossy` from a getter.
+
+use crate::types::{PyByteArray, PyBytes};
+use crate::{
+    FromPyObject, IntoPy, PyAny, PyDowncastError, PyObject, PyResult, PyTryFrom, Python, ToPyObject,
+};
+use ::bstr::{BStr, BString};
+
+impl ToPyObject for BStr {
+    fn to_object(&self, py: Python) -> PyObject {
+        PyBytes::new(py, self).into()
+    }
+}
+
+impl<'a> IntoPy<PyObject> for &'a BStr {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl<'a> FromPyObject<'a> for &'a BStr {
+    fn extract(ob: &'a PyAny) -> PyResult<Self> {
+        Ok(BStr::new(<PyBytes as PyTryFrom>::try_from(ob)?.as_bytes()))
+    }
+}
+
+impl ToPyObject for BString {
+    fn to_object(&self, py: Python) -> PyObject {
+        PyBytes::new(py, self).into()
+    }
+}
+
+impl IntoPy<PyObject> for BString {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl FromPyObject<'_> for BString {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        if let Ok(bytes) = <PyBytes as PyTryFrom>::try_from(ob) {
+            Ok(BString::from(bytes.as_bytes()))
+        } else if let Ok(bytearray) = <PyByteArray as PyTryFrom>::try_from(ob) {
+            Ok(BString::from(bytearray.to_vec()))
+        } else {
+            Err(PyDowncastError::new(ob, "bytes or bytearray").into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BStr, BString};
+    use crate::exceptions::PyTypeError;
+    use crate::types::PyBytes;
+    use crate::{IntoPy, PyObject, Python, ToPyObject};
+
+    #[test]
+    fn test_bstring_roundtrip() {
+        Python::with_gil(|py| {
+            let s = BString::from(&b"caf\xe9"[..]);
+            let obj = s.to_object(py);
+            assert_eq!(obj.cast_as::<PyBytes>(py).unwrap().as_bytes(), b"caf\xe9");
+            assert_eq!(obj.extract::<BString>(py).unwrap(), s);
+            assert_eq!(obj.extract::<&BStr>(py).unwrap(), BStr::new(b"caf\xe9"));
+
+            let obj: PyObject = BStr::new("ok").into_py(py);
+            assert_eq!(obj.extract::<BString>(py).unwrap(), "ok");
+        });
+    }
+
+    #[test]
+    fn test_bstring_from_python() {
+        Python::with_gil(|py| {
+            let obj = py.eval("bytearray(b'\\xff\\x00')", None, None).unwrap();
+            assert_eq!(
+                obj.extract::<BString>().unwrap(),
+                BString::from(vec![0xff, 0])
+            );
+            assert!(obj.extract::<&BStr>().is_err());
+
+            let obj = py.eval("'text'", None, None).unwrap();
+            let err = obj.extract::<BString>().unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+}
@@ -0,0 +1,67 @@
+//! Conversions for [`bytes::Bytes`](https://docs.rs/bytes/1/bytes/struct.Bytes.html).
+//!
+//! A Python `bytes` object owns its data, so both directions copy the contents. To share the
+//! memory of a `Bytes` with Python without copying, expose it through the buffer protocol instead
+//! (see the `bytes` section of the features chapter in the guide).
+
+use crate::types::PyBytes;
+use crate::{FromPyObject, IntoPy, PyAny, PyObject, PyResult, Python, ToPyObject};
+use ::bytes::Bytes;
+
+impl ToPyObject for Bytes {
+    fn to_object(&self, py: Python) -> PyObject {
+        PyBytes::new(py, self).into()
+    }
+}
+
+impl IntoPy<PyObject> for Bytes {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl<'source> FromPyObject<'source> for Bytes {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let bytes = <PyBytes as crate::PyTryFrom>::try_from(ob)?;
+        Ok(Bytes::copy_from_slice(bytes.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Bytes;
+    use crate::types::PyBytes;
+    use crate::{IntoPy, PyObject, Python, ToPyObject};
+
+    #[test]
+    fn test_bytes_to_python() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+
+        let bytes = Bytes::from_static(b"Hello Python");
+        let obj = bytes.to_object(py);
+        assert_eq!(
+            obj.cast_as::<PyBytes>(py).unwrap().as_bytes(),
+            b"Hello Python"
+        );
+
+        let obj: PyObject = bytes.into_py(py);
+        assert_eq!(
+            obj.cast_as::<PyBytes>(py).unwrap().as_bytes(),
+            b"Hello Python"
+        );
+    }
+
+    #[test]
+    fn test_bytes_from_python() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+
+        let obj = py.eval("b'Hello Rust'", None, None).unwrap();
+        let bytes: Bytes = obj.extract().unwrap();
+        assert_eq!(bytes, Bytes::from_static(b"Hello Rust"));
+
+        let obj = py.eval("'Hello Rust'", None, None).unwrap();
+        assert!(obj.extract::<Bytes>().is_err());
+    }
+}
@@ -0,0 +1,87 @@
+//! Conversions for the UTF-8 paths of [`camino`](https://docs.rs/camino/1).
+//!
+//! `Utf8Path` and `Utf8PathBuf` convert to Python `str`, like `Path` and `PathBuf`. Extraction
+//! accepts `str` as well as any `os.PathLike` whose path is a `str`, such as `pathlib.Path`.
+//! To hand a `pathlib.Path` to Python, pass the path to `pathlib.Path(...)`.
+
+use crate::types::PyString;
+use crate::{
+    ffi, AsPyPointer, FromPyObject, IntoPy, PyAny, PyNativeType, PyObject, PyResult, PyTryFrom,
+    Python, ToPyObject,
+};
+use ::camino::{Utf8Path, Utf8PathBuf};
+
+impl ToPyObject for Utf8Path {
+    #[inline]
+    fn to_object(&self, py: Python) -> PyObject {
+        PyString::new(py, self.as_str()).into()
+    }
+}
+
+impl<'a> IntoPy<PyObject> for &'a Utf8Path {
+    #[inline]
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl ToPyObject for Utf8PathBuf {
+    #[inline]
+    fn to_object(&self, py: Python) -> PyObject {
+        self.as_path().to_object(py)
+    }
+}
+
+impl IntoPy<PyObject> for Utf8PathBuf {
+    #[inline]
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl FromPyObject<'_> for Utf8PathBuf {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        let py = ob.py();
+        let path: &PyAny = unsafe { py.from_owned_ptr_or_err(ffi::PyOS_FSPath(ob.as_ptr()))? };
+        let path = <PyString as PyTryFrom>::try_from(path)?.to_str()?;
+        Ok(Utf8PathBuf::from(path))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Utf8Path, Utf8PathBuf};
+    use crate::types::PyString;
+    use crate::{IntoPy, PyObject, Python, ToPyObject};
+
+    #[test]
+    fn test_utf8_path_roundtrip() {
+        Python::with_gil(|py| {
+            let path = Utf8Path::new("/tmp/ünïcode/file.txt");
+            let obj = path.to_object(py);
+            assert!(obj.as_ref(py).is_instance::<PyString>().unwrap());
+            assert_eq!(obj.extract::<Utf8PathBuf>(py).unwrap(), path);
+
+            let obj: PyObject = path.to_path_buf().into_py(py);
+            assert_eq!(obj.extract::<&str>(py).unwrap(), "/tmp/ünïcode/file.txt");
+        });
+    }
+
+    #[test]
+    fn test_utf8_path_from_pathlib() {
+        Python::with_gil(|py| {
+            let obj = py
+                .eval("__import__('pathlib').PurePosixPath('a/b.txt')", None, None)
+                .unwrap();
+            assert_eq!(
+                obj.extract::<Utf8PathBuf>().unwrap(),
+                Utf8Path::new("a/b.txt")
+            );
+
+            let obj = py.eval("b'a/b.txt'", None, None).unwrap();
+            assert!(obj.extract::<Utf8PathBuf>().is_err());
+            let obj = py.eval("1", None, None).unwrap();
+            assert!(obj.extract::<Utf8PathBuf>().is_err());
+        });
+    }
+}
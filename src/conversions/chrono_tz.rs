@@ -0,0 +1,78 @@
+//! Conversions for the IANA time zones [`chrono_tz::Tz`](https://docs.rs/chrono-tz/0.10/chrono_tz/enum.Tz.html).
+//!
+//! A `Tz` converts to a `zoneinfo.ZoneInfo` of the same name. `zoneinfo` needs Python 3.9, and
+//! Python's time zone database may lack zones that chrono-tz knows, so where no `ZoneInfo` can be
+//! created the `Tz` converts to its IANA name as a `str` instead. Extraction accepts either of
+//! the two, or any object with a string `key` like `ZoneInfo`, and raises `ValueError` for names
+//! which chrono-tz doesn't know.
+
+use crate::exceptions::PyValueError;
+use crate::types::PyString;
+use crate::{
+    FromPyObject, IntoPy, PyAny, PyDowncastError, PyObject, PyResult, PyTryFrom, Python, ToPyObject,
+};
+use ::chrono_tz::Tz;
+
+impl ToPyObject for Tz {
+    fn to_object(&self, py: Python) -> PyObject {
+        let name = self.name();
+        py.import("zoneinfo")
+            .and_then(|zoneinfo| zoneinfo.getattr("ZoneInfo")?.call1((name,)))
+            .map(PyObject::from)
+            .unwrap_or_else(|_| name.to_object(py))
+    }
+}
+
+impl IntoPy<PyObject> for Tz {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl FromPyObject<'_> for Tz {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        let name = match <PyString as PyTryFrom>::try_from(ob) {
+            Ok(name) => name,
+            Err(_) => ob
+                .getattr("key")
+                .ok()
+                .and_then(|key| <PyString as PyTryFrom>::try_from(key).ok())
+                .ok_or_else(|| PyDowncastError::new(ob, "ZoneInfo or str"))?,
+        };
+        let name = name.to_str()?;
+        name.parse()
+            .map_err(|e| PyValueError::new_err(format!("unknown time zone {:?}: {}", name, e)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Tz;
+    use crate::exceptions::{PyTypeError, PyValueError};
+    use crate::{Python, ToPyObject};
+
+    #[test]
+    fn test_tz_roundtrip() {
+        Python::with_gil(|py| {
+            let obj = Tz::Europe__Amsterdam.to_object(py);
+            assert_eq!(
+                obj.as_ref(py).str().unwrap().to_str().unwrap(),
+                "Europe/Amsterdam"
+            );
+            assert_eq!(obj.extract::<Tz>(py).unwrap(), Tz::Europe__Amsterdam);
+        });
+    }
+
+    #[test]
+    fn test_tz_extract() {
+        Python::with_gil(|py| {
+            let extract = |code: &str| py.eval(code, None, None).unwrap().extract::<Tz>();
+            assert_eq!(extract("'Asia/Tokyo'").unwrap(), Tz::Asia__Tokyo);
+
+            let err = extract("'Mars/Olympus_Mons'").unwrap_err();
+            assert!(err.is_instance::<PyValueError>(py));
+            let err = extract("3600").unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+}
@@ -0,0 +1,69 @@
+//! Conversions for [`compact_str::CompactString`](https://docs.rs/compact_str/0.7/compact_str/struct.CompactString.html),
+//! which convert to and from Python `str` like `String` does.
+
+use crate::types::PyString;
+use crate::{FromPyObject, IntoPy, PyAny, PyObject, PyResult, PyTryFrom, Python, ToPyObject};
+use ::compact_str::CompactString;
+
+impl ToPyObject for CompactString {
+    #[inline]
+    fn to_object(&self, py: Python) -> PyObject {
+        PyString::new(py, self).into()
+    }
+}
+
+impl IntoPy<PyObject> for CompactString {
+    #[inline]
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl IntoPy<PyObject> for &CompactString {
+    #[inline]
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl FromPyObject<'_> for CompactString {
+    fn extract(obj: &PyAny) -> PyResult<Self> {
+        <PyString as PyTryFrom>::try_from(obj)?
+            .to_str()
+            .map(CompactString::new)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CompactString;
+    use crate::{Python, ToPyObject};
+
+    #[test]
+    fn test_compact_str_roundtrip() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+
+        // Inline (up to 24 bytes on 64-bit targets) and heap-allocated strings.
+        for s in &[
+            "",
+            "short",
+            "a string which is too long to be stored inline",
+            "😀 ünicode",
+        ] {
+            let compact = CompactString::new(s);
+            let obj = compact.to_object(py);
+            assert_eq!(obj.extract::<&str>(py).unwrap(), *s);
+            assert_eq!(obj.extract::<CompactString>(py).unwrap(), compact);
+        }
+    }
+
+    #[test]
+    fn test_compact_str_extract_error() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+
+        let obj = 42.to_object(py);
+        assert!(obj.extract::<CompactString>(py).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Conversions for [`either::Either`](https://docs.rs/either/1/either/enum.Either.html).
+//!
+//! An `Either<L, R>` converts to the Python form of whichever value it holds. Extraction tries
+//! `L` first and falls back to `R`, so when a Python value would extract as both, e.g. an `int`
+//! for `Either<i64, f64>`, it becomes a `Left`.
+
+use crate::exceptions::PyTypeError;
+use crate::{FromPyObject, IntoPy, PyAny, PyObject, PyResult, Python, ToPyObject};
+use ::either::Either;
+
+impl<L, R> ToPyObject for Either<L, R>
+where
+    L: ToPyObject,
+    R: ToPyObject,
+{
+    fn to_object(&self, py: Python) -> PyObject {
+        match self {
+            Either::Left(left) => left.to_object(py),
+            Either::Right(right) => right.to_object(py),
+        }
+    }
+}
+
+impl<L, R> IntoPy<PyObject> for Either<L, R>
+where
+    L: IntoPy<PyObject>,
+    R: IntoPy<PyObject>,
+{
+    fn into_py(self, py: Python) -> PyObject {
+        match self {
+            Either::Left(left) => left.into_py(py),
+            Either::Right(right) => right.into_py(py),
+        }
+    }
+}
+
+impl<'source, L, R> FromPyObject<'source> for Either<L, R>
+where
+    L: FromPyObject<'source>,
+    R: FromPyObject<'source>,
+{
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(left) = ob.extract() {
+            Ok(Either::Left(left))
+        } else if let Ok(right) = ob.extract() {
+            Ok(Either::Right(right))
+        } else {
+            Err(PyTypeError::new_err(format!(
+                "'{}' object cannot be converted to either variant",
+                ob.get_type().name()?
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Either;
+    use crate::exceptions::PyTypeError;
+    use crate::{Python, ToPyObject};
+
+    #[test]
+    fn test_either_roundtrip() {
+        Python::with_gil(|py| {
+            let left: Either<i64, String> = Either::Left(42);
+            let obj = left.to_object(py);
+            assert_eq!(obj.extract::<i64>(py).unwrap(), 42);
+            assert_eq!(obj.extract::<Either<i64, String>>(py).unwrap(), left);
+
+            let right: Either<i64, String> = Either::Right("text".to_string());
+            let obj = right.to_object(py);
+            assert_eq!(obj.extract::<String>(py).unwrap(), "text");
+            assert_eq!(obj.extract::<Either<i64, String>>(py).unwrap(), right);
+        });
+    }
+
+    #[test]
+    fn test_either_extract_order() {
+        Python::with_gil(|py| {
+            let obj = py.eval("7", None, None).unwrap();
+            assert_eq!(obj.extract::<Either<i64, f64>>().unwrap(), Either::Left(7));
+            let obj = py.eval("7.5", None, None).unwrap();
+            assert_eq!(
+                obj.extract::<Either<i64, f64>>().unwrap(),
+                Either::Right(7.5)
+            );
+
+            let obj = py.eval("[7]", None, None).unwrap();
+            let err = obj.extract::<Either<i64, f64>>().unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+}
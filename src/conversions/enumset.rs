@@ -0,0 +1,132 @@
+//! Conversions for [`enumset::EnumSet`](https://docs.rs/enumset/1/enumset/struct.EnumSet.html).
+//!
+//! An `EnumSet<T>` converts to a Python `frozenset` of its variants, each converted with `T`'s
+//! own `ToPyObject` implementation. Extraction accepts a `set` or `frozenset` whose elements all
+//! extract as `T`; duplicates collapse as usual.
+
+use crate::types::{PyFrozenSet, PySet};
+use crate::{
+    FromPyObject, IntoPy, PyAny, PyDowncastError, PyObject, PyResult, PyTryFrom, Python, ToPyObject,
+};
+use ::enumset::{EnumSet, EnumSetType};
+
+impl<T> ToPyObject for EnumSet<T>
+where
+    T: EnumSetType + ToPyObject,
+{
+    fn to_object(&self, py: Python) -> PyObject {
+        let elements: Vec<T> = self.iter().collect();
+        PyFrozenSet::new(py, &elements)
+            .expect("Failed to construct frozenset")
+            .into()
+    }
+}
+
+impl<T> IntoPy<PyObject> for EnumSet<T>
+where
+    T: EnumSetType + ToPyObject,
+{
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl<'source, T> FromPyObject<'source> for EnumSet<T>
+where
+    T: EnumSetType + FromPyObject<'source>,
+{
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let mut result = EnumSet::new();
+        if let Ok(set) = <PySet as PyTryFrom>::try_from(ob) {
+            for item in set.iter() {
+                result.insert(item.extract()?);
+            }
+        } else if let Ok(set) = <PyFrozenSet as PyTryFrom>::try_from(ob) {
+            for item in set.iter() {
+                result.insert(item.extract()?);
+            }
+        } else {
+            return Err(PyDowncastError::new(ob, "set or frozenset").into());
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::EnumSet;
+    use crate::exceptions::{PyTypeError, PyValueError};
+    use crate::types::PyDict;
+    use crate::{FromPyObject, PyAny, PyObject, PyResult, Python, ToPyObject};
+    use ::enumset::EnumSetType;
+
+    #[derive(EnumSetType, Debug)]
+    enum Color {
+        Red,
+        Green,
+        Blue,
+    }
+
+    impl ToPyObject for Color {
+        fn to_object(&self, py: Python) -> PyObject {
+            format!("{:?}", self).to_object(py)
+        }
+    }
+
+    impl FromPyObject<'_> for Color {
+        fn extract(ob: &PyAny) -> PyResult<Self> {
+            match ob.extract::<&str>()? {
+                "Red" => Ok(Color::Red),
+                "Green" => Ok(Color::Green),
+                "Blue" => Ok(Color::Blue),
+                other => Err(PyValueError::new_err(format!("unknown color {:?}", other))),
+            }
+        }
+    }
+
+    #[test]
+    fn test_enumset_to_python() {
+        Python::with_gil(|py| {
+            let colors = Color::Red | Color::Blue;
+            let locals = PyDict::new(py);
+            locals.set_item("c", colors.to_object(py)).unwrap();
+            locals
+                .set_item("empty", EnumSet::<Color>::empty().to_object(py))
+                .unwrap();
+            py.run(
+                r#"
+assert c == frozenset({"Red", "Blue"})
+assert type(c) is frozenset
+assert empty == frozenset()
+"#,
+                None,
+                Some(locals),
+            )
+            .unwrap();
+        });
+    }
+
+    #[test]
+    fn test_enumset_from_python() {
+        Python::with_gil(|py| {
+            let obj = py.eval("{'Green', 'Red'}", None, None).unwrap();
+            assert_eq!(
+                obj.extract::<EnumSet<Color>>().unwrap(),
+                Color::Red | Color::Green
+            );
+            let obj = py.eval("frozenset(['Blue'])", None, None).unwrap();
+            assert_eq!(
+                obj.extract::<EnumSet<Color>>().unwrap(),
+                EnumSet::only(Color::Blue)
+            );
+
+            let obj = py.eval("{'Red', 'Purple'}", None, None).unwrap();
+            let err = obj.extract::<EnumSet<Color>>().unwrap_err();
+            assert!(err.is_instance::<PyValueError>(py));
+
+            let obj = py.eval("['Red']", None, None).unwrap();
+            let err = obj.extract::<EnumSet<Color>>().unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+}
@@ -0,0 +1,79 @@
+//! Conversions for the geometry types of [`euclid`](https://docs.rs/euclid/0.22).
+//!
+//! Points, vectors and sizes convert to Python tuples with one element per component, in the
+//! order of their constructors: `Point2D` and `Vector2D` to `(x, y)`, `Point3D` and `Vector3D` to
+//! `(x, y, z)`, `Size2D` to `(width, height)` and `Size3D` to `(width, height, depth)`.
+//! Extraction requires a tuple of the same length. The unit type parameter is not represented
+//! in Python.
+
+use crate::{FromPyObject, IntoPy, PyAny, PyObject, PyResult, Python, ToPyObject};
+use ::euclid::{Point2D, Point3D, Size2D, Size3D, Vector2D, Vector3D};
+
+macro_rules! euclid_impls {
+    ($ty:ident, $($field:ident),+) => {
+        impl<T: ToPyObject, U> ToPyObject for $ty<T, U> {
+            fn to_object(&self, py: Python) -> PyObject {
+                ($(&self.$field,)+).to_object(py)
+            }
+        }
+
+        impl<T: IntoPy<PyObject>, U> IntoPy<PyObject> for $ty<T, U> {
+            fn into_py(self, py: Python) -> PyObject {
+                ($(self.$field,)+).into_py(py)
+            }
+        }
+
+        impl<'source, T: FromPyObject<'source>, U> FromPyObject<'source> for $ty<T, U> {
+            fn extract(ob: &'source PyAny) -> PyResult<Self> {
+                let ($($field,)+) = ob.extract()?;
+                Ok($ty::new($($field),+))
+            }
+        }
+    };
+}
+
+euclid_impls!(Point2D, x, y);
+euclid_impls!(Point3D, x, y, z);
+euclid_impls!(Vector2D, x, y);
+euclid_impls!(Vector3D, x, y, z);
+euclid_impls!(Size2D, width, height);
+euclid_impls!(Size3D, width, height, depth);
+
+#[cfg(test)]
+mod test {
+    use crate::exceptions::{PyTypeError, PyValueError};
+    use crate::{IntoPy, PyObject, Python, ToPyObject};
+    use euclid::default::{Point2D, Size2D, Vector3D};
+
+    #[test]
+    fn test_point_roundtrip() {
+        Python::with_gil(|py| {
+            let point = Point2D::new(1.5, -2.0);
+            let obj = point.to_object(py);
+            assert_eq!(obj.extract::<(f64, f64)>(py).unwrap(), (1.5, -2.0));
+            assert_eq!(obj.extract::<Point2D<f64>>(py).unwrap(), point);
+
+            let obj: PyObject = Vector3D::new(1, 2, 3).into_py(py);
+            assert_eq!(obj.extract::<(i32, i32, i32)>(py).unwrap(), (1, 2, 3));
+            let size: Size2D<u32> = py
+                .eval("(640, 480)", None, None)
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(size, Size2D::new(640, 480));
+        });
+    }
+
+    #[test]
+    fn test_wrong_arity() {
+        Python::with_gil(|py| {
+            let obj = py.eval("(1.0, 2.0, 3.0)", None, None).unwrap();
+            let err = obj.extract::<Point2D<f64>>().unwrap_err();
+            assert!(err.is_instance::<PyValueError>(py));
+
+            let obj = py.eval("[1.0, 2.0]", None, None).unwrap();
+            let err = obj.extract::<Point2D<f64>>().unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+}
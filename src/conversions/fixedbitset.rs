@@ -0,0 +1,85 @@
+//! Conversions for [`fixedbitset::FixedBitSet`](https://docs.rs/fixedbitset/0.5/fixedbitset/struct.FixedBitSet.html).
+//!
+//! A `FixedBitSet` converts to a Python `list` of `bool`, one per bit, so that its length is
+//! preserved. Extraction accepts any sequence of `bool`. To expose a field as the indices of its
+//! set bits instead, use `#[pyo3(get, set, as_indices)]`.
+
+use crate::types::PyList;
+use crate::{FromPyObject, IntoPy, PyAny, PyObject, PyResult, Python, ToPyObject};
+use ::fixedbitset::FixedBitSet;
+
+impl ToPyObject for FixedBitSet {
+    fn to_object(&self, py: Python) -> PyObject {
+        PyList::new(py, (0..self.len()).map(|bit| self.contains(bit))).into()
+    }
+}
+
+impl IntoPy<PyObject> for FixedBitSet {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl FromPyObject<'_> for FixedBitSet {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        let bits: Vec<bool> = ob.extract()?;
+        let mut set = FixedBitSet::with_capacity(bits.len());
+        for (bit, value) in bits.into_iter().enumerate() {
+            set.set(bit, value);
+        }
+        Ok(set)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FixedBitSet;
+    use crate::class::impl_::{bits_to_indices, indices_to_bits};
+    use crate::exceptions::{PyOverflowError, PyTypeError};
+    use crate::types::PyDict;
+    use crate::{Python, ToPyObject};
+
+    #[test]
+    fn test_fixedbitset_roundtrip() {
+        Python::with_gil(|py| {
+            let mut bits = FixedBitSet::with_capacity(4);
+            bits.insert(1);
+            bits.insert(3);
+            let obj = bits.to_object(py);
+            assert_eq!(
+                obj.extract::<Vec<bool>>(py).unwrap(),
+                vec![false, true, false, true]
+            );
+            assert_eq!(obj.extract::<FixedBitSet>(py).unwrap(), bits);
+
+            let obj = py.eval("[1, 0]", None, None).unwrap();
+            let err = obj.extract::<FixedBitSet>().unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+
+    #[test]
+    fn test_fixedbitset_indices() {
+        Python::with_gil(|py| {
+            let bits: FixedBitSet = [2, 5].iter().cloned().collect();
+            let locals = PyDict::new(py);
+            locals
+                .set_item("i", bits_to_indices(py, &bits).unwrap())
+                .unwrap();
+            py.run("assert i == frozenset({2, 5})", None, Some(locals))
+                .unwrap();
+
+            // The length is kept if all indices fit, and grown otherwise.
+            let obj = py.eval("[0, 2]", None, None).unwrap();
+            let set = indices_to_bits(obj, 8).unwrap();
+            assert_eq!(set.len(), 8);
+            assert_eq!(set.ones().collect::<Vec<_>>(), vec![0, 2]);
+            let obj = py.eval("{9}", None, None).unwrap();
+            assert_eq!(indices_to_bits(obj, 8).unwrap().len(), 10);
+
+            let obj = py.eval("{-1}", None, None).unwrap();
+            let err = indices_to_bits(obj, 8).unwrap_err();
+            assert!(err.is_instance::<PyOverflowError>(py));
+        });
+    }
+}
@@ -0,0 +1,115 @@
+//! Conversions for the fixed-capacity [`heapless::Vec`](https://docs.rs/heapless/0.8/heapless/struct.Vec.html)
+//! and [`heapless::String`](https://docs.rs/heapless/0.8/heapless/struct.String.html).
+//!
+//! A `Vec<T, N>` converts like a `std` `Vec`: to a Python `list`, and from any Python sequence. A
+//! `String<N>` converts to and from `str`. Extracting a value which doesn't fit in the capacity `N`
+//! raises `ValueError`; for a `String<N>` the capacity counts UTF-8 bytes, not characters.
+
+use crate::exceptions::PyValueError;
+use crate::types::{PyList, PySequence, PyString};
+use crate::{
+    FromPyObject, IntoPy, PyAny, PyErr, PyObject, PyResult, PyTryFrom, Python, ToPyObject,
+};
+use ::heapless::{String as HeaplessString, Vec as HeaplessVec};
+
+fn capacity_error(len: usize, capacity: usize, unit: &str) -> PyErr {
+    PyValueError::new_err(format!(
+        "{} {} exceed the capacity of {}",
+        len, unit, capacity
+    ))
+}
+
+impl<T: ToPyObject, const N: usize> ToPyObject for HeaplessVec<T, N> {
+    fn to_object(&self, py: Python) -> PyObject {
+        self.as_slice().to_object(py)
+    }
+}
+
+impl<T: IntoPy<PyObject>, const N: usize> IntoPy<PyObject> for HeaplessVec<T, N> {
+    fn into_py(self, py: Python) -> PyObject {
+        let items: Vec<PyObject> = self.into_iter().map(|e| e.into_py(py)).collect();
+        PyList::new(py, items).into()
+    }
+}
+
+impl<'a, T: FromPyObject<'a>, const N: usize> FromPyObject<'a> for HeaplessVec<T, N> {
+    fn extract(obj: &'a PyAny) -> PyResult<Self> {
+        let seq = <PySequence as PyTryFrom>::try_from(obj)?;
+        let len = seq.len()? as usize;
+        if len > N {
+            return Err(capacity_error(len, N, "elements"));
+        }
+        let mut v = HeaplessVec::new();
+        for item in seq.iter()? {
+            // The sequence may have grown since its length was checked.
+            v.push(item?.extract()?)
+                .map_err(|_| capacity_error(N + 1, N, "elements"))?;
+        }
+        Ok(v)
+    }
+}
+
+impl<const N: usize> ToPyObject for HeaplessString<N> {
+    #[inline]
+    fn to_object(&self, py: Python) -> PyObject {
+        PyString::new(py, self).into()
+    }
+}
+
+impl<const N: usize> IntoPy<PyObject> for HeaplessString<N> {
+    #[inline]
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl<const N: usize> FromPyObject<'_> for HeaplessString<N> {
+    fn extract(obj: &PyAny) -> PyResult<Self> {
+        let s = <PyString as PyTryFrom>::try_from(obj)?.to_str()?;
+        let mut string = HeaplessString::new();
+        string
+            .push_str(s)
+            .map_err(|_| capacity_error(s.len(), N, "bytes"))?;
+        Ok(string)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{HeaplessString, HeaplessVec};
+    use crate::exceptions::{PyTypeError, PyValueError};
+    use crate::types::PyList;
+    use crate::{Python, ToPyObject};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_heapless_vec_roundtrip() {
+        Python::with_gil(|py| {
+            let v: HeaplessVec<u32, 4> = HeaplessVec::from_slice(&[1, 2, 3]).unwrap();
+            let obj = v.to_object(py);
+            assert!(obj.as_ref(py).is_instance::<PyList>().unwrap());
+            assert_eq!(obj.extract::<HeaplessVec<u32, 4>>(py).unwrap(), v);
+            assert_eq!(obj.extract::<HeaplessVec<u32, 3>>(py).unwrap(), [1, 2, 3]);
+
+            let err = obj.extract::<HeaplessVec<u32, 2>>(py).unwrap_err();
+            assert!(err.is_instance::<PyValueError>(py));
+            let obj = py.eval("[1, 'two']", None, None).unwrap();
+            let err = obj.extract::<HeaplessVec<u32, 4>>().unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+
+    #[test]
+    fn test_heapless_string_roundtrip() {
+        Python::with_gil(|py| {
+            let s: HeaplessString<8> = HeaplessString::try_from("héllo").unwrap();
+            let obj = s.to_object(py);
+            assert_eq!(obj.extract::<&str>(py).unwrap(), "héllo");
+            assert_eq!(obj.extract::<HeaplessString<6>>(py).unwrap(), s);
+
+            // "é" takes two bytes, so "héllo" doesn't fit in 5.
+            let err = obj.extract::<HeaplessString<5>>(py).unwrap_err();
+            assert!(err.is_instance::<PyValueError>(py));
+        });
+    }
+}
@@ -0,0 +1,81 @@
+//! Conversions for [`indexmap::IndexSet`](https://docs.rs/indexmap/2/indexmap/set/struct.IndexSet.html).
+//!
+//! Python has no ordered set, so an `IndexSet` converts to a `list` in insertion order. Extraction
+//! accepts a `set` or `frozenset`, in their iteration order, or any sequence, whose order is kept
+//! and whose duplicates are dropped after their first occurrence. A `#[pyclass]` field can be
+//! exposed as a `frozenset` instead with `#[pyo3(get, as_frozenset)]`, giving up the order.
+
+use crate::types::{PyFrozenSet, PySet};
+use crate::{FromPyObject, IntoPy, PyAny, PyObject, PyResult, Python, ToPyObject};
+use ::indexmap::IndexSet;
+use std::hash::{BuildHasher, Hash};
+
+impl<T, S> ToPyObject for IndexSet<T, S>
+where
+    T: ToPyObject,
+{
+    fn to_object(&self, py: Python) -> PyObject {
+        self.iter().collect::<Vec<_>>().to_object(py)
+    }
+}
+
+impl<T, S> IntoPy<PyObject> for IndexSet<T, S>
+where
+    T: IntoPy<PyObject>,
+{
+    fn into_py(self, py: Python) -> PyObject {
+        self.into_iter().collect::<Vec<_>>().into_py(py)
+    }
+}
+
+impl<'source, T, S> FromPyObject<'source> for IndexSet<T, S>
+where
+    T: FromPyObject<'source> + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(set) = ob.downcast::<PySet>() {
+            set.iter().map(T::extract).collect()
+        } else if let Ok(set) = ob.downcast::<PyFrozenSet>() {
+            set.iter().map(T::extract).collect()
+        } else {
+            Ok(Vec::<T>::extract(ob)?.into_iter().collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::IndexSet;
+    use crate::exceptions::PyTypeError;
+    use crate::{Python, ToPyObject};
+
+    #[test]
+    fn test_index_set_roundtrip() {
+        Python::with_gil(|py| {
+            let set: IndexSet<&str> = vec!["c", "a", "b"].into_iter().collect();
+            let obj = set.to_object(py);
+            assert_eq!(obj.extract::<Vec<String>>(py).unwrap(), ["c", "a", "b"]);
+            let back: IndexSet<String> = obj.extract(py).unwrap();
+            assert_eq!(back.iter().collect::<Vec<_>>(), ["c", "a", "b"]);
+        });
+    }
+
+    #[test]
+    fn test_index_set_extract() {
+        Python::with_gil(|py| {
+            let extract = |code: &str| {
+                py.eval(code, None, None)
+                    .unwrap()
+                    .extract::<IndexSet<i32>>()
+            };
+            let set = extract("[3, 1, 3, 2, 1]").unwrap();
+            assert_eq!(set.iter().copied().collect::<Vec<_>>(), [3, 1, 2]);
+            assert_eq!(extract("{1, 2}").unwrap().len(), 2);
+            assert_eq!(extract("frozenset([5])").unwrap().len(), 1);
+
+            let err = extract("7").unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+}
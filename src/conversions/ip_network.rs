@@ -0,0 +1,326 @@
+//! Conversions for the network types of [`ipnetwork`](https://docs.rs/ipnetwork/0.20) and the CIDR
+//! types of [`cidr`](https://docs.rs/cidr/0.2).
+//!
+//! Both convert to `ipaddress.IPv4Network` and `ipaddress.IPv6Network`. Extraction accepts those,
+//! as well as strings, which are parsed with `ipaddress.ip_network`. Like Python's `ipaddress`,
+//! parsing is strict: a network with host bits set raises `ValueError`.
+//!
+//! `ipnetwork` allows host bits to be set, e.g. `192.168.1.1/24`; these are cleared on conversion
+//! to Python, which only represents the network itself. `cidr` rejects host bits like Python, so
+//! its conversions are lossless.
+
+use crate::exceptions::PyValueError;
+use crate::once_cell::GILOnceCell;
+use crate::types::{PyString, PyType};
+use crate::{PyAny, PyDowncastError, PyErr, PyNativeType, PyObject, PyResult, PyTryFrom, Python};
+use std::net::IpAddr;
+
+/// `ipaddress.ip_network`, which both creates and parses networks.
+fn ip_network(py: Python) -> PyResult<&PyAny> {
+    static IP_NETWORK: GILOnceCell<PyObject> = GILOnceCell::new();
+    let function = match IP_NETWORK.get(py) {
+        Some(function) => function,
+        None => {
+            let function = py.import("ipaddress")?.getattr("ip_network")?.into();
+            IP_NETWORK.get_or_init(py, || function)
+        }
+    };
+    Ok(function.as_ref(py))
+}
+
+/// Creates an `ipaddress` network from its network address and prefix length.
+///
+/// # Panics
+///
+/// Panics with the Python error if `ipaddress` can't be imported, e.g. during interpreter shutdown,
+/// as `ToPyObject` can't return it.
+fn network_to_py(py: Python, address: IpAddr, prefix: u8) -> PyObject {
+    ip_network(py)
+        .and_then(|ip_network| ip_network.call1(((address.to_string(), prefix),)))
+        .map(Into::into)
+        .unwrap_or_else(|err: PyErr| panic!("failed to create an ipaddress network: {}", err))
+}
+
+/// Extracts the network address and prefix length of an `ipaddress` network or a string.
+fn network_from_py(ob: &PyAny, expected: &'static str) -> PyResult<(IpAddr, u8)> {
+    let py = ob.py();
+    let network = if ob.is_instance::<PyString>()? {
+        ip_network(py)?.call1((ob,))?
+    } else {
+        let ipaddress = py.import("ipaddress")?;
+        let is_network = |name| -> PyResult<bool> {
+            <PyType as PyTryFrom>::try_from(ipaddress.getattr(name)?)?.is_instance(ob)
+        };
+        if is_network("IPv4Network")? || is_network("IPv6Network")? {
+            ob
+        } else {
+            return Err(PyDowncastError::new(ob, expected).into());
+        }
+    };
+    let address = network
+        .getattr("network_address")?
+        .str()?
+        .to_str()?
+        .parse()
+        .map_err(|e| PyValueError::new_err(format!("invalid network address: {}", e)))?;
+    let prefix = network.getattr("prefixlen")?.extract()?;
+    Ok((address, prefix))
+}
+
+#[cfg(feature = "ipnetwork")]
+mod ipnetwork {
+    use super::{network_from_py, network_to_py};
+    use crate::exceptions::PyValueError;
+    use crate::{
+        FromPyObject, IntoPy, PyAny, PyDowncastError, PyObject, PyResult, Python, ToPyObject,
+    };
+    use ::ipnetwork::{IpNetwork, IpNetworkError, Ipv4Network, Ipv6Network};
+    use std::net::IpAddr;
+
+    fn network_error(err: IpNetworkError) -> crate::PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+
+    impl ToPyObject for IpNetwork {
+        fn to_object(&self, py: Python) -> PyObject {
+            network_to_py(py, self.network(), self.prefix())
+        }
+    }
+
+    impl IntoPy<PyObject> for IpNetwork {
+        fn into_py(self, py: Python) -> PyObject {
+            self.to_object(py)
+        }
+    }
+
+    impl FromPyObject<'_> for IpNetwork {
+        fn extract(ob: &PyAny) -> PyResult<Self> {
+            let (address, prefix) = network_from_py(ob, "IPv4Network or IPv6Network")?;
+            IpNetwork::new(address, prefix).map_err(network_error)
+        }
+    }
+
+    impl ToPyObject for Ipv4Network {
+        fn to_object(&self, py: Python) -> PyObject {
+            network_to_py(py, self.network().into(), self.prefix())
+        }
+    }
+
+    impl IntoPy<PyObject> for Ipv4Network {
+        fn into_py(self, py: Python) -> PyObject {
+            self.to_object(py)
+        }
+    }
+
+    impl FromPyObject<'_> for Ipv4Network {
+        fn extract(ob: &PyAny) -> PyResult<Self> {
+            match network_from_py(ob, "IPv4Network")? {
+                (IpAddr::V4(address), prefix) => {
+                    Ipv4Network::new(address, prefix).map_err(network_error)
+                }
+                (IpAddr::V6(_), _) => Err(PyDowncastError::new(ob, "IPv4Network").into()),
+            }
+        }
+    }
+
+    impl ToPyObject for Ipv6Network {
+        fn to_object(&self, py: Python) -> PyObject {
+            network_to_py(py, self.network().into(), self.prefix())
+        }
+    }
+
+    impl IntoPy<PyObject> for Ipv6Network {
+        fn into_py(self, py: Python) -> PyObject {
+            self.to_object(py)
+        }
+    }
+
+    impl FromPyObject<'_> for Ipv6Network {
+        fn extract(ob: &PyAny) -> PyResult<Self> {
+            match network_from_py(ob, "IPv6Network")? {
+                (IpAddr::V6(address), prefix) => {
+                    Ipv6Network::new(address, prefix).map_err(network_error)
+                }
+                (IpAddr::V4(_), _) => Err(PyDowncastError::new(ob, "IPv6Network").into()),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::{IpNetwork, Ipv4Network, Ipv6Network};
+        use crate::exceptions::{PyTypeError, PyValueError};
+        use crate::{Python, ToPyObject};
+
+        #[test]
+        fn test_ipnetwork_roundtrip() {
+            Python::with_gil(|py| {
+                let v4: IpNetwork = "10.1.0.0/16".parse().unwrap();
+                let obj = v4.to_object(py);
+                assert_eq!(
+                    obj.as_ref(py).repr().unwrap().to_str().unwrap(),
+                    "IPv4Network('10.1.0.0/16')"
+                );
+                assert_eq!(obj.extract::<IpNetwork>(py).unwrap(), v4);
+
+                let v6: Ipv6Network = "2001:db8::/32".parse().unwrap();
+                let obj = v6.to_object(py);
+                assert_eq!(
+                    obj.as_ref(py).repr().unwrap().to_str().unwrap(),
+                    "IPv6Network('2001:db8::/32')"
+                );
+                assert_eq!(obj.extract::<Ipv6Network>(py).unwrap(), v6);
+                assert!(obj.extract::<Ipv4Network>(py).is_err());
+
+                // Host bits are cleared on conversion to Python.
+                let host: Ipv4Network = "192.168.1.1/24".parse().unwrap();
+                let obj = host.to_object(py);
+                assert_eq!(
+                    obj.extract::<Ipv4Network>(py).unwrap(),
+                    "192.168.1.0/24".parse::<Ipv4Network>().unwrap()
+                );
+            });
+        }
+
+        #[test]
+        fn test_ipnetwork_from_str() {
+            Python::with_gil(|py| {
+                let obj = py.eval("'172.16.0.0/12'", None, None).unwrap();
+                assert_eq!(
+                    obj.extract::<Ipv4Network>().unwrap(),
+                    "172.16.0.0/12".parse::<Ipv4Network>().unwrap()
+                );
+
+                let obj = py.eval("'172.16.0.1/12'", None, None).unwrap();
+                let err = obj.extract::<IpNetwork>().unwrap_err();
+                assert!(err.is_instance::<PyValueError>(py));
+
+                let obj = py.eval("42", None, None).unwrap();
+                let err = obj.extract::<IpNetwork>().unwrap_err();
+                assert!(err.is_instance::<PyTypeError>(py));
+            });
+        }
+    }
+}
+
+#[cfg(feature = "cidr")]
+mod cidr {
+    use super::{network_from_py, network_to_py};
+    use crate::exceptions::PyValueError;
+    use crate::{
+        FromPyObject, IntoPy, PyAny, PyDowncastError, PyObject, PyResult, Python, ToPyObject,
+    };
+    use ::cidr::errors::NetworkParseError;
+    use ::cidr::{IpCidr, Ipv4Cidr, Ipv6Cidr};
+    use std::net::IpAddr;
+
+    fn cidr_error(err: NetworkParseError) -> crate::PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+
+    impl ToPyObject for IpCidr {
+        fn to_object(&self, py: Python) -> PyObject {
+            network_to_py(py, self.first_address(), self.network_length())
+        }
+    }
+
+    impl IntoPy<PyObject> for IpCidr {
+        fn into_py(self, py: Python) -> PyObject {
+            self.to_object(py)
+        }
+    }
+
+    impl FromPyObject<'_> for IpCidr {
+        fn extract(ob: &PyAny) -> PyResult<Self> {
+            let (address, prefix) = network_from_py(ob, "IPv4Network or IPv6Network")?;
+            IpCidr::new(address, prefix).map_err(cidr_error)
+        }
+    }
+
+    impl ToPyObject for Ipv4Cidr {
+        fn to_object(&self, py: Python) -> PyObject {
+            network_to_py(py, self.first_address().into(), self.network_length())
+        }
+    }
+
+    impl IntoPy<PyObject> for Ipv4Cidr {
+        fn into_py(self, py: Python) -> PyObject {
+            self.to_object(py)
+        }
+    }
+
+    impl FromPyObject<'_> for Ipv4Cidr {
+        fn extract(ob: &PyAny) -> PyResult<Self> {
+            match network_from_py(ob, "IPv4Network")? {
+                (IpAddr::V4(address), prefix) => Ipv4Cidr::new(address, prefix).map_err(cidr_error),
+                (IpAddr::V6(_), _) => Err(PyDowncastError::new(ob, "IPv4Network").into()),
+            }
+        }
+    }
+
+    impl ToPyObject for Ipv6Cidr {
+        fn to_object(&self, py: Python) -> PyObject {
+            network_to_py(py, self.first_address().into(), self.network_length())
+        }
+    }
+
+    impl IntoPy<PyObject> for Ipv6Cidr {
+        fn into_py(self, py: Python) -> PyObject {
+            self.to_object(py)
+        }
+    }
+
+    impl FromPyObject<'_> for Ipv6Cidr {
+        fn extract(ob: &PyAny) -> PyResult<Self> {
+            match network_from_py(ob, "IPv6Network")? {
+                (IpAddr::V6(address), prefix) => Ipv6Cidr::new(address, prefix).map_err(cidr_error),
+                (IpAddr::V4(_), _) => Err(PyDowncastError::new(ob, "IPv6Network").into()),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::{IpCidr, Ipv4Cidr, Ipv6Cidr};
+        use crate::exceptions::{PyTypeError, PyValueError};
+        use crate::{Python, ToPyObject};
+
+        #[test]
+        fn test_cidr_roundtrip() {
+            Python::with_gil(|py| {
+                let v4: Ipv4Cidr = "10.1.0.0/16".parse().unwrap();
+                let obj = v4.to_object(py);
+                assert_eq!(
+                    obj.as_ref(py).repr().unwrap().to_str().unwrap(),
+                    "IPv4Network('10.1.0.0/16')"
+                );
+                assert_eq!(obj.extract::<Ipv4Cidr>(py).unwrap(), v4);
+
+                let v6: IpCidr = "2001:db8::/32".parse().unwrap();
+                let obj = v6.to_object(py);
+                assert_eq!(
+                    obj.as_ref(py).repr().unwrap().to_str().unwrap(),
+                    "IPv6Network('2001:db8::/32')"
+                );
+                assert_eq!(obj.extract::<IpCidr>(py).unwrap(), v6);
+                let err = obj.extract::<Ipv4Cidr>(py).unwrap_err();
+                assert!(err.is_instance::<PyTypeError>(py));
+            });
+        }
+
+        #[test]
+        fn test_cidr_from_str() {
+            Python::with_gil(|py| {
+                let obj = py.eval("'fd00::/8'", None, None).unwrap();
+                assert_eq!(
+                    obj.extract::<Ipv6Cidr>().unwrap(),
+                    "fd00::/8".parse::<Ipv6Cidr>().unwrap()
+                );
+
+                let obj = py.eval("'fd00::1/8'", None, None).unwrap();
+                let err = obj.extract::<IpCidr>().unwrap_err();
+                assert!(err.is_instance::<PyValueError>(py));
+            });
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Conversions for the [`jiff`](https://docs.rs/jiff/0.2) date and time library.
+//!
+//! `Timestamp` converts to an aware `datetime.datetime` in UTC, and `Zoned` to an aware
+//! `datetime.datetime` in its own time zone. The time zone of a `Zoned` becomes a
+//! `zoneinfo.ZoneInfo` if it has an IANA name that Python knows, and a fixed-offset
+//! `datetime.timezone` otherwise. In the other direction, a `ZoneInfo` is looked up by its key in
+//! jiff's time zone database, and any other `tzinfo` becomes a fixed offset.
+//!
+//! Python datetimes have microsecond precision, so sub-microsecond digits are truncated.
+
+use super::systemtime::{datetime_to_epoch_micros, is_datetime, unix_epoch};
+use crate::exceptions::{PyOverflowError, PyValueError};
+use crate::types::PyString;
+use crate::{
+    FromPyObject, IntoPy, PyAny, PyDowncastError, PyErr, PyObject, PyResult, PyTryFrom, Python,
+    ToPyObject,
+};
+use ::jiff::tz::{Offset, TimeZone};
+use ::jiff::{Timestamp, Zoned};
+use std::convert::TryFrom;
+
+fn timestamp_to_datetime(py: Python, timestamp: Timestamp) -> PyResult<&PyAny> {
+    let timedelta =
+        py.import("datetime")?
+            .getattr("timedelta")?
+            .call1((0, 0, timestamp.as_microsecond()))?;
+    unix_epoch(py)?.call_method1("__add__", (timedelta,))
+}
+
+fn datetime_to_timestamp(ob: &PyAny, target: &str) -> PyResult<Timestamp> {
+    if !is_datetime(ob)? {
+        return Err(PyDowncastError::new(ob, "datetime").into());
+    }
+    let micros = datetime_to_epoch_micros(ob, target)?;
+    i64::try_from(micros)
+        .ok()
+        .and_then(|micros| Timestamp::from_microsecond(micros).ok())
+        .ok_or_else(|| PyOverflowError::new_err("datetime out of range for jiff::Timestamp"))
+}
+
+fn time_zone_to_tzinfo<'py>(py: Python<'py>, zoned: &Zoned) -> PyResult<&'py PyAny> {
+    if let Some(name) = zoned.time_zone().iana_name() {
+        // `zoneinfo` needs Python 3.9, and may not know every zone that jiff knows.
+        let tzinfo = py
+            .import("zoneinfo")
+            .and_then(|zoneinfo| zoneinfo.getattr("ZoneInfo")?.call1((name,)));
+        if let Ok(tzinfo) = tzinfo {
+            return Ok(tzinfo);
+        }
+    }
+    let datetime = py.import("datetime")?;
+    let offset = datetime
+        .getattr("timedelta")?
+        .call1((0, zoned.offset().seconds()))?;
+    datetime.getattr("timezone")?.call1((offset,))
+}
+
+fn tzinfo_to_time_zone(ob: &PyAny) -> PyResult<TimeZone> {
+    let tzinfo = ob.getattr("tzinfo")?;
+    if let Ok(key) = tzinfo.getattr("key") {
+        if let Ok(key) = <PyString as PyTryFrom>::try_from(key) {
+            let key = key.to_str()?;
+            return TimeZone::get(key)
+                .map_err(|e| PyValueError::new_err(format!("unknown time zone {:?}: {}", key, e)));
+        }
+    }
+    let delta = ob.call_method0("utcoffset")?;
+    let days: i32 = delta.getattr("days")?.extract()?;
+    let seconds: i32 = delta.getattr("seconds")?.extract()?;
+    let offset = Offset::from_seconds(days * 24 * 60 * 60 + seconds)
+        .map_err(|e| PyValueError::new_err(format!("invalid UTC offset: {}", e)))?;
+    Ok(TimeZone::fixed(offset))
+}
+
+impl ToPyObject for Timestamp {
+    /// Converts to an aware `datetime.datetime` in UTC.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the timestamp is outside the range of `datetime.datetime` (years 1 to 9999).
+    fn to_object(&self, py: Python) -> PyObject {
+        timestamp_to_datetime(py, *self)
+            .map(Into::into)
+            .unwrap_or_else(|err: PyErr| panic!("Timestamp out of range for datetime: {}", err))
+    }
+}
+
+impl IntoPy<PyObject> for Timestamp {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl FromPyObject<'_> for Timestamp {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        datetime_to_timestamp(ob, "Timestamp")
+    }
+}
+
+impl ToPyObject for Zoned {
+    /// Converts to an aware `datetime.datetime` in the same time zone.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the time is outside the range of `datetime.datetime` (years 1 to 9999).
+    fn to_object(&self, py: Python) -> PyObject {
+        timestamp_to_datetime(py, self.timestamp())
+            .and_then(|utc| utc.call_method1("astimezone", (time_zone_to_tzinfo(py, self)?,)))
+            .map(Into::into)
+            .unwrap_or_else(|err: PyErr| panic!("Zoned out of range for datetime: {}", err))
+    }
+}
+
+impl IntoPy<PyObject> for Zoned {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl FromPyObject<'_> for Zoned {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        let timestamp = datetime_to_timestamp(ob, "Zoned")?;
+        Ok(timestamp.to_zoned(tzinfo_to_time_zone(ob)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Timestamp, Zoned};
+    use crate::exceptions::{PyTypeError, PyValueError};
+    use crate::types::PyDict;
+    use crate::{Python, ToPyObject};
+
+    #[test]
+    fn test_timestamp_roundtrip() {
+        Python::with_gil(|py| {
+            let timestamp: Timestamp = "2024-03-10T12:30:45.123456789Z".parse().unwrap();
+            let obj = timestamp.to_object(py);
+            let locals = PyDict::new(py);
+            locals.set_item("t", &obj).unwrap();
+            py.run(
+                r#"
+import datetime
+assert t == datetime.datetime(2024, 3, 10, 12, 30, 45, 123456, tzinfo=datetime.timezone.utc)
+"#,
+                None,
+                Some(locals),
+            )
+            .unwrap();
+            let back: Timestamp = obj.extract(py).unwrap();
+            assert_eq!(back, "2024-03-10T12:30:45.123456Z".parse().unwrap());
+
+            let obj = py
+                .eval("__import__('datetime').datetime(2024, 1, 1)", None, None)
+                .unwrap();
+            let err = obj.extract::<Timestamp>().unwrap_err();
+            assert!(err.is_instance::<PyValueError>(py));
+            let obj = py.eval("1700000000", None, None).unwrap();
+            let err = obj.extract::<Timestamp>().unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+
+    #[test]
+    fn test_zoned_roundtrip() {
+        Python::with_gil(|py| {
+            let zoned: Zoned = "2024-07-01T09:15:00+02:00[Europe/Paris]".parse().unwrap();
+            let obj = zoned.to_object(py);
+            let locals = PyDict::new(py);
+            locals.set_item("z", &obj).unwrap();
+            py.run(
+                r#"
+import datetime
+assert z.utcoffset() == datetime.timedelta(hours=2)
+assert (z.hour, z.minute) == (9, 15)
+assert str(z.tzinfo) == "Europe/Paris"
+"#,
+                None,
+                Some(locals),
+            )
+            .unwrap();
+            let back: Zoned = obj.extract(py).unwrap();
+            assert_eq!(back, zoned);
+            assert_eq!(back.time_zone().iana_name(), Some("Europe/Paris"));
+        });
+    }
+
+    #[test]
+    fn test_zoned_fixed_offset() {
+        Python::with_gil(|py| {
+            let obj = py
+                .eval(
+                    "__import__('datetime').datetime.fromisoformat('2024-01-01T08:00:00-05:00')",
+                    None,
+                    None,
+                )
+                .unwrap();
+            let zoned: Zoned = obj.extract().unwrap();
+            assert_eq!(zoned.offset().seconds(), -5 * 60 * 60);
+            assert_eq!(zoned.time_zone().iana_name(), None);
+            assert_eq!(zoned.hour(), 8);
+
+            let locals = PyDict::new(py);
+            locals.set_item("z", zoned.to_object(py)).unwrap();
+            py.run(
+                "assert z.utcoffset() == __import__('datetime').timedelta(hours=-5)",
+                None,
+                Some(locals),
+            )
+            .unwrap();
+        });
+    }
+}
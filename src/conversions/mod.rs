@@ -1,5 +1,51 @@
 //! This module contains conversions between various Rust object and their representation in Python.
 
 mod array;
+#[cfg(feature = "beef")]
+mod beef;
+#[cfg(feature = "bstr")]
+mod bstr;
+#[cfg(feature = "bytes")]
+mod bytes;
+#[cfg(feature = "camino")]
+mod camino;
+#[cfg(feature = "chrono-tz")]
+mod chrono_tz;
+#[cfg(feature = "compact_str")]
+mod compact_str;
+#[cfg(feature = "either")]
+mod either;
+#[cfg(feature = "enumset")]
+mod enumset;
+#[cfg(feature = "euclid")]
+mod euclid;
+#[cfg(feature = "fixedbitset")]
+mod fixedbitset;
+#[cfg(feature = "heapless")]
+mod heapless;
+#[cfg(feature = "indexmap")]
+mod indexmap;
+#[cfg(any(feature = "ipnetwork", feature = "cidr"))]
+mod ip_network;
+#[cfg(feature = "jiff")]
+mod jiff;
+#[cfg(feature = "nonmax")]
+mod nonmax;
 mod osstr;
 mod path;
+mod range;
+#[cfg(feature = "semver")]
+mod semver;
+#[cfg(feature = "serde_bytes")]
+mod serde_bytes;
+mod systemtime;
+#[cfg(feature = "time")]
+mod time;
+#[cfg(feature = "tinyvec")]
+mod tinyvec;
+#[cfg(feature = "typed-index-collections")]
+mod typed_index_collections;
+#[cfg(feature = "url")]
+mod url;
+mod vec_deque;
+mod wrapping;
@@ -0,0 +1,96 @@
+//! Conversions for the integer types of [`nonmax`](https://docs.rs/nonmax/0.5), such as
+//! `NonMaxU32`.
+//!
+//! They convert to a Python `int` holding the inner value. Extracting the maximum value of the
+//! inner type, which a `NonMax*` cannot hold, raises `ValueError`; other values out of range for
+//! the inner type raise `OverflowError` as for the plain integer types.
+
+use crate::exceptions::PyValueError;
+use crate::{FromPyObject, IntoPy, PyAny, PyObject, PyResult, Python, ToPyObject};
+use ::nonmax::{
+    NonMaxI128, NonMaxI16, NonMaxI32, NonMaxI64, NonMaxI8, NonMaxIsize, NonMaxU128, NonMaxU16,
+    NonMaxU32, NonMaxU64, NonMaxU8, NonMaxUsize,
+};
+
+macro_rules! nonmax_conversion {
+    ($nonmax:ident, $int:ty) => {
+        impl ToPyObject for $nonmax {
+            fn to_object(&self, py: Python) -> PyObject {
+                self.get().to_object(py)
+            }
+        }
+
+        impl IntoPy<PyObject> for $nonmax {
+            fn into_py(self, py: Python) -> PyObject {
+                self.get().into_py(py)
+            }
+        }
+
+        impl FromPyObject<'_> for $nonmax {
+            fn extract(ob: &PyAny) -> PyResult<Self> {
+                let value: $int = ob.extract()?;
+                $nonmax::new(value).ok_or_else(|| {
+                    PyValueError::new_err(format!(
+                        "{} is the maximum of {} and cannot be stored in {}",
+                        value,
+                        stringify!($int),
+                        stringify!($nonmax)
+                    ))
+                })
+            }
+        }
+    };
+}
+
+nonmax_conversion!(NonMaxI8, i8);
+nonmax_conversion!(NonMaxU8, u8);
+nonmax_conversion!(NonMaxI16, i16);
+nonmax_conversion!(NonMaxU16, u16);
+nonmax_conversion!(NonMaxI32, i32);
+nonmax_conversion!(NonMaxU32, u32);
+nonmax_conversion!(NonMaxI64, i64);
+nonmax_conversion!(NonMaxU64, u64);
+nonmax_conversion!(NonMaxI128, i128);
+nonmax_conversion!(NonMaxU128, u128);
+nonmax_conversion!(NonMaxIsize, isize);
+nonmax_conversion!(NonMaxUsize, usize);
+
+#[cfg(test)]
+mod test {
+    use super::{NonMaxI8, NonMaxU32};
+    use crate::exceptions::{PyOverflowError, PyValueError};
+    use crate::{Python, ToPyObject};
+
+    #[test]
+    fn test_nonmax_roundtrip() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+
+        let value = NonMaxU32::new(42).unwrap();
+        let obj = value.to_object(py);
+        assert_eq!(obj.extract::<u32>(py).unwrap(), 42);
+        assert_eq!(obj.extract::<NonMaxU32>(py).unwrap(), value);
+
+        let value = NonMaxI8::new(-128).unwrap();
+        assert_eq!(value.to_object(py).extract::<NonMaxI8>(py).unwrap(), value);
+    }
+
+    #[test]
+    fn test_nonmax_extract_error() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+
+        let err = std::u32::MAX
+            .to_object(py)
+            .extract::<NonMaxU32>(py)
+            .unwrap_err();
+        assert!(err.is_instance::<PyValueError>(py));
+        assert_eq!(
+            err.to_string(),
+            "ValueError: 4294967295 is the maximum of u32 and cannot be stored in NonMaxU32"
+        );
+
+        let err = (-1).to_object(py).extract::<NonMaxU32>(py).unwrap_err();
+        assert!(err.is_instance::<PyOverflowError>(py));
+    }
+}
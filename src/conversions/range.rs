@@ -0,0 +1,181 @@
+//! Conversions for the integer ranges [`std::ops::Range`] and [`std::ops::RangeInclusive`].
+//!
+//! Both convert to a Python `range` with a step of 1, whose stop is one past the end of a
+//! `RangeInclusive`. Extraction accepts a `range`, or a `slice` with both a start and a stop. Rust
+//! ranges always step by 1, so a `range` or `slice` with any other step raises `ValueError` rather
+//! than silently losing elements. A slice step of `None` means 1. An empty `range` extracts as the
+//! `RangeInclusive` `1..=0`, since its own bounds may not fit, as for `range(0, 0)` and `u32`.
+
+use crate::err::PyDowncastError;
+use crate::exceptions::PyValueError;
+use crate::types::{PySlice, PyType};
+use crate::{
+    ffi, AsPyPointer, FromPyObject, IntoPy, PyAny, PyNativeType, PyObject, PyResult, PyTypeInfo,
+    Python, ToPyObject,
+};
+use std::cmp::Ordering;
+use std::ops::{Range, RangeInclusive};
+
+fn new_range(py: Python, start: PyObject, stop: &PyAny) -> PyObject {
+    let range = unsafe { PyType::from_type_ptr(py, &mut ffi::PyRange_Type) };
+    range
+        .call1((start, stop))
+        .expect("failed to create a range")
+        .into()
+}
+
+/// Adds `delta` to the Python integer `ob`, without overflowing the Rust integer type.
+fn offset(ob: &PyAny, delta: i32) -> PyResult<&PyAny> {
+    let py = ob.py();
+    let delta = delta.to_object(py);
+    unsafe { py.from_owned_ptr_or_err(ffi::PyNumber_Add(ob.as_ptr(), delta.as_ptr())) }
+}
+
+/// Returns the start and the exclusive stop of a `range` or `slice` with a step of 1.
+fn range_bounds(ob: &PyAny) -> PyResult<(&PyAny, &PyAny)> {
+    let is_range = unsafe { ffi::PyRange_Check(ob.as_ptr()) } != 0;
+    if !is_range && !PySlice::is_type_of(ob) {
+        return Err(PyDowncastError::new(ob, "range or slice").into());
+    }
+    let step = ob.getattr("step")?;
+    if !step.is_none() && step.compare(1)? != Ordering::Equal {
+        return Err(PyValueError::new_err(format!(
+            "cannot convert a step of {} to a Rust range, which always steps by 1",
+            step
+        )));
+    }
+    let start = ob.getattr("start")?;
+    let stop = ob.getattr("stop")?;
+    if start.is_none() || stop.is_none() {
+        return Err(PyValueError::new_err(
+            "a slice needs both a start and a stop to be converted to a Rust range",
+        ));
+    }
+    Ok((start, stop))
+}
+
+macro_rules! range_conversion {
+    ($int:ty) => {
+        impl ToPyObject for Range<$int> {
+            fn to_object(&self, py: Python) -> PyObject {
+                new_range(
+                    py,
+                    self.start.to_object(py),
+                    self.end.to_object(py).as_ref(py),
+                )
+            }
+        }
+
+        impl IntoPy<PyObject> for Range<$int> {
+            fn into_py(self, py: Python) -> PyObject {
+                self.to_object(py)
+            }
+        }
+
+        impl FromPyObject<'_> for Range<$int> {
+            fn extract(ob: &PyAny) -> PyResult<Self> {
+                let (start, stop) = range_bounds(ob)?;
+                Ok(start.extract()?..stop.extract()?)
+            }
+        }
+
+        impl ToPyObject for RangeInclusive<$int> {
+            fn to_object(&self, py: Python) -> PyObject {
+                let end = self.end().to_object(py);
+                let stop = offset(end.as_ref(py), 1).expect("failed to compute the range stop");
+                new_range(py, self.start().to_object(py), stop)
+            }
+        }
+
+        impl IntoPy<PyObject> for RangeInclusive<$int> {
+            fn into_py(self, py: Python) -> PyObject {
+                self.to_object(py)
+            }
+        }
+
+        impl FromPyObject<'_> for RangeInclusive<$int> {
+            fn extract(ob: &PyAny) -> PyResult<Self> {
+                let (start, stop) = range_bounds(ob)?;
+                if stop.compare(start)? != Ordering::Greater {
+                    // An empty Python range has no last element; `1..=0` is an empty
+                    // `RangeInclusive` for every integer type.
+                    return Ok(RangeInclusive::new(1, 0));
+                }
+                Ok(start.extract()?..=offset(stop, -1)?.extract()?)
+            }
+        }
+    };
+}
+
+range_conversion!(i8);
+range_conversion!(u8);
+range_conversion!(i16);
+range_conversion!(u16);
+range_conversion!(i32);
+range_conversion!(u32);
+range_conversion!(i64);
+range_conversion!(u64);
+range_conversion!(i128);
+range_conversion!(u128);
+range_conversion!(isize);
+range_conversion!(usize);
+
+#[cfg(test)]
+mod test {
+    use crate::exceptions::{PyTypeError, PyValueError};
+    use crate::{Python, ToPyObject};
+    use std::ops::{Range, RangeInclusive};
+
+    #[test]
+    fn test_range_roundtrip() {
+        Python::with_gil(|py| {
+            let obj = (2u32..5).to_object(py);
+            assert_eq!(obj.as_ref(py).get_type().name().unwrap(), "range");
+            assert_eq!(obj.extract::<Vec<u32>>(py).unwrap(), vec![2, 3, 4]);
+            assert_eq!(obj.extract::<Range<u32>>(py).unwrap(), 2..5);
+
+            let obj = (0u8..=255).to_object(py);
+            assert_eq!(obj.as_ref(py).len().unwrap(), 256);
+            assert_eq!(obj.extract::<RangeInclusive<u8>>(py).unwrap(), 0..=255);
+            assert_eq!(obj.extract::<Range<u16>>(py).unwrap(), 0..256);
+        });
+    }
+
+    #[test]
+    fn test_range_extract() {
+        Python::with_gil(|py| {
+            let extract = |code: &str| py.eval(code, None, None).unwrap().extract::<Range<i64>>();
+            assert_eq!(extract("range(-3, 7)").unwrap(), -3..7);
+            assert_eq!(extract("slice(1, 4)").unwrap(), 1..4);
+            assert_eq!(extract("slice(1, 4, 1)").unwrap(), 1..4);
+            assert_eq!(extract("range(5, 2)").unwrap(), Range { start: 5, end: 2 });
+
+            let err = extract("range(0, 10, 2)").unwrap_err();
+            assert!(err.is_instance::<PyValueError>(py));
+            let err = extract("slice(None, 4)").unwrap_err();
+            assert!(err.is_instance::<PyValueError>(py));
+            let err = extract("[1, 2]").unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+
+    #[test]
+    fn test_empty_range_inclusive_extract() {
+        Python::with_gil(|py| {
+            let extract = |code: &str| {
+                py.eval(code, None, None)
+                    .unwrap()
+                    .extract::<RangeInclusive<u32>>()
+            };
+            for code in &["range(0, 0)", "range(5, 2)", "slice(0, 0)"] {
+                let range = extract(code).unwrap();
+                assert!(range.is_empty(), "{}", code);
+                assert_eq!(range, 1..=0);
+            }
+            assert_eq!(extract("range(0, 1)").unwrap(), 0..=0);
+
+            let obj = (1u32..=0).to_object(py);
+            assert_eq!(obj.as_ref(py).len().unwrap(), 0);
+        });
+    }
+}
@@ -0,0 +1,67 @@
+//! Conversions for [`semver::Version`](https://docs.rs/semver/1/semver/struct.Version.html).
+//!
+//! A `Version` converts to its string form as a Python `str`, e.g. `"1.2.3-rc.1+build.5"`.
+//! Extraction parses a `str` with `Version::parse`, raising `ValueError` if it is not a valid
+//! semantic version.
+
+use crate::exceptions::PyValueError;
+use crate::types::PyString;
+use crate::{FromPyObject, IntoPy, PyAny, PyObject, PyResult, PyTryFrom, Python, ToPyObject};
+use ::semver::Version;
+
+impl ToPyObject for Version {
+    #[inline]
+    fn to_object(&self, py: Python) -> PyObject {
+        PyString::new(py, &self.to_string()).into()
+    }
+}
+
+impl IntoPy<PyObject> for Version {
+    #[inline]
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl FromPyObject<'_> for Version {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        let s = <PyString as PyTryFrom>::try_from(ob)?.to_str()?;
+        Version::parse(s)
+            .map_err(|e| PyValueError::new_err(format!("invalid version {:?}: {}", s, e)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Version;
+    use crate::exceptions::{PyTypeError, PyValueError};
+    use crate::{Python, ToPyObject};
+
+    #[test]
+    fn test_version_roundtrip() {
+        Python::with_gil(|py| {
+            let version = Version::parse("1.2.3-rc.1+build.5").unwrap();
+            let obj = version.to_object(py);
+            assert_eq!(obj.extract::<&str>(py).unwrap(), "1.2.3-rc.1+build.5");
+            assert_eq!(obj.extract::<Version>(py).unwrap(), version);
+        });
+    }
+
+    #[test]
+    fn test_version_from_python() {
+        Python::with_gil(|py| {
+            let obj = py.eval("'0.14.0'", None, None).unwrap();
+            assert_eq!(obj.extract::<Version>().unwrap(), Version::new(0, 14, 0));
+
+            for invalid in &["'1.2'", "'v1.2.3'", "'1.2.3-'", "''"] {
+                let obj = py.eval(invalid, None, None).unwrap();
+                let err = obj.extract::<Version>().unwrap_err();
+                assert!(err.is_instance::<PyValueError>(py), "{}", invalid);
+            }
+
+            let obj = py.eval("(1, 2, 3)", None, None).unwrap();
+            let err = obj.extract::<Version>().unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+}
@@ -0,0 +1,96 @@
+//! Conversions for [`serde_bytes`](https://docs.rs/serde_bytes/0.11) byte buffers.
+//!
+//! `ByteBuf` and `&Bytes` mark data that serializes as bytes rather than as a sequence of
+//! integers, so they convert to Python `bytes` instead of a `list` of `int` like `Vec<u8>` does.
+//! Extraction into a `ByteBuf` accepts `bytes` and `bytearray`, and `&Bytes` borrows from a `bytes`
+//! object like `&[u8]` does. A plain `Vec<u8>` field can be exposed as `bytes` with
+//! `#[pyo3(get, set, as_bytes)]`.
+
+use crate::types::{PyByteArray, PyBytes};
+use crate::{
+    FromPyObject, IntoPy, PyAny, PyDowncastError, PyObject, PyResult, PyTryFrom, Python, ToPyObject,
+};
+use ::serde_bytes::{ByteBuf, Bytes};
+
+impl ToPyObject for Bytes {
+    fn to_object(&self, py: Python) -> PyObject {
+        PyBytes::new(py, self).into()
+    }
+}
+
+impl<'a> IntoPy<PyObject> for &'a Bytes {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl<'a> FromPyObject<'a> for &'a Bytes {
+    fn extract(ob: &'a PyAny) -> PyResult<Self> {
+        Ok(Bytes::new(<PyBytes as PyTryFrom>::try_from(ob)?.as_bytes()))
+    }
+}
+
+impl ToPyObject for ByteBuf {
+    fn to_object(&self, py: Python) -> PyObject {
+        PyBytes::new(py, self).into()
+    }
+}
+
+impl IntoPy<PyObject> for ByteBuf {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl FromPyObject<'_> for ByteBuf {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        if let Ok(bytes) = <PyBytes as PyTryFrom>::try_from(ob) {
+            Ok(ByteBuf::from(bytes.as_bytes()))
+        } else if let Ok(bytearray) = <PyByteArray as PyTryFrom>::try_from(ob) {
+            Ok(ByteBuf::from(bytearray.to_vec()))
+        } else {
+            Err(PyDowncastError::new(ob, "bytes or bytearray").into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ByteBuf, Bytes};
+    use crate::exceptions::PyTypeError;
+    use crate::types::PyBytes;
+    use crate::{IntoPy, PyObject, Python, ToPyObject};
+
+    #[test]
+    fn test_bytebuf_roundtrip() {
+        Python::with_gil(|py| {
+            let buf = ByteBuf::from(vec![0, 1, 255]);
+            let obj = buf.to_object(py);
+            assert_eq!(
+                obj.cast_as::<PyBytes>(py).unwrap().as_bytes(),
+                b"\x00\x01\xff"
+            );
+            assert_eq!(obj.extract::<ByteBuf>(py).unwrap(), buf);
+            assert_eq!(
+                obj.extract::<&Bytes>(py).unwrap(),
+                Bytes::new(b"\x00\x01\xff")
+            );
+
+            let obj: PyObject = Bytes::new(b"ok").into_py(py);
+            assert_eq!(obj.extract::<ByteBuf>(py).unwrap(), ByteBuf::from("ok"));
+        });
+    }
+
+    #[test]
+    fn test_bytebuf_from_python() {
+        Python::with_gil(|py| {
+            let obj = py.eval("bytearray(b'\\x01')", None, None).unwrap();
+            assert_eq!(obj.extract::<ByteBuf>().unwrap(), ByteBuf::from(vec![1]));
+            assert!(obj.extract::<&Bytes>().is_err());
+
+            let obj = py.eval("[1, 2]", None, None).unwrap();
+            let err = obj.extract::<ByteBuf>().unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+}
@@ -0,0 +1,204 @@
+//! Conversions for [`std::time::SystemTime`].
+//!
+//! A `SystemTime` converts to a timezone-aware `datetime.datetime` in UTC, truncated to
+//! microseconds. Extraction accepts an aware `datetime.datetime` as well as a POSIX timestamp
+//! given as an `int` or `float` number of seconds since the epoch.
+
+use crate::exceptions::{PyOverflowError, PyValueError};
+use crate::types::{PyFloat, PyLong, PyType};
+use crate::{
+    FromPyObject, IntoPy, PyAny, PyDowncastError, PyErr, PyNativeType, PyObject, PyResult,
+    PyTryFrom, Python, ToPyObject,
+};
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::u64;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Returns `datetime.datetime(1970, 1, 1, tzinfo=datetime.timezone.utc)`.
+pub(super) fn unix_epoch(py: Python) -> PyResult<&PyAny> {
+    let datetime = py.import("datetime")?;
+    let utc = datetime.getattr("timezone")?.getattr("utc")?;
+    datetime
+        .getattr("datetime")?
+        .call1((1970, 1, 1, 0, 0, 0, 0, utc))
+}
+
+fn system_time_to_datetime(py: Python, time: SystemTime) -> PyResult<PyObject> {
+    let (duration, method) = match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => (duration, "__add__"),
+        Err(err) => (err.duration(), "__sub__"),
+    };
+    let days = duration.as_secs() / SECONDS_PER_DAY;
+    let seconds = duration.as_secs() % SECONDS_PER_DAY;
+    let microseconds = duration.subsec_micros();
+    let timedelta =
+        py.import("datetime")?
+            .getattr("timedelta")?
+            .call1((days, seconds, microseconds))?;
+    Ok(unix_epoch(py)?.call_method1(method, (timedelta,))?.into())
+}
+
+/// Offsets the epoch by a signed duration, failing if the result is not a valid `SystemTime`.
+fn offset_from_epoch(duration: Duration, before_epoch: bool) -> PyResult<SystemTime> {
+    let time = if before_epoch {
+        UNIX_EPOCH.checked_sub(duration)
+    } else {
+        UNIX_EPOCH.checked_add(duration)
+    };
+    time.ok_or_else(|| PyOverflowError::new_err("timestamp out of range for SystemTime"))
+}
+
+/// Returns whether `ob` is a `datetime.datetime`.
+pub(super) fn is_datetime(ob: &PyAny) -> PyResult<bool> {
+    let datetime = ob.py().import("datetime")?.getattr("datetime")?;
+    <PyType as PyTryFrom>::try_from(datetime)?.is_instance(ob)
+}
+
+/// Returns the microseconds between the epoch and an aware `datetime.datetime`.
+///
+/// `target` names the Rust type in the error raised for naive datetimes.
+pub(super) fn datetime_to_epoch_micros(ob: &PyAny, target: &str) -> PyResult<i128> {
+    if ob.getattr("tzinfo")?.is_none() {
+        return Err(PyValueError::new_err(format!(
+            "cannot convert a naive datetime to {}, set its tzinfo",
+            target
+        )));
+    }
+    let delta = ob.call_method1("__sub__", (unix_epoch(ob.py())?,))?;
+    // A timedelta is normalized so that only `days` may be negative.
+    let days: i64 = delta.getattr("days")?.extract()?;
+    let seconds: i64 = delta.getattr("seconds")?.extract()?;
+    let microseconds: i64 = delta.getattr("microseconds")?.extract()?;
+    Ok(
+        (i128::from(days) * i128::from(SECONDS_PER_DAY) + i128::from(seconds)) * 1_000_000
+            + i128::from(microseconds),
+    )
+}
+
+fn datetime_to_system_time(ob: &PyAny) -> PyResult<SystemTime> {
+    let total = datetime_to_epoch_micros(ob, "SystemTime")?;
+    let micros = u64::try_from(total.abs())
+        .map_err(|_| PyOverflowError::new_err("timestamp out of range for SystemTime"))?;
+    offset_from_epoch(Duration::from_micros(micros), total < 0)
+}
+
+impl ToPyObject for SystemTime {
+    /// Converts to an aware `datetime.datetime` in UTC.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the time is outside the range of `datetime.datetime` (years 1 to 9999).
+    fn to_object(&self, py: Python) -> PyObject {
+        system_time_to_datetime(py, *self)
+            .unwrap_or_else(|err: PyErr| panic!("SystemTime out of range for datetime: {}", err))
+    }
+}
+
+impl IntoPy<PyObject> for SystemTime {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl FromPyObject<'_> for SystemTime {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        if ob.is_instance::<PyLong>()? {
+            let seconds: i64 = ob.extract()?;
+            // `wrapping_abs` maps `i64::MIN` to itself, which is still correct as a `u64`.
+            offset_from_epoch(
+                Duration::from_secs(seconds.wrapping_abs() as u64),
+                seconds < 0,
+            )
+        } else if ob.is_instance::<PyFloat>()? {
+            let seconds: f64 = ob.extract()?;
+            if seconds.is_nan() {
+                return Err(PyValueError::new_err("cannot convert NaN to SystemTime"));
+            }
+            // `u64::MAX as f64` rounds up to 2**64, which `Duration::from_secs_f64` rejects with a
+            // panic, so it must be out of range too. This also covers the infinities.
+            if seconds.abs() >= u64::MAX as f64 {
+                return Err(PyOverflowError::new_err(
+                    "timestamp out of range for SystemTime",
+                ));
+            }
+            offset_from_epoch(Duration::from_secs_f64(seconds.abs()), seconds < 0.0)
+        } else if is_datetime(ob)? {
+            datetime_to_system_time(ob)
+        } else {
+            Err(PyDowncastError::new(ob, "datetime, int or float").into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::exceptions::{PyOverflowError, PyTypeError, PyValueError};
+    use crate::types::PyDict;
+    use crate::{Python, ToPyObject};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn test_system_time_to_python() {
+        Python::with_gil(|py| {
+            let time = UNIX_EPOCH + Duration::new(1_600_000_000, 123_456_789);
+            let locals = PyDict::new(py);
+            locals.set_item("t", time.to_object(py)).unwrap();
+            py.run(
+                r#"
+import datetime
+assert t == datetime.datetime(2020, 9, 13, 12, 26, 40, 123456, tzinfo=datetime.timezone.utc)
+assert t.tzinfo is datetime.timezone.utc
+"#,
+                None,
+                Some(locals),
+            )
+            .unwrap();
+
+            let before = UNIX_EPOCH - Duration::from_secs(86_400 + 1);
+            let obj = before.to_object(py);
+            let repr = obj.as_ref(py).repr().unwrap().to_string();
+            assert!(repr.contains("1969, 12, 30, 23, 59, 59"), "{}", repr);
+        });
+    }
+
+    #[test]
+    fn test_system_time_from_python() {
+        Python::with_gil(|py| {
+            let time = UNIX_EPOCH + Duration::new(1_600_000_000, 123_456_000);
+            assert_eq!(time.to_object(py).extract::<SystemTime>(py).unwrap(), time);
+
+            let obj = py.eval("1600000000", None, None).unwrap();
+            assert_eq!(
+                obj.extract::<SystemTime>().unwrap(),
+                UNIX_EPOCH + Duration::from_secs(1_600_000_000)
+            );
+            let obj = py.eval("-1.5", None, None).unwrap();
+            assert_eq!(
+                obj.extract::<SystemTime>().unwrap(),
+                UNIX_EPOCH - Duration::from_millis(1500)
+            );
+
+            let obj = py
+                .eval("__import__('datetime').datetime(2020, 1, 1)", None, None)
+                .unwrap();
+            let err = obj.extract::<SystemTime>().unwrap_err();
+            assert!(err.is_instance::<PyValueError>(py));
+
+            for timestamp in &["float('inf')", "float('-inf')", "2.0**64", "-2.0**64"] {
+                let obj = py.eval(timestamp, None, None).unwrap();
+                let err = obj.extract::<SystemTime>().unwrap_err();
+                assert!(err.is_instance::<PyOverflowError>(py), "{}", timestamp);
+            }
+
+            let obj = py.eval("float('nan')", None, None).unwrap();
+            let err = obj.extract::<SystemTime>().unwrap_err();
+            assert!(err.is_instance::<PyValueError>(py));
+
+            let obj = py.eval("'2020-01-01'", None, None).unwrap();
+            let err = obj.extract::<SystemTime>().unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+}
@@ -0,0 +1,126 @@
+//! Conversions for [`time::Duration`](https://docs.rs/time/0.3/time/struct.Duration.html).
+//!
+//! Unlike `std::time::Duration`, which is unsigned and is not covered by this feature, a
+//! `time::Duration` may be negative, so it maps naturally onto `datetime.timedelta`. Python
+//! timedeltas have microsecond precision, so sub-microsecond digits are truncated towards zero.
+
+use crate::exceptions::PyOverflowError;
+use crate::types::PyType;
+use crate::{
+    FromPyObject, IntoPy, PyAny, PyDowncastError, PyErr, PyNativeType, PyObject, PyResult,
+    PyTryFrom, Python, ToPyObject,
+};
+use ::time::Duration;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+fn duration_to_timedelta(py: Python, duration: Duration) -> PyResult<PyObject> {
+    // Both parts have the same sign, and `timedelta` normalizes them.
+    let seconds = duration.whole_seconds();
+    let microseconds = duration.subsec_microseconds();
+    let days = seconds / SECONDS_PER_DAY;
+    let seconds = seconds % SECONDS_PER_DAY;
+    Ok(py
+        .import("datetime")?
+        .getattr("timedelta")?
+        .call1((days, seconds, microseconds))?
+        .into())
+}
+
+fn is_timedelta(ob: &PyAny) -> PyResult<bool> {
+    let timedelta = ob.py().import("datetime")?.getattr("timedelta")?;
+    <PyType as PyTryFrom>::try_from(timedelta)?.is_instance(ob)
+}
+
+impl ToPyObject for Duration {
+    /// Converts to a `datetime.timedelta`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the duration is outside the range of `datetime.timedelta` (999999999 days).
+    fn to_object(&self, py: Python) -> PyObject {
+        duration_to_timedelta(py, *self)
+            .unwrap_or_else(|err: PyErr| panic!("Duration out of range for timedelta: {}", err))
+    }
+}
+
+impl IntoPy<PyObject> for Duration {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl FromPyObject<'_> for Duration {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        if !is_timedelta(ob)? {
+            return Err(PyDowncastError::new(ob, "timedelta").into());
+        }
+        // A timedelta is normalized so that only `days` may be negative.
+        let days: i64 = ob.getattr("days")?.extract()?;
+        let seconds: i64 = ob.getattr("seconds")?.extract()?;
+        let microseconds: i64 = ob.getattr("microseconds")?.extract()?;
+        days.checked_mul(SECONDS_PER_DAY)
+            .and_then(|total| total.checked_add(seconds))
+            .map(|total| Duration::seconds(total) + Duration::microseconds(microseconds))
+            .ok_or_else(|| PyOverflowError::new_err("timedelta out of range for Duration"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Duration;
+    use crate::exceptions::PyTypeError;
+    use crate::types::PyDict;
+    use crate::{Python, ToPyObject};
+
+    #[test]
+    fn test_duration_to_python() {
+        Python::with_gil(|py| {
+            let locals = PyDict::new(py);
+            locals
+                .set_item("d", Duration::new(90_061, 500_999_999).to_object(py))
+                .unwrap();
+            locals
+                .set_item("n", Duration::new(-1, -250_000_000).to_object(py))
+                .unwrap();
+            py.run(
+                r#"
+import datetime
+assert d == datetime.timedelta(days=1, hours=1, minutes=1, seconds=1, microseconds=500999)
+assert n == datetime.timedelta(seconds=-1.25)
+assert (n.days, n.seconds, n.microseconds) == (-1, 86398, 750000)
+"#,
+                None,
+                Some(locals),
+            )
+            .unwrap();
+        });
+    }
+
+    #[test]
+    fn test_duration_from_python() {
+        Python::with_gil(|py| {
+            let obj = py
+                .eval(
+                    "__import__('datetime').timedelta(minutes=-3, microseconds=-7)",
+                    None,
+                    None,
+                )
+                .unwrap();
+            assert_eq!(
+                obj.extract::<Duration>().unwrap(),
+                -(Duration::minutes(3) + Duration::microseconds(7))
+            );
+
+            let obj = py
+                .eval("__import__('datetime').timedelta.max", None, None)
+                .unwrap();
+            let max = obj.extract::<Duration>().unwrap();
+            assert_eq!(max.whole_days(), 999_999_999);
+
+            let obj = py.eval("1.5", None, None).unwrap();
+            let err = obj.extract::<Duration>().unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+}
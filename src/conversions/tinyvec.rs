@@ -0,0 +1,87 @@
+//! Conversions for [`tinyvec::TinyVec`](https://docs.rs/tinyvec/1/tinyvec/enum.TinyVec.html).
+//!
+//! A `TinyVec` converts like a `Vec`: to a Python `list`, and from any Python sequence. Whether
+//! the elements are stored inline or on the heap makes no difference to Python.
+
+use crate::types::{PyList, PySequence};
+use crate::{FromPyObject, IntoPy, PyAny, PyObject, PyResult, PyTryFrom, Python, ToPyObject};
+use ::tinyvec::{Array, TinyVec};
+
+impl<A> ToPyObject for TinyVec<A>
+where
+    A: Array,
+    A::Item: ToPyObject,
+{
+    fn to_object(&self, py: Python) -> PyObject {
+        self.as_slice().to_object(py)
+    }
+}
+
+impl<A> IntoPy<PyObject> for TinyVec<A>
+where
+    A: Array,
+    A::Item: IntoPy<PyObject>,
+{
+    fn into_py(self, py: Python) -> PyObject {
+        let items: Vec<PyObject> = self.into_iter().map(|e| e.into_py(py)).collect();
+        PyList::new(py, items).into()
+    }
+}
+
+impl<'a, A> FromPyObject<'a> for TinyVec<A>
+where
+    A: Array,
+    A::Item: FromPyObject<'a>,
+{
+    fn extract(obj: &'a PyAny) -> PyResult<Self> {
+        let seq = <PySequence as PyTryFrom>::try_from(obj)?;
+        let mut v = TinyVec::with_capacity(seq.len().unwrap_or(0) as usize);
+        for item in seq.iter()? {
+            v.push(item?.extract()?);
+        }
+        Ok(v)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TinyVec;
+    use crate::types::PyList;
+    use crate::{IntoPy, PyObject, Python, ToPyObject};
+
+    #[test]
+    fn test_tinyvec_to_python() {
+        Python::with_gil(|py| {
+            let inline: TinyVec<[u32; 4]> = TinyVec::from([1, 2, 3, 4]);
+            assert!(inline.is_inline());
+            let obj = inline.to_object(py);
+            assert!(obj.as_ref(py).is_instance::<PyList>().unwrap());
+            assert_eq!(obj.extract::<Vec<u32>>(py).unwrap(), vec![1, 2, 3, 4]);
+
+            let heap: TinyVec<[u32; 2]> = (0..5).collect();
+            assert!(heap.is_heap());
+            let obj: PyObject = heap.into_py(py);
+            assert_eq!(obj.extract::<Vec<u32>>(py).unwrap(), vec![0, 1, 2, 3, 4]);
+        });
+    }
+
+    #[test]
+    fn test_tinyvec_from_python() {
+        Python::with_gil(|py| {
+            let obj = py.eval("(1, 2)", None, None).unwrap();
+            let v: TinyVec<[u32; 4]> = obj.extract().unwrap();
+            assert!(v.is_inline());
+            assert_eq!(v.as_slice(), &[1, 2]);
+
+            let obj = py.eval("list(range(10))", None, None).unwrap();
+            let v: TinyVec<[u32; 4]> = obj.extract().unwrap();
+            assert!(v.is_heap());
+            assert_eq!(v.as_slice(), &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+            let obj = py.eval("[1, 'two']", None, None).unwrap();
+            assert!(obj.extract::<TinyVec<[u32; 4]>>().is_err());
+            let obj = py.eval("1", None, None).unwrap();
+            assert!(obj.extract::<TinyVec<[u32; 4]>>().is_err());
+        });
+    }
+}
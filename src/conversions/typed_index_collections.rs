@@ -0,0 +1,65 @@
+//! Conversions for [`typed_index_collections::TiVec`](https://docs.rs/typed-index-collections/3/typed_index_collections/struct.TiVec.html).
+//!
+//! A `TiVec<K, V>` converts like a `Vec<V>`: to a Python `list`, and from any Python sequence. The
+//! typed index `K` only exists in Rust, so Python indexes the list with plain integers.
+
+use crate::{FromPyObject, IntoPy, PyAny, PyObject, PyResult, Python, ToPyObject};
+use ::typed_index_collections::TiVec;
+
+impl<K, V: ToPyObject> ToPyObject for TiVec<K, V> {
+    fn to_object(&self, py: Python) -> PyObject {
+        self.raw.to_object(py)
+    }
+}
+
+impl<K, V: IntoPy<PyObject>> IntoPy<PyObject> for TiVec<K, V> {
+    fn into_py(self, py: Python) -> PyObject {
+        self.raw.into_py(py)
+    }
+}
+
+impl<'a, K, V: FromPyObject<'a>> FromPyObject<'a> for TiVec<K, V> {
+    fn extract(obj: &'a PyAny) -> PyResult<Self> {
+        Vec::extract(obj).map(TiVec::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TiVec;
+    use crate::exceptions::PyTypeError;
+    use crate::types::PyList;
+    use crate::{Python, ToPyObject};
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct NodeId(usize);
+
+    impl From<usize> for NodeId {
+        fn from(index: usize) -> Self {
+            NodeId(index)
+        }
+    }
+
+    impl From<NodeId> for usize {
+        fn from(id: NodeId) -> Self {
+            id.0
+        }
+    }
+
+    #[test]
+    fn test_tivec_roundtrip() {
+        Python::with_gil(|py| {
+            let names: TiVec<NodeId, &str> = vec!["root", "leaf"].into();
+            let obj = names.to_object(py);
+            assert!(obj.as_ref(py).is_instance::<PyList>().unwrap());
+            assert_eq!(obj.extract::<Vec<String>>(py).unwrap(), ["root", "leaf"]);
+
+            let names: TiVec<NodeId, String> = obj.extract(py).unwrap();
+            assert_eq!(names[NodeId(1)], "leaf");
+
+            let obj = py.eval("(1, 'two')", None, None).unwrap();
+            let err = obj.extract::<TiVec<NodeId, u32>>().unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+}
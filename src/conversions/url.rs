@@ -0,0 +1,71 @@
+//! Conversions for [`url::Url`](https://docs.rs/url/2/url/struct.Url.html).
+//!
+//! A `Url` converts to its serialization as a Python `str`. Extraction parses a `str`, raising
+//! `ValueError` if it is not a valid absolute URL.
+
+use crate::exceptions::PyValueError;
+use crate::types::PyString;
+use crate::{FromPyObject, IntoPy, PyAny, PyObject, PyResult, PyTryFrom, Python, ToPyObject};
+use ::url::Url;
+
+impl ToPyObject for Url {
+    #[inline]
+    fn to_object(&self, py: Python) -> PyObject {
+        PyString::new(py, self.as_str()).into()
+    }
+}
+
+impl IntoPy<PyObject> for Url {
+    #[inline]
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl FromPyObject<'_> for Url {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        let s = <PyString as PyTryFrom>::try_from(ob)?.to_str()?;
+        Url::parse(s).map_err(|e| PyValueError::new_err(format!("invalid URL {:?}: {}", s, e)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Url;
+    use crate::exceptions::PyValueError;
+    use crate::types::PyString;
+    use crate::{IntoPy, PyObject, Python, ToPyObject};
+
+    #[test]
+    fn test_url_to_python() {
+        Python::with_gil(|py| {
+            let url = Url::parse("https://example.com/a b?q=1").unwrap();
+            let obj = url.to_object(py);
+            assert!(obj.as_ref(py).is_instance::<PyString>().unwrap());
+            assert_eq!(
+                obj.extract::<&str>(py).unwrap(),
+                "https://example.com/a%20b?q=1"
+            );
+
+            let obj: PyObject = url.clone().into_py(py);
+            assert_eq!(obj.extract::<Url>(py).unwrap(), url);
+        });
+    }
+
+    #[test]
+    fn test_url_from_python() {
+        Python::with_gil(|py| {
+            let obj = py.eval("'http://localhost:8080/path'", None, None).unwrap();
+            let url: Url = obj.extract().unwrap();
+            assert_eq!(url.port(), Some(8080));
+            assert_eq!(url.path(), "/path");
+
+            let obj = py.eval("'not a url'", None, None).unwrap();
+            let err = obj.extract::<Url>().unwrap_err();
+            assert!(err.is_instance::<PyValueError>(py));
+
+            let obj = py.eval("b'http://localhost'", None, None).unwrap();
+            assert!(obj.extract::<Url>().is_err());
+        });
+    }
+}
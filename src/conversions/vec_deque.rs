@@ -0,0 +1,78 @@
+//! Conversions for [`std::collections::VecDeque`].
+//!
+//! A `VecDeque` converts to a Python `list` like a `Vec` does, front to back. Extraction accepts
+//! any sequence, including a `collections.deque`. A `#[pyclass]` field can be exposed as a
+//! `collections.deque` instead with `#[pyo3(get, as_deque)]`.
+
+use crate::{FromPyObject, IntoPy, PyAny, PyObject, PyResult, Python, ToPyObject};
+use std::collections::VecDeque;
+
+impl<T: ToPyObject> ToPyObject for VecDeque<T> {
+    fn to_object(&self, py: Python) -> PyObject {
+        let (front, back) = self.as_slices();
+        if back.is_empty() {
+            front.to_object(py)
+        } else {
+            self.iter().collect::<Vec<_>>().to_object(py)
+        }
+    }
+}
+
+impl<T: IntoPy<PyObject>> IntoPy<PyObject> for VecDeque<T> {
+    fn into_py(self, py: Python) -> PyObject {
+        Vec::from(self).into_py(py)
+    }
+}
+
+impl<'a, T: FromPyObject<'a>> FromPyObject<'a> for VecDeque<T> {
+    fn extract(ob: &'a PyAny) -> PyResult<Self> {
+        ob.extract::<Vec<T>>().map(VecDeque::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::VecDeque;
+    use crate::exceptions::PyTypeError;
+    use crate::{IntoPy, PyObject, Python, ToPyObject};
+
+    #[test]
+    fn test_vec_deque_roundtrip() {
+        Python::with_gil(|py| {
+            // Wrapped around, so that the elements are split between the two slices.
+            let mut deque = VecDeque::with_capacity(8);
+            deque.extend(vec![3, 4, 5, 6]);
+            deque.push_front(1);
+            assert!(!deque.as_slices().1.is_empty());
+
+            let obj = deque.to_object(py);
+            assert_eq!(obj.extract::<Vec<i32>>(py).unwrap(), vec![1, 3, 4, 5, 6]);
+            assert_eq!(obj.extract::<VecDeque<i32>>(py).unwrap(), deque);
+            let obj: PyObject = deque.into_py(py);
+            assert_eq!(obj.extract::<Vec<i32>>(py).unwrap(), vec![1, 3, 4, 5, 6]);
+        });
+    }
+
+    #[test]
+    fn test_vec_deque_extract() {
+        Python::with_gil(|py| {
+            let extract = |code: &str| {
+                py.eval(code, None, None)
+                    .unwrap()
+                    .extract::<VecDeque<i32>>()
+            };
+            let from_deque = extract("__import__('collections').deque([1, 2, 3])").unwrap();
+            assert_eq!(
+                from_deque,
+                vec![1, 2, 3].into_iter().collect::<VecDeque<_>>()
+            );
+            assert_eq!(
+                extract("(4, 5)").unwrap(),
+                vec![4, 5].into_iter().collect::<VecDeque<_>>()
+            );
+
+            let err = extract("3").unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+}
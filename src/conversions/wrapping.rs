@@ -0,0 +1,185 @@
+//! Conversions for the integer wrappers [`std::num::Wrapping`] and `std::num::Saturating`.
+//!
+//! Both convert to a Python `int` holding the inner value. When extracting an `int` that is out of
+//! range for the inner type, a `Wrapping` wraps it around modulo `2**N` like `Wrapping` arithmetic
+//! does, and a `Saturating` clamps it to the minimum or maximum of the type. `Saturating` is only
+//! available on Rust 1.74 and later.
+
+use crate::{
+    ffi, AsPyPointer, FromPyObject, IntoPy, PyAny, PyNativeType, PyObject, PyResult, Python,
+    ToPyObject,
+};
+use std::num::Wrapping;
+
+impl<T: ToPyObject> ToPyObject for Wrapping<T> {
+    fn to_object(&self, py: Python) -> PyObject {
+        self.0.to_object(py)
+    }
+}
+
+impl<T: IntoPy<PyObject>> IntoPy<PyObject> for Wrapping<T> {
+    fn into_py(self, py: Python) -> PyObject {
+        self.0.into_py(py)
+    }
+}
+
+/// Reduces an integer modulo `2**N` by masking it with `mask`, which is `2**N - 1`.
+///
+/// Python integers behave as if they had infinitely many two's complement bits, so e.g. `-1`
+/// becomes `mask` itself.
+fn mask_int(ob: &PyAny, mask: PyObject) -> PyResult<&PyAny> {
+    let py = ob.py();
+    unsafe {
+        let index: &PyAny = py.from_owned_ptr_or_err(ffi::PyNumber_Index(ob.as_ptr()))?;
+        py.from_owned_ptr_or_err(ffi::PyNumber_And(index.as_ptr(), mask.as_ptr()))
+    }
+}
+
+macro_rules! wrapping_conversion {
+    ($int:ty, $unsigned:ty) => {
+        impl FromPyObject<'_> for Wrapping<$int> {
+            fn extract(ob: &PyAny) -> PyResult<Self> {
+                let mask = <$unsigned>::max_value().into_py(ob.py());
+                let bits: $unsigned = mask_int(ob, mask)?.extract()?;
+                Ok(Wrapping(bits as $int))
+            }
+        }
+    };
+}
+
+wrapping_conversion!(i8, u8);
+wrapping_conversion!(u8, u8);
+wrapping_conversion!(i16, u16);
+wrapping_conversion!(u16, u16);
+wrapping_conversion!(i32, u32);
+wrapping_conversion!(u32, u32);
+wrapping_conversion!(i64, u64);
+wrapping_conversion!(u64, u64);
+wrapping_conversion!(i128, u128);
+wrapping_conversion!(u128, u128);
+wrapping_conversion!(isize, usize);
+wrapping_conversion!(usize, usize);
+
+#[cfg(num_saturating)]
+mod saturating_conversion {
+    use crate::exceptions::PyOverflowError;
+    use crate::{
+        FromPyObject, IntoPy, PyAny, PyNativeType, PyObject, PyResult, Python, ToPyObject,
+    };
+    use std::cmp::Ordering;
+    use std::num::Saturating;
+
+    impl<T: ToPyObject> ToPyObject for Saturating<T> {
+        fn to_object(&self, py: Python) -> PyObject {
+            self.0.to_object(py)
+        }
+    }
+
+    impl<T: IntoPy<PyObject>> IntoPy<PyObject> for Saturating<T> {
+        fn into_py(self, py: Python) -> PyObject {
+            self.0.into_py(py)
+        }
+    }
+
+    macro_rules! saturating_conversion {
+        ($int:ty) => {
+            impl FromPyObject<'_> for Saturating<$int> {
+                fn extract(ob: &PyAny) -> PyResult<Self> {
+                    match ob.extract::<$int>() {
+                        Ok(value) => Ok(Saturating(value)),
+                        Err(err) if err.is_instance::<PyOverflowError>(ob.py()) => {
+                            if ob.compare(0)? == Ordering::Less {
+                                Ok(Saturating(<$int>::MIN))
+                            } else {
+                                Ok(Saturating(<$int>::MAX))
+                            }
+                        }
+                        Err(err) => Err(err),
+                    }
+                }
+            }
+        };
+    }
+
+    saturating_conversion!(i8);
+    saturating_conversion!(u8);
+    saturating_conversion!(i16);
+    saturating_conversion!(u16);
+    saturating_conversion!(i32);
+    saturating_conversion!(u32);
+    saturating_conversion!(i64);
+    saturating_conversion!(u64);
+    saturating_conversion!(i128);
+    saturating_conversion!(u128);
+    saturating_conversion!(isize);
+    saturating_conversion!(usize);
+}
+
+#[cfg(test)]
+mod test {
+    use super::Wrapping;
+    use crate::exceptions::PyTypeError;
+    use crate::{Python, ToPyObject};
+
+    #[test]
+    fn test_wrapping_roundtrip() {
+        Python::with_gil(|py| {
+            let obj = Wrapping(std::u32::MAX).to_object(py);
+            assert_eq!(obj.extract::<u32>(py).unwrap(), std::u32::MAX);
+            assert_eq!(
+                obj.extract::<Wrapping<u32>>(py).unwrap(),
+                Wrapping(std::u32::MAX)
+            );
+        });
+    }
+
+    #[test]
+    fn test_wrapping_out_of_range() {
+        Python::with_gil(|py| {
+            let wrap_u32 = |code: &str| {
+                py.eval(code, None, None)
+                    .unwrap()
+                    .extract::<Wrapping<u32>>()
+            };
+            assert_eq!(wrap_u32("2**32 + 5").unwrap(), Wrapping(5));
+            assert_eq!(wrap_u32("-1").unwrap(), Wrapping(std::u32::MAX));
+
+            let wrap_i8 = |code: &str| py.eval(code, None, None).unwrap().extract::<Wrapping<i8>>();
+            assert_eq!(wrap_i8("128").unwrap(), Wrapping(std::i8::MIN));
+            assert_eq!(wrap_i8("-129").unwrap(), Wrapping(std::i8::MAX));
+            assert_eq!(wrap_i8("-1").unwrap(), Wrapping(-1));
+
+            let obj = py.eval("2**200 + 1", None, None).unwrap();
+            assert_eq!(obj.extract::<Wrapping<i128>>().unwrap(), Wrapping(1));
+
+            let err = wrap_u32("1.5").unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+
+    #[cfg(num_saturating)]
+    #[test]
+    fn test_saturating_out_of_range() {
+        use std::num::Saturating;
+
+        Python::with_gil(|py| {
+            let sat_u32 = |code: &str| {
+                py.eval(code, None, None)
+                    .unwrap()
+                    .extract::<Saturating<u32>>()
+            };
+            assert_eq!(sat_u32("7").unwrap(), Saturating(7));
+            assert_eq!(sat_u32("2**32").unwrap(), Saturating(std::u32::MAX));
+            assert_eq!(sat_u32("-1").unwrap(), Saturating(0));
+
+            let obj = py.eval("-2**200", None, None).unwrap();
+            assert_eq!(
+                obj.extract::<Saturating<i64>>().unwrap(),
+                Saturating(std::i64::MIN)
+            );
+
+            let err = sat_u32("1.5").unwrap_err();
+            assert!(err.is_instance::<PyTypeError>(py));
+        });
+    }
+}
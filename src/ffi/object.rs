@@ -337,6 +337,17 @@ extern "C" {
     pub fn PyCallable_Check(arg1: *mut PyObject) -> c_int;
     #[cfg_attr(PyPy, link_name = "PyPyObject_ClearWeakRefs")]
     pub fn PyObject_ClearWeakRefs(arg1: *mut PyObject);
+    // Public from Python 3.13, exported with a leading underscore in 3.12.
+    #[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+    #[cfg_attr(not(Py_3_13), link_name = "_PyObject_VisitManagedDict")]
+    pub fn PyObject_VisitManagedDict(
+        obj: *mut PyObject,
+        visit: visitproc,
+        arg: *mut c_void,
+    ) -> c_int;
+    #[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+    #[cfg_attr(not(Py_3_13), link_name = "_PyObject_ClearManagedDict")]
+    pub fn PyObject_ClearManagedDict(obj: *mut PyObject);
 
     #[cfg_attr(PyPy, link_name = "PyPyObject_Dir")]
     pub fn PyObject_Dir(arg1: *mut PyObject) -> *mut PyObject;
@@ -347,6 +358,18 @@ extern "C" {
 // Flag bits for printing:
 pub const Py_PRINT_RAW: c_int = 1; // No string quotes etc.
 
+/// Set if the interpreter manages the instance `__dict__`, in front of the object
+#[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+pub const Py_TPFLAGS_MANAGED_DICT: c_ulong = 1 << 4;
+
+/// Set if instances of the type are sequences, for pattern matching
+#[cfg(Py_3_10)]
+pub const Py_TPFLAGS_SEQUENCE: c_ulong = 1 << 5;
+
+/// Set if instances of the type are mappings, for pattern matching
+#[cfg(Py_3_10)]
+pub const Py_TPFLAGS_MAPPING: c_ulong = 1 << 6;
+
 /// Set if the type object is dynamically allocated
 pub const Py_TPFLAGS_HEAPTYPE: c_ulong = 1 << 9;
 
@@ -1,9 +1,9 @@
 //! Includes `PyCell` implementation.
 use crate::conversion::{AsPyPointer, FromPyPointer, ToPyObject};
 use crate::exceptions::PyRuntimeError;
-use crate::pyclass::PyClass;
+use crate::pyclass::{Boolean, False, PyClass};
 use crate::pyclass_init::PyClassInitializer;
-use crate::pyclass_slots::{PyClassDict, PyClassWeakRef};
+use crate::pyclass_slots::{PyClassDict, PyClassEnterDepth, PyClassHashCache, PyClassWeakRef};
 use crate::type_object::{PyLayout, PySizedLayout};
 use crate::types::PyAny;
 use crate::{class::impl_::PyClassBaseType, class::impl_::PyClassThreadChecker};
@@ -38,6 +38,11 @@ where
 pub struct PyCellInner<T: PyClass> {
     ob_base: <T::BaseType as PyClassBaseType>::LayoutAsBase,
     value: ManuallyDrop<UnsafeCell<T>>,
+    // Kept here rather than in `PyCell` so that it is at the same offset in subclass instances,
+    // which inherit the `__hash__` slot.
+    hash_cache: T::HashCache,
+    // Likewise for the inherited `__enter__` and `__exit__` of `#[pyclass(reentrant)]`.
+    enter_depth: T::EnterDepth,
 }
 
 impl<T: PyClass> AsPyPointer for PyCellInner<T> {
@@ -50,9 +55,12 @@ unsafe impl<T: PyClass> PyLayout<T> for PyCellInner<T> {
     const IS_NATIVE_TYPE: bool = false;
     fn py_init(&mut self, value: T) {
         self.value = ManuallyDrop::new(UnsafeCell::new(value));
+        self.hash_cache = T::HashCache::new();
+        self.enter_depth = T::EnterDepth::new();
     }
     unsafe fn py_drop(&mut self, py: Python) {
         ManuallyDrop::drop(&mut self.value);
+        self.enter_depth.set_resource(None);
         self.ob_base.py_drop(py);
     }
 }
@@ -135,34 +143,80 @@ impl<T: PyClass> PyCellInner<T> {
 pub struct PyCell<T: PyClass> {
     inner: PyCellInner<T>,
     thread_checker: T::ThreadChecker,
-    // DO NOT CHANGE THE ORDER OF THESE FIELDS WITHOUT CHANGING PyCell::dict_offset()
-    // AND PyCell::weakref_offset()
+    // DO NOT CHANGE THE ORDER OF THESE FIELDS WITHOUT CHANGING PyCell::DICT_OFFSET
+    // AND PyCell::WEAKREF_OFFSET
     dict: T::Dict,
     weakref: T::WeakRef,
 }
 
+/// Rounds `offset` up to a multiple of `align`, which is a power of two.
+const fn align_up(offset: usize, align: usize) -> usize {
+    (offset + align - 1) & !(align - 1)
+}
+
 impl<T: PyClass> PyCell<T> {
+    // The field offsets by the `repr(C)` layout rules. Counting back from the end of the struct
+    // instead would be wrong when an over-aligned class leaves padding after the weakref slot.
+    const DICT_OFFSET: usize = align_up(
+        align_up(
+            std::mem::size_of::<PyCellInner<T>>(),
+            std::mem::align_of::<T::ThreadChecker>(),
+        ) + std::mem::size_of::<T::ThreadChecker>(),
+        std::mem::align_of::<T::Dict>(),
+    );
+    const WEAKREF_OFFSET: usize = align_up(
+        Self::DICT_OFFSET + std::mem::size_of::<T::Dict>(),
+        std::mem::align_of::<T::WeakRef>(),
+    );
+
+    /// Whether `DICT_OFFSET` and `WEAKREF_OFFSET` agree with the layout of `PyCell<T>`: the dict
+    /// and weakref slots don't overlap, and the weakref slot is the last field.
+    ///
+    /// `#[pyclass(dict)]` and `#[pyclass(weakref)]` assert this at compile time.
+    #[doc(hidden)]
+    pub const SLOT_LAYOUT_IS_VALID: bool = (Self::DICT_OFFSET + std::mem::size_of::<T::Dict>()
+        <= Self::WEAKREF_OFFSET)
+        & (align_up(
+            Self::WEAKREF_OFFSET + std::mem::size_of::<T::WeakRef>(),
+            std::mem::align_of::<Self>(),
+        ) == std::mem::size_of::<Self>());
+
     /// Get the offset of the dictionary from the start of the struct in bytes.
     #[cfg(not(all(Py_LIMITED_API, not(Py_3_9))))]
     pub(crate) fn dict_offset() -> Option<usize> {
-        if T::Dict::IS_DUMMY {
+        if T::Dict::IS_DUMMY || T::Dict::IS_MANAGED {
             None
         } else {
-            Some(
-                std::mem::size_of::<Self>()
-                    - std::mem::size_of::<T::Dict>()
-                    - std::mem::size_of::<T::WeakRef>(),
-            )
+            Some(Self::DICT_OFFSET)
         }
     }
 
+    /// Get the cached hash slot of `#[pyclass(cache_hash)]`.
+    pub(crate) fn hash_cache(&self) -> &T::HashCache {
+        &self.inner.hash_cache
+    }
+
+    /// Get the enter depth slot of `#[pyclass(reentrant)]`.
+    pub(crate) fn enter_depth(&self) -> &T::EnterDepth {
+        &self.inner.enter_depth
+    }
+
+    /// Releases a shared borrow which was taken with `try_borrow` and then leaked with
+    /// `mem::forget`, e.g. for a buffer exported by `#[pyclass(buffer(...))]`.
+    ///
+    /// # Safety
+    /// There must be such a leaked borrow, which is not used afterwards.
+    pub(crate) unsafe fn release_leaked_borrow(&self) {
+        drop(PyRef { inner: &self.inner });
+    }
+
     /// Get the offset of the weakref list from the start of the struct in bytes.
     #[cfg(not(all(Py_LIMITED_API, not(Py_3_9))))]
     pub(crate) fn weakref_offset() -> Option<usize> {
         if T::WeakRef::IS_DUMMY {
             None
         } else {
-            Some(std::mem::size_of::<Self>() - std::mem::size_of::<T::WeakRef>())
+            Some(Self::WEAKREF_OFFSET)
         }
     }
 }
@@ -195,11 +249,16 @@ impl<T: PyClass> PyCell<T> {
 
     /// Mutably borrows the value `T`. This borrow lasts untill the returned `PyRefMut` exists.
     ///
+    /// Not available for `#[pyclass(frozen)]` classes.
+    ///
     /// # Panics
     ///
     /// Panics if the value is currently mutably borrowed. For a non-panicking variant, use
     /// [`try_borrow_mut`](#method.try_borrow_mut).
-    pub fn borrow_mut(&self) -> PyRefMut<'_, T> {
+    pub fn borrow_mut(&self) -> PyRefMut<'_, T>
+    where
+        T: PyClass<Frozen = False>,
+    {
         self.try_borrow_mut().expect("Already borrowed")
     }
 
@@ -229,6 +288,10 @@ impl<T: PyClass> PyCell<T> {
     /// ```
     pub fn try_borrow(&self) -> Result<PyRef<'_, T>, PyBorrowError> {
         self.thread_checker.ensure();
+        if T::Frozen::VALUE {
+            // A frozen class is never mutably borrowed, so the borrow flag is not used.
+            return Ok(PyRef { inner: &self.inner });
+        }
         let flag = self.inner.get_borrow_flag();
         if flag == BorrowFlag::HAS_MUTABLE_BORROW {
             Err(PyBorrowError { _private: () })
@@ -259,9 +322,18 @@ impl<T: PyClass> PyCell<T> {
     ///     assert!(c.try_borrow_mut().is_ok());
     /// });
     /// ```
-    pub fn try_borrow_mut(&self) -> Result<PyRefMut<'_, T>, PyBorrowMutError> {
+    pub fn try_borrow_mut(&self) -> Result<PyRefMut<'_, T>, PyBorrowMutError>
+    where
+        T: PyClass<Frozen = False>,
+    {
+        self.try_borrow_mut_checked()
+    }
+
+    /// Like [`try_borrow_mut`](#method.try_borrow_mut), but available for all classes, so that
+    /// `#[pyproto]` slots can be implemented generically. Always fails for a frozen class.
+    pub(crate) fn try_borrow_mut_checked(&self) -> Result<PyRefMut<'_, T>, PyBorrowMutError> {
         self.thread_checker.ensure();
-        if self.inner.get_borrow_flag() != BorrowFlag::UNUSED {
+        if T::Frozen::VALUE || self.inner.get_borrow_flag() != BorrowFlag::UNUSED {
             Err(PyBorrowMutError { _private: () })
         } else {
             self.inner.set_borrow_flag(BorrowFlag::HAS_MUTABLE_BORROW);
@@ -313,7 +385,10 @@ impl<T: PyClass> PyCell<T> {
     ///
     /// Panics if the value is currently borrowed.
     #[inline]
-    pub fn replace(&self, t: T) -> T {
+    pub fn replace(&self, t: T) -> T
+    where
+        T: PyClass<Frozen = False>,
+    {
         std::mem::replace(&mut *self.borrow_mut(), t)
     }
 
@@ -322,7 +397,10 @@ impl<T: PyClass> PyCell<T> {
     /// # Panics
     ///
     /// Panics if the value is currently borrowed.
-    pub fn replace_with<F: FnOnce(&mut T) -> T>(&self, f: F) -> T {
+    pub fn replace_with<F: FnOnce(&mut T) -> T>(&self, f: F) -> T
+    where
+        T: PyClass<Frozen = False>,
+    {
         let mut_borrow = &mut *self.borrow_mut();
         let replacement = f(mut_borrow);
         std::mem::replace(mut_borrow, replacement)
@@ -334,7 +412,10 @@ impl<T: PyClass> PyCell<T> {
     ///
     /// Panics if the value in either `PyCell` is currently borrowed.
     #[inline]
-    pub fn swap(&self, other: &Self) {
+    pub fn swap(&self, other: &Self)
+    where
+        T: PyClass<Frozen = False>,
+    {
         std::mem::swap(&mut *self.borrow_mut(), &mut *other.borrow_mut())
     }
 
@@ -361,10 +442,19 @@ unsafe impl<T: PyClass> PyLayout<T> for PyCell<T> {
     const IS_NATIVE_TYPE: bool = false;
     fn py_init(&mut self, value: T) {
         self.inner.value = ManuallyDrop::new(UnsafeCell::new(value));
+        self.inner.hash_cache = T::HashCache::new();
+        self.inner.enter_depth = T::EnterDepth::new();
     }
     unsafe fn py_drop(&mut self, py: Python) {
         ManuallyDrop::drop(&mut self.inner.value);
+        self.inner.enter_depth.set_resource(None);
         self.dict.clear_dict(py);
+        #[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+        {
+            if T::Dict::IS_MANAGED && crate::class::gc::has_managed_dict(self.as_ptr()) {
+                ffi::PyObject_ClearManagedDict(self.as_ptr());
+            }
+        }
         self.weakref.clear_weakrefs(self.as_ptr(), py);
         self.inner.ob_base.py_drop(py);
     }
@@ -477,7 +567,7 @@ where
 impl<'p, T, U> PyRef<'p, T>
 where
     T: PyClass<BaseType = U>,
-    U: PyClass,
+    U: PyClass<Frozen = False>,
 {
     /// Get `PyRef<T::BaseType>`.
     /// You can use this method to get super class of super class.
@@ -536,8 +626,10 @@ impl<'p, T: PyClass> Deref for PyRef<'p, T> {
 
 impl<'p, T: PyClass> Drop for PyRef<'p, T> {
     fn drop(&mut self) {
-        let flag = self.inner.get_borrow_flag();
-        self.inner.set_borrow_flag(flag.decrement())
+        if !T::Frozen::VALUE {
+            let flag = self.inner.get_borrow_flag();
+            self.inner.set_borrow_flag(flag.decrement())
+        }
     }
 }
 
@@ -594,7 +686,7 @@ where
 impl<'p, T, U> AsMut<U> for PyRefMut<'p, T>
 where
     T: PyClass<BaseType = U>,
-    U: PyClass,
+    U: PyClass<Frozen = False>,
 {
     fn as_mut(&mut self) -> &mut T::BaseType {
         unsafe { &mut *self.inner.ob_base.get_ptr() }
@@ -604,7 +696,7 @@ where
 impl<'p, T, U> PyRefMut<'p, T>
 where
     T: PyClass<BaseType = U>,
-    U: PyClass,
+    U: PyClass<Frozen = False>,
 {
     /// Get `PyRef<T::BaseType>`.
     /// See  [`PyRef::into_super`](struct.PyRef.html#method.into_super) for more.
@@ -651,7 +743,9 @@ impl<'a, T: PyClass> AsPyPointer for PyRefMut<'a, T> {
     }
 }
 
-impl<'a, T: PyClass> std::convert::TryFrom<&'a PyCell<T>> for crate::PyRefMut<'a, T> {
+impl<'a, T: PyClass<Frozen = False>> std::convert::TryFrom<&'a PyCell<T>>
+    for crate::PyRefMut<'a, T>
+{
     type Error = PyBorrowMutError;
     fn try_from(cell: &'a crate::PyCell<T>) -> Result<Self, Self::Error> {
         cell.try_borrow_mut()
@@ -1,7 +1,8 @@
 //! `PyClass` and related traits.
 use crate::class::methods::PyMethodDefType;
-use crate::pyclass_slots::{PyClassDict, PyClassWeakRef};
+use crate::pyclass_slots::{PyClassDict, PyClassEnterDepth, PyClassHashCache, PyClassWeakRef};
 use crate::type_object::PyLayout;
+use crate::types::PyType;
 use crate::{class::impl_::PyClassBaseType, class::impl_::PyClassImpl};
 use crate::{ffi, PyCell, PyErr, PyNativeType, PyResult, PyTypeInfo, Python};
 use std::convert::TryInto;
@@ -143,9 +144,35 @@ pub trait PyClass:
     type Dict: PyClassDict;
     /// Specify this class has `#[pyclass(weakref)]` or not.
     type WeakRef: PyClassWeakRef;
+    /// Specify this class has `#[pyclass(cache_hash)]` or not.
+    type HashCache: PyClassHashCache;
+    /// Specify this class has `#[pyclass(reentrant)]` or not.
+    type EnterDepth: PyClassEnterDepth;
     /// The closest native ancestor. This is `PyAny` by default, and when you declare
     /// `#[pyclass(extends=PyDict)]`, it's `PyDict`.
     type BaseNativeType: PyTypeInfo + PyNativeType;
+    /// Specify this class has `#[pyclass(frozen)]` or not. A frozen class can never be mutably
+    /// borrowed, so immutable borrows need no runtime check.
+    type Frozen: Boolean;
+}
+
+/// A type-level boolean, used for [`PyClass::Frozen`].
+pub trait Boolean {
+    const VALUE: bool;
+}
+
+/// Type-level `true`.
+pub struct True;
+
+/// Type-level `false`.
+pub struct False;
+
+impl Boolean for True {
+    const VALUE: bool = true;
+}
+
+impl Boolean for False {
+    const VALUE: bool = false;
 }
 
 /// For collecting slot items.
@@ -158,13 +185,25 @@ impl TypeSlots {
     }
 }
 
-fn tp_doc<T: PyClass>() -> PyResult<Option<*mut c_void>> {
-    Ok(match T::DOC {
-        "\0" => None,
-        s if s.as_bytes().ends_with(b"\0") => Some(s.as_ptr() as _),
-        // If the description is not null-terminated, create CString and leak it
-        s => Some(CString::new(s)?.into_raw() as _),
-    })
+/// The null-terminated doc string of the class, if it has one.
+///
+/// The text signature generated from `#[new]`, if any, is prepended, so that e.g.
+/// `inspect.signature` works on the class.
+fn tp_doc<T: PyClass>() -> PyResult<Option<&'static [u8]>> {
+    let doc = match T::get_new_text_signature() {
+        Some(text_signature) => format!(
+            "{}{}\n--\n\n{}",
+            T::NAME,
+            text_signature,
+            T::DOC.trim_end_matches('\0')
+        ),
+        None if T::DOC == "\0" => return Ok(None),
+        None if T::DOC.ends_with('\0') => return Ok(Some(T::DOC.as_bytes())),
+        None => T::DOC.to_string(),
+    };
+    // The type object lives for the rest of the program, so the doc string is leaked.
+    let doc = Box::leak(CString::new(doc)?.into_boxed_c_str());
+    Ok(Some(doc.to_bytes_with_nul()))
 }
 
 fn get_type_name<T: PyTypeInfo>(module_name: Option<&str>) -> PyResult<*mut c_char> {
@@ -189,8 +228,9 @@ where
 
     slots.push(ffi::Py_tp_base, T::BaseType::type_object_raw(py) as _);
     slots.push(ffi::Py_tp_dealloc, tp_dealloc::<T> as _);
-    if let Some(doc) = tp_doc::<T>()? {
-        slots.push(ffi::Py_tp_doc, doc);
+    let doc = tp_doc::<T>()?;
+    if let Some(doc) = doc {
+        slots.push(ffi::Py_tp_doc, doc.as_ptr() as _);
     }
 
     slots.push(ffi::Py_tp_new, T::get_new().unwrap_or(fallback_new) as _);
@@ -224,13 +264,22 @@ where
         has_gc_methods |= slot.slot == ffi::Py_tp_traverse;
         slots.0.push(*slot);
     });
+    // A managed dict must be visited by the GC; classes with `PyGCProtocol` do so in its slots.
+    let managed_dict = has_managed_dict::<T>(py);
+    #[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+    {
+        if managed_dict && !has_gc_methods {
+            slots.push(ffi::Py_tp_traverse, managed_dict_traverse::<T> as _);
+            slots.push(ffi::Py_tp_clear, managed_dict_clear::<T> as _);
+        }
+    }
 
     slots.push(0, ptr::null_mut());
     let mut spec = ffi::PyType_Spec {
         name: get_type_name::<T>(module_name)?,
         basicsize: std::mem::size_of::<T::Layout>() as c_int,
         itemsize: 0,
-        flags: py_class_flags(has_gc_methods, T::IS_GC, T::IS_BASETYPE),
+        flags: py_class_flags::<T>(has_gc_methods, managed_dict),
         slots: slots.0.as_mut_ptr(),
     };
 
@@ -238,14 +287,133 @@ where
     if type_object.is_null() {
         Err(PyErr::fetch(py))
     } else {
-        tp_init_additional::<T>(type_object as _);
+        tp_init_additional::<T>(type_object as _, doc);
+        set_metaclass::<T>(py, module_name, type_object)?;
+        register_abc::<T>(py, type_object)?;
         Ok(type_object as _)
     }
 }
 
+/// `PyType_FromSpec` always creates classes with metaclass `type`. If `T` needs metaclass
+/// methods or slots, this creates a metaclass holding them and makes it the type of `type_object`.
+///
+/// The metaclass derives from the metaclass of `T`'s base, and classes without metaclass methods
+/// of their own still take their base's metaclass, so that the hooks are inherited.
+fn set_metaclass<T: PyClass>(
+    py: Python,
+    module_name: Option<&str>,
+    type_object: *mut ffi::PyObject,
+) -> PyResult<()> {
+    let base_metaclass = unsafe { ffi::Py_TYPE(T::BaseType::type_object_raw(py) as _) };
+    let methods = py_class_method_defs(&T::for_each_metaclass_method_def);
+    let mut slots = TypeSlots::default();
+    T::for_each_metaclass_slot(&mut |slot| slots.push(slot.slot, slot.pfunc));
+    let metaclass = if methods.is_empty() && slots.0.is_empty() {
+        if ptr::eq(base_metaclass, unsafe { &ffi::PyType_Type }) {
+            return Ok(());
+        }
+        unsafe { ffi::Py_INCREF(base_metaclass as _) };
+        base_metaclass
+    } else {
+        slots.push(ffi::Py_tp_base, base_metaclass as _);
+        if !methods.is_empty() {
+            slots.push(ffi::Py_tp_methods, into_raw(methods));
+        }
+        slots.push(0, ptr::null_mut());
+        let name = match module_name {
+            Some(module_name) => format!("{}.{}Meta", module_name, T::NAME),
+            None => format!("builtins.{}Meta", T::NAME),
+        };
+        let mut spec = ffi::PyType_Spec {
+            name: CString::new(name)?.into_raw(),
+            basicsize: 0,
+            itemsize: 0,
+            // Subclasses with hooks of their own derive their metaclass from this one.
+            flags: (ffi::Py_TPFLAGS_DEFAULT | ffi::Py_TPFLAGS_BASETYPE) as c_uint,
+            slots: slots.0.as_mut_ptr(),
+        };
+        let metaclass = unsafe { ffi::PyType_FromSpec(&mut spec) };
+        if metaclass.is_null() {
+            return Err(PyErr::fetch(py));
+        }
+        metaclass as *mut ffi::PyTypeObject
+    };
+    // The reference to the metaclass is owned by `type_object`; the previous type,
+    // `PyType_Type`, is a static type and needs no reference count adjustment.
+    unsafe { (*type_object).ob_type = metaclass };
+    Ok(())
+}
+
+/// Registers `T` with `collections.abc.Sequence` or `collections.abc.Mapping` when it was declared
+/// with `#[pyclass(sequence)]` or `#[pyclass(mapping)]`, so that `isinstance` recognizes it.
+fn register_abc<T: PyClass>(py: Python, type_object: *mut ffi::PyObject) -> PyResult<()> {
+    let abc = if T::IS_SEQUENCE {
+        "Sequence"
+    } else if T::IS_MAPPING {
+        "Mapping"
+    } else {
+        return Ok(());
+    };
+    let ty = unsafe { PyType::from_type_ptr(py, type_object as _) };
+    py.import("collections.abc")?
+        .getattr(abc)?
+        .call_method1("register", (ty,))?;
+    Ok(())
+}
+
+/// Whether the interpreter manages the `__dict__` of `T`, which is the case for `#[pyclass(dict)]` on
+/// Python 3.12 and up. A base type with a dict slot of its own, such as an exception, keeps
+/// providing the dict instead.
+#[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+fn has_managed_dict<T: PyClass>(py: Python) -> bool {
+    let base = T::BaseType::type_object_raw(py);
+    T::Dict::IS_MANAGED
+        && unsafe {
+            ffi::PyType_HasFeature(base, ffi::Py_TPFLAGS_MANAGED_DICT) != 0
+                || (*base).tp_dictoffset == 0
+        }
+}
+
+#[cfg(not(all(Py_3_12, not(Py_LIMITED_API))))]
+fn has_managed_dict<T: PyClass>(_py: Python) -> bool {
+    // Always false: only the Python 3.12+ dict slot is managed.
+    T::Dict::IS_MANAGED
+}
+
+/// `tp_traverse` of a `#[pyclass(dict)]` without `PyGCProtocol`, on Python 3.12 and up: visits
+/// the managed dict, then whatever the base type visits.
+#[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+unsafe extern "C" fn managed_dict_traverse<T: PyClass>(
+    slf: *mut ffi::PyObject,
+    visit: ffi::visitproc,
+    arg: *mut c_void,
+) -> c_int {
+    let result = ffi::PyObject_VisitManagedDict(slf, visit, arg);
+    if result != 0 {
+        return result;
+    }
+    let pool = crate::GILPool::new();
+    match (*T::BaseType::type_object_raw(pool.python())).tp_traverse {
+        Some(traverse) => traverse(slf, visit, arg),
+        None => 0,
+    }
+}
+
+/// `tp_clear` of a `#[pyclass(dict)]` without `PyGCProtocol`, on Python 3.12 and up: clears the
+/// managed dict, then whatever the base type clears.
+#[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+unsafe extern "C" fn managed_dict_clear<T: PyClass>(slf: *mut ffi::PyObject) -> c_int {
+    ffi::PyObject_ClearManagedDict(slf);
+    let pool = crate::GILPool::new();
+    match (*T::BaseType::type_object_raw(pool.python())).tp_clear {
+        Some(clear) => clear(slf),
+        None => 0,
+    }
+}
+
 /// Additional type initializations necessary before Python 3.10
 #[cfg(all(not(Py_LIMITED_API), not(Py_3_10)))]
-fn tp_init_additional<T: PyClass>(type_object: *mut ffi::PyTypeObject) {
+fn tp_init_additional<T: PyClass>(type_object: *mut ffi::PyTypeObject, doc: Option<&[u8]>) {
     // Just patch the type objects for the things there's no
     // PyType_FromSpec API for... there's no reason this should work,
     // except for that it does and we have tests.
@@ -253,15 +421,15 @@ fn tp_init_additional<T: PyClass>(type_object: *mut ffi::PyTypeObject) {
     // Running this causes PyPy to segfault.
     #[cfg(all(not(PyPy), not(Py_3_10)))]
     {
-        if T::DOC != "\0" {
+        if let Some(doc) = doc {
             unsafe {
                 // Until CPython 3.10, tp_doc was treated specially for
                 // heap-types, and it removed the text_signature value from it.
                 // We go in after the fact and replace tp_doc with something
                 // that _does_ include the text_signature value!
                 ffi::PyObject_Free((*type_object).tp_doc as _);
-                let data = ffi::PyObject_Malloc(T::DOC.len());
-                data.copy_from(T::DOC.as_ptr() as _, T::DOC.len());
+                let data = ffi::PyObject_Malloc(doc.len());
+                data.copy_from(doc.as_ptr() as _, doc.len());
                 (*type_object).tp_doc = data as _;
             }
         }
@@ -297,17 +465,33 @@ fn tp_init_additional<T: PyClass>(type_object: *mut ffi::PyTypeObject) {
 }
 
 #[cfg(any(Py_LIMITED_API, Py_3_10))]
-fn tp_init_additional<T: PyClass>(_type_object: *mut ffi::PyTypeObject) {}
+fn tp_init_additional<T: PyClass>(_type_object: *mut ffi::PyTypeObject, _doc: Option<&[u8]>) {}
 
-fn py_class_flags(has_gc_methods: bool, is_gc: bool, is_basetype: bool) -> c_uint {
-    let mut flags = if has_gc_methods || is_gc {
+fn py_class_flags<T: PyClass>(has_gc_methods: bool, managed_dict: bool) -> c_uint {
+    // A managed dict may hold references to the object, so the class must take part in the GC.
+    let mut flags = if has_gc_methods || T::IS_GC || managed_dict {
         ffi::Py_TPFLAGS_DEFAULT | ffi::Py_TPFLAGS_HAVE_GC
     } else {
         ffi::Py_TPFLAGS_DEFAULT
     };
-    if is_basetype {
+    if T::IS_BASETYPE {
         flags |= ffi::Py_TPFLAGS_BASETYPE;
     }
+    #[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+    {
+        if managed_dict {
+            flags |= ffi::Py_TPFLAGS_MANAGED_DICT;
+        }
+    }
+    #[cfg(Py_3_10)]
+    {
+        if T::IS_SEQUENCE {
+            flags |= ffi::Py_TPFLAGS_SEQUENCE;
+        }
+        if T::IS_MAPPING {
+            flags |= ffi::Py_TPFLAGS_MAPPING;
+        }
+    }
     flags.try_into().unwrap()
 }
 
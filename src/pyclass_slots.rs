@@ -1,10 +1,13 @@
 //! This module contains additional fields for `#[pyclass]`..
 //! Mainly used by our proc-macro codes.
-use crate::{ffi, Python};
+use crate::{ffi, PyObject, Python};
+use std::cell::Cell;
 
 /// Represents `__dict__` field for `#[pyclass]`.
 pub trait PyClassDict {
     const IS_DUMMY: bool = true;
+    /// Whether the interpreter stores the dict, rather than this field.
+    const IS_MANAGED: bool = false;
     fn new() -> Self;
     fn clear_dict(&mut self, _py: Python) {}
     private_decl! {}
@@ -18,6 +21,32 @@ pub trait PyClassWeakRef {
     private_decl! {}
 }
 
+/// Represents the cached `__hash__` field for `#[pyclass]`.
+pub trait PyClassHashCache {
+    const IS_DUMMY: bool = true;
+    fn new() -> Self;
+    fn get(&self) -> Option<ffi::Py_hash_t> {
+        None
+    }
+    fn set(&self, _hash: ffi::Py_hash_t) {}
+    private_decl! {}
+}
+
+/// Represents the `with` nesting depth of `#[pyclass(reentrant)]`.
+pub trait PyClassEnterDepth {
+    const IS_DUMMY: bool = true;
+    fn new() -> Self;
+    fn depth(&self) -> usize {
+        0
+    }
+    fn set_depth(&self, _depth: usize) {}
+    fn resource(&self, _py: Python) -> Option<PyObject> {
+        None
+    }
+    fn set_resource(&self, _resource: Option<PyObject>) {}
+    private_decl! {}
+}
+
 /// Zero-sized dummy field.
 pub struct PyClassDummySlot;
 
@@ -35,12 +64,28 @@ impl PyClassWeakRef for PyClassDummySlot {
     }
 }
 
+impl PyClassHashCache for PyClassDummySlot {
+    private_impl! {}
+    fn new() -> Self {
+        PyClassDummySlot
+    }
+}
+
+impl PyClassEnterDepth for PyClassDummySlot {
+    private_impl! {}
+    fn new() -> Self {
+        PyClassDummySlot
+    }
+}
+
 /// Actual dict field, which holds the pointer to `__dict__`.
 ///
 /// `#[pyclass(dict)]` automatically adds this.
+#[cfg(not(all(Py_3_12, not(Py_LIMITED_API))))]
 #[repr(transparent)]
 pub struct PyClassDictSlot(*mut ffi::PyObject);
 
+#[cfg(not(all(Py_3_12, not(Py_LIMITED_API))))]
 impl PyClassDict for PyClassDictSlot {
     private_impl! {}
     const IS_DUMMY: bool = false;
@@ -54,6 +99,25 @@ impl PyClassDict for PyClassDictSlot {
     }
 }
 
+/// Zero-sized dict field of a class whose `__dict__` is managed by the interpreter.
+///
+/// `#[pyclass(dict)]` automatically adds this. From Python 3.12 the interpreter keeps the dict in
+/// front of the object (`Py_TPFLAGS_MANAGED_DICT`), so instances need no space of their own for it.
+/// If the base type has a dict slot, like an exception, that one is used instead. `PyCell` clears
+/// the managed dict when the object is dropped.
+#[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+pub struct PyClassDictSlot(());
+
+#[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+impl PyClassDict for PyClassDictSlot {
+    private_impl! {}
+    const IS_DUMMY: bool = false;
+    const IS_MANAGED: bool = true;
+    fn new() -> Self {
+        Self(())
+    }
+}
+
 /// Actual weakref field, which holds the pointer to `__weakref__`.
 ///
 /// `#[pyclass(weakref)]` automatically adds this.
@@ -72,3 +136,62 @@ impl PyClassWeakRef for PyClassWeakRefSlot {
         }
     }
 }
+
+/// Actual hash cache field, which holds the hash once it has been computed.
+///
+/// `#[pyclass(cache_hash)]` automatically adds this. It is only accessed with the GIL held; `-1`,
+/// which is never a valid hash, marks it as not yet computed.
+#[repr(transparent)]
+pub struct PyClassHashCacheSlot(Cell<ffi::Py_hash_t>);
+
+impl PyClassHashCache for PyClassHashCacheSlot {
+    private_impl! {}
+    const IS_DUMMY: bool = false;
+    fn new() -> Self {
+        Self(Cell::new(-1))
+    }
+    fn get(&self) -> Option<ffi::Py_hash_t> {
+        match self.0.get() {
+            -1 => None,
+            hash => Some(hash),
+        }
+    }
+    fn set(&self, hash: ffi::Py_hash_t) {
+        self.0.set(hash)
+    }
+}
+
+/// Actual enter depth field, which counts the nested `with` blocks and holds the resource returned
+/// by the outermost `__enter__`.
+///
+/// `#[pyclass(reentrant)]` automatically adds this. It is only accessed with the GIL held.
+pub struct PyClassEnterDepthSlot {
+    depth: Cell<usize>,
+    resource: Cell<Option<PyObject>>,
+}
+
+impl PyClassEnterDepth for PyClassEnterDepthSlot {
+    private_impl! {}
+    const IS_DUMMY: bool = false;
+    fn new() -> Self {
+        Self {
+            depth: Cell::new(0),
+            resource: Cell::new(None),
+        }
+    }
+    fn depth(&self) -> usize {
+        self.depth.get()
+    }
+    fn set_depth(&self, depth: usize) {
+        self.depth.set(depth)
+    }
+    fn resource(&self, py: Python) -> Option<PyObject> {
+        let resource = self.resource.take();
+        let copy = resource.as_ref().map(|r| r.clone_ref(py));
+        self.resource.set(resource);
+        copy
+    }
+    fn set_resource(&self, resource: Option<PyObject>) {
+        self.resource.set(resource)
+    }
+}
@@ -183,9 +183,10 @@ impl<'a> std::iter::IntoIterator for &'a PySet {
     }
 }
 
-impl<T> ToPyObject for collections::HashSet<T>
+impl<T, S> ToPyObject for collections::HashSet<T, S>
 where
     T: hash::Hash + Eq + ToPyObject,
+    S: hash::BuildHasher,
 {
     fn to_object(&self, py: Python) -> PyObject {
         let set = PySet::new::<T>(py, &[]).expect("Failed to construct empty set");
@@ -216,7 +217,7 @@ where
 impl<K, S> IntoPy<PyObject> for HashSet<K, S>
 where
     K: IntoPy<PyObject> + Eq + hash::Hash,
-    S: hash::BuildHasher + Default,
+    S: hash::BuildHasher,
 {
     fn into_py(self, py: Python) -> PyObject {
         let set = PySet::empty(py).expect("Failed to construct empty set");
@@ -329,9 +330,10 @@ mod hashbrown_hashset_conversion {
     use super::*;
     use crate::{FromPyObject, PyObject, PyResult, ToPyObject};
 
-    impl<T> ToPyObject for hashbrown::HashSet<T>
+    impl<T, S> ToPyObject for hashbrown::HashSet<T, S>
     where
         T: hash::Hash + Eq + ToPyObject,
+        S: hash::BuildHasher,
     {
         fn to_object(&self, py: Python) -> PyObject {
             let set = PySet::new::<T>(py, &[]).expect("Failed to construct empty set");
@@ -347,7 +349,7 @@ mod hashbrown_hashset_conversion {
     impl<K, S> IntoPy<PyObject> for hashbrown::HashSet<K, S>
     where
         K: IntoPy<PyObject> + Eq + hash::Hash,
-        S: hash::BuildHasher + Default,
+        S: hash::BuildHasher,
     {
         fn into_py(self, py: Python) -> PyObject {
             let set = PySet::empty(py).expect("Failed to construct empty set");
@@ -173,6 +173,19 @@ impl FromPyObject<'_> for String {
     }
 }
 
+/// Allows extracting strings from Python objects.
+/// Accepts Python `str` and `unicode` objects.
+///
+/// The result is always `Cow::Owned`, so that any lifetime can be extracted, e.g. for the keys of a
+/// `HashMap<Cow<'static, str>, V>`. Extract a `&str` to borrow from the Python object instead.
+impl<'a> FromPyObject<'_> for Cow<'a, str> {
+    fn extract(obj: &PyAny) -> PyResult<Self> {
+        <PyString as PyTryFrom>::try_from(obj)?
+            .to_str()
+            .map(|s| Cow::Owned(s.to_owned()))
+    }
+}
+
 impl FromPyObject<'_> for char {
     fn extract(obj: &PyAny) -> PyResult<Self> {
         let s = PyString::try_from(obj)?.to_str()?;
@@ -192,6 +205,7 @@ mod test {
     use super::PyString;
     use crate::Python;
     use crate::{FromPyObject, PyObject, PyTryFrom, ToPyObject};
+    use std::borrow::Cow;
 
     #[test]
     fn test_non_bmp() {
@@ -213,6 +227,19 @@ mod test {
         })
     }
 
+    #[test]
+    fn test_extract_cow() {
+        Python::with_gil(|py| {
+            let py_string = "Hello Python".to_object(py);
+            let s: Cow<'static, str> = py_string.extract(py).unwrap();
+            assert_eq!(s, "Hello Python");
+            match s {
+                Cow::Owned(_) => {}
+                Cow::Borrowed(_) => panic!("expected an owned string"),
+            }
+        })
+    }
+
     #[test]
     fn test_extract_char() {
         Python::with_gil(|py| {
@@ -58,6 +58,53 @@ fn unary_arithmetic() {
     py_run!(py, c, "assert repr(round(c, 1)) == 'UA(3)'");
 }
 
+#[pyclass(unary_ops)]
+struct Meters(f64);
+
+#[pyclass(unary_ops)]
+struct Count {
+    #[pyo3(get)]
+    value: i64,
+}
+
+#[test]
+fn generated_unary_ops() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let m = PyCell::new(py, Meters(-3.0)).unwrap();
+    let ty = py.get_type::<Meters>();
+    py_run!(py, m ty, "assert type(abs(m)) is ty");
+    py_run!(py, m ty, "assert type(-m) is ty and type(+m) is ty");
+    py_run!(py, m, "assert abs(m) is not m and +m is not m");
+
+    let abs: &PyCell<Meters> = m
+        .as_ref()
+        .call_method0("__abs__")
+        .unwrap()
+        .downcast()
+        .unwrap();
+    assert_eq!(abs.borrow().0, 3.0);
+    let neg: &PyCell<Meters> = m
+        .as_ref()
+        .call_method0("__neg__")
+        .unwrap()
+        .downcast()
+        .unwrap();
+    assert_eq!(neg.borrow().0, 3.0);
+    let pos: &PyCell<Meters> = m
+        .as_ref()
+        .call_method0("__pos__")
+        .unwrap()
+        .downcast()
+        .unwrap();
+    assert_eq!(pos.borrow().0, -3.0);
+
+    let c = PyCell::new(py, Count { value: 7 }).unwrap();
+    py_run!(py, c, "assert (-c).value == -7");
+    py_run!(py, c, "assert abs(-c).value == 7");
+}
+
 #[pyclass]
 struct BinaryArithmetic {}
 
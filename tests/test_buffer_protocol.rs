@@ -5,6 +5,7 @@ use pyo3::class::PyBufferProtocol;
 use pyo3::exceptions::PyBufferError;
 use pyo3::ffi;
 use pyo3::prelude::*;
+use pyo3::py_run;
 use pyo3::types::IntoPyDict;
 use pyo3::AsPyPointer;
 use std::ffi::CStr;
@@ -130,3 +131,70 @@ fn test_buffer_referenced() {
 
     assert!(drop_called.load(Ordering::Relaxed));
 }
+
+#[pyclass(buffer(data = data, shape = shape))]
+struct Matrix {
+    data: Vec<f64>,
+    shape: Vec<usize>,
+}
+
+#[pymethods]
+impl Matrix {
+    fn reshape(&mut self, shape: Vec<usize>) {
+        self.shape = shape;
+    }
+}
+
+#[test]
+fn test_shaped_buffer() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let matrix = Py::new(
+        py,
+        Matrix {
+            data: vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+            shape: vec![2, 3],
+        },
+    )
+    .unwrap();
+
+    let buf = PyBuffer::<f64>::get(matrix.as_ref(py)).unwrap();
+    assert_eq!(buf.shape(), &[2, 3]);
+    assert_eq!(buf.strides(), &[24, 8]);
+    assert!(buf.is_c_contiguous());
+    assert!(buf.readonly());
+    assert_eq!(buf.to_vec(py).unwrap(), vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    drop(buf);
+
+    let env = [("matrix", matrix)].into_py_dict(py);
+    py_run!(
+        py,
+        *env,
+        r#"
+view = memoryview(matrix)
+assert view.format == "d"
+assert view.shape == (2, 3)
+assert view.tolist() == [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]
+try:
+    matrix.reshape([3, 2])
+except RuntimeError:
+    pass
+else:
+    assert False, "the matrix must stay borrowed while it is exported"
+view.release()
+
+matrix.reshape([3, 2])
+assert memoryview(matrix).tolist() == [[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]
+matrix.reshape([])
+try:
+    memoryview(matrix)
+except BufferError:
+    pass
+else:
+    assert False, "a shape which doesn't match the data must be rejected"
+matrix.reshape([6])
+assert memoryview(matrix).tolist() == [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+"#
+    );
+}
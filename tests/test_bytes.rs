@@ -40,3 +40,58 @@ fn test_bytearray_vec_conversion() {
     let f = wrap_pyfunction!(bytes_vec_conversion)(py).unwrap();
     py_assert!(py, f, "f(bytearray(b'Hello World')) == b'Hello World'");
 }
+
+#[pyclass(bytes = to_bytes)]
+struct Header {
+    version: u8,
+    length: u16,
+}
+
+impl Header {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.version];
+        bytes.extend_from_slice(&self.length.to_be_bytes());
+        bytes
+    }
+}
+
+#[pyclass(bytes = encode)]
+struct Fallible {
+    valid: bool,
+}
+
+impl Fallible {
+    fn encode(&self) -> PyResult<Vec<u8>> {
+        if self.valid {
+            Ok(b"ok".to_vec())
+        } else {
+            Err(pyo3::exceptions::PyValueError::new_err("cannot encode"))
+        }
+    }
+}
+
+#[test]
+fn test_pyclass_bytes_method() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let header = Py::new(
+        py,
+        Header {
+            version: 1,
+            length: 258,
+        },
+    )
+    .unwrap();
+    py_assert!(py, header, "bytes(header) == b'\\x01\\x01\\x02'");
+    py_assert!(
+        py,
+        header,
+        "bytearray(bytes(header)) == bytearray(b'\\x01\\x01\\x02')"
+    );
+
+    let valid = Py::new(py, Fallible { valid: true }).unwrap();
+    py_assert!(py, valid, "bytes(valid) == b'ok'");
+    let invalid = Py::new(py, Fallible { valid: false }).unwrap();
+    py_expect_exception!(py, invalid, "bytes(invalid)", PyValueError);
+}
@@ -0,0 +1,98 @@
+#![cfg(all(feature = "bytes", not(Py_LIMITED_API)))]
+
+use bytes::Bytes;
+use pyo3::buffer::PyBuffer;
+use pyo3::class::PyBufferProtocol;
+use pyo3::exceptions::PyBufferError;
+use pyo3::ffi;
+use pyo3::prelude::*;
+use pyo3::AsPyPointer;
+use std::os::raw::{c_int, c_void};
+
+mod common;
+
+#[pyclass]
+struct Message {
+    #[pyo3(get, set)]
+    payload: Bytes,
+}
+
+#[test]
+fn bytes_field() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let msg = Py::new(
+        py,
+        Message {
+            payload: Bytes::from_static(b"hello"),
+        },
+    )
+    .unwrap();
+
+    py_assert!(py, msg, "msg.payload == b'hello'");
+    py_assert!(py, msg, "type(msg.payload) is bytes");
+    pyo3::py_run!(py, msg, "msg.payload = b'world'");
+    assert_eq!(msg.borrow(py).payload, Bytes::from_static(b"world"));
+    py_expect_exception!(py, msg, "msg.payload = 'world'", PyTypeError);
+}
+
+/// Exposes its `Bytes` through the buffer protocol, so that e.g. `memoryview` shares its memory.
+#[pyclass]
+struct SharedPayload {
+    payload: Bytes,
+}
+
+#[pyproto]
+impl PyBufferProtocol for SharedPayload {
+    fn bf_getbuffer(slf: PyRefMut<Self>, view: *mut ffi::Py_buffer, flags: c_int) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("View is null"));
+        }
+        // `Bytes` is immutable, and `view.obj` keeps `slf` (and thus the data) alive.
+        let ret = unsafe {
+            ffi::PyBuffer_FillInfo(
+                view,
+                slf.as_ptr(),
+                slf.payload.as_ptr() as *mut c_void,
+                slf.payload.len() as ffi::Py_ssize_t,
+                1,
+                flags,
+            )
+        };
+        if ret == -1 {
+            return Err(PyErr::fetch(slf.py()));
+        }
+        Ok(())
+    }
+
+    fn bf_releasebuffer(_slf: PyRefMut<Self>, _view: *mut ffi::Py_buffer) {}
+}
+
+#[test]
+fn bytes_zero_copy_buffer() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let data = Bytes::from(vec![1u8, 2, 3, 4]);
+    let shared = Py::new(
+        py,
+        SharedPayload {
+            payload: data.clone(),
+        },
+    )
+    .unwrap();
+
+    py_assert!(
+        py,
+        shared,
+        "bytes(memoryview(shared)) == b'\\x01\\x02\\x03\\x04'"
+    );
+    py_assert!(py, shared, "memoryview(shared).readonly");
+    py_expect_exception!(py, shared, "memoryview(shared)[0] = 5", PyTypeError);
+
+    let buffer = PyBuffer::<u8>::get(shared.as_ref(py)).unwrap();
+    assert_eq!(buffer.buf_ptr() as *const u8, data.as_ptr());
+    assert_eq!(buffer.to_vec(py).unwrap(), vec![1, 2, 3, 4]);
+    buffer.release(py);
+}
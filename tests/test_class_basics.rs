@@ -171,6 +171,24 @@ fn empty_class_in_module() {
     assert_eq!(module, "builtins");
 }
 
+const MODULE_NAME: &str = concat!(env!("CARGO_PKG_NAME"), ".classes");
+
+#[pyclass(module = MODULE_NAME)]
+struct ClassInConstModule {}
+
+#[pyclass(module = concat!(env!("CARGO_PKG_NAME"), ".other"))]
+struct ClassInMacroModule {}
+
+#[test]
+fn module_from_constant_expression() {
+    Python::with_gil(|py| {
+        let ty = py.get_type::<ClassInConstModule>();
+        py_assert!(py, ty, "ty.__module__ == 'pyo3.classes'");
+        let ty = py.get_type::<ClassInMacroModule>();
+        py_assert!(py, ty, "ty.__module__ == 'pyo3.other'");
+    });
+}
+
 #[pyclass]
 struct ClassWithObjectField {
     // It used to be that PyObject was not supported with (get, set)
@@ -328,3 +346,142 @@ fn test_tuple_struct_class() {
         py_assert!(py, typeobj, "typeobj.__name__ == 'TupleClass'");
     });
 }
+
+#[pyclass(set_name = name, set_owner = owner)]
+struct NamedDescriptor {
+    #[pyo3(get)]
+    name: Option<String>,
+    #[pyo3(get)]
+    owner: Option<PyObject>,
+}
+
+#[test]
+fn set_name() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let descriptor = Py::new(
+        py,
+        NamedDescriptor {
+            name: None,
+            owner: None,
+        },
+    )
+    .unwrap();
+
+    py_run!(
+        py,
+        descriptor,
+        r#"
+assert descriptor.name is None
+assert descriptor.owner is None
+
+# Equivalent to a class body assigning `attr = descriptor`.
+Owner = type("Owner", (), {"attr": descriptor})
+
+assert descriptor.name == "attr"
+assert descriptor.owner is Owner
+"#
+    );
+}
+
+#[pyclass(frozen)]
+struct FrozenCounter {
+    #[pyo3(get)]
+    value: usize,
+}
+
+#[pymethods]
+impl FrozenCounter {
+    fn double(&self) -> usize {
+        self.value * 2
+    }
+}
+
+#[test]
+fn frozen_class() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let counter = Py::new(py, FrozenCounter { value: 21 }).unwrap();
+
+    // Any number of borrows can coexist, also while Python code runs.
+    let first = counter.borrow(py);
+    let second = counter.try_borrow(py).unwrap();
+    py_assert!(py, counter, "counter.value == 21");
+    py_assert!(py, counter, "counter.double() == 42");
+    assert_eq!(first.value + second.value, 42);
+    drop(first);
+    drop(second);
+
+    py_expect_exception!(py, counter, "counter.value = 1", PyAttributeError);
+}
+
+struct Wrapper<T> {
+    inner: T,
+}
+
+/// A wrapped integer.
+#[pyclass]
+type IntWrapper = Wrapper<i64>;
+
+#[pyclass(name = "StrWrapper")]
+type StringWrapper = Wrapper<String>;
+
+#[pymethods]
+impl IntWrapper {
+    #[new]
+    fn new(inner: i64) -> Self {
+        Wrapper { inner }
+    }
+
+    #[getter]
+    fn inner(&self) -> i64 {
+        self.inner
+    }
+
+    fn doubled(&self) -> i64 {
+        self.inner * 2
+    }
+}
+
+#[pymethods]
+impl StringWrapper {
+    #[new]
+    fn new(inner: String) -> Self {
+        Wrapper { inner }
+    }
+
+    #[getter]
+    fn inner(&self) -> &str {
+        &self.inner
+    }
+
+    fn doubled(&self) -> String {
+        self.inner.repeat(2)
+    }
+}
+
+#[test]
+fn generic_struct_instantiations() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let int_wrapper = py.get_type::<IntWrapper>();
+    let str_wrapper = py.get_type::<StringWrapper>();
+    let from_rust = Py::new(py, Wrapper { inner: 5i64 }).unwrap();
+    py_run!(
+        py,
+        int_wrapper str_wrapper from_rust,
+        r#"
+        assert int_wrapper is not str_wrapper
+        assert (int_wrapper.__name__, str_wrapper.__name__) == ('IntWrapper', 'StrWrapper')
+        assert int_wrapper.__doc__ == 'A wrapped integer.'
+        assert int_wrapper(21).doubled() == 42
+        assert str_wrapper('ab').doubled() == 'abab'
+        assert isinstance(from_rust, int_wrapper) and not isinstance(from_rust, str_wrapper)
+        assert from_rust.inner == 5
+        "#
+    );
+    assert_eq!(
+        py.get_type::<Wrapper<String>>().name().unwrap(),
+        "StrWrapper"
+    );
+}
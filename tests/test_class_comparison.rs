@@ -0,0 +1,359 @@
+use pyo3::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod common;
+
+#[pyclass(eq)]
+#[derive(PartialEq)]
+struct EqClass {
+    value: i32,
+}
+
+#[test]
+fn eq_uses_partial_eq() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let a = Py::new(py, EqClass { value: 1 }).unwrap();
+    let b = Py::new(py, EqClass { value: 1 }).unwrap();
+    let c = Py::new(py, EqClass { value: 2 }).unwrap();
+
+    py_assert!(py, a b c, "a == b and not a != b");
+    py_assert!(py, a b c, "a != c and not a == c");
+    py_assert!(py, a, "a != 1");
+    py_expect_exception!(py, a b, "a < b", PyTypeError);
+}
+
+/// Like `f64::NAN`, never equal to anything, which makes the identity check observable.
+#[pyclass(eq)]
+struct NeverEqual {}
+
+impl PartialEq for NeverEqual {
+    fn eq(&self, _other: &Self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn eq_identity_implies_equality() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let a = Py::new(py, NeverEqual {}).unwrap();
+    let b = Py::new(py, NeverEqual {}).unwrap();
+
+    py_assert!(py, a b, "a == a and not a != a");
+    py_assert!(py, a b, "a != b and not a == b");
+
+    // The object isn't borrowed, so comparing it to itself works while it is mutably borrowed.
+    let _guard = a.borrow_mut(py);
+    py_assert!(py, a, "a == a");
+}
+
+#[pyclass(eq, eq_dict)]
+#[derive(PartialEq)]
+struct Point {
+    #[pyo3(get)]
+    x: i32,
+    #[pyo3(get, set)]
+    y: f64,
+    hidden: bool,
+}
+
+#[test]
+fn eq_dict() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let p = Py::new(
+        py,
+        Point {
+            x: 1,
+            y: 2.0,
+            hidden: true,
+        },
+    )
+    .unwrap();
+
+    py_assert!(py, p, "p == {'x': 1, 'y': 2.0}");
+    py_assert!(py, p, "{'x': 1, 'y': 2} == p");
+    py_assert!(py, p, "p != {'x': 1, 'y': 3.0}");
+    py_assert!(py, p, "p != {'x': 1}");
+    py_assert!(py, p, "p != {'x': 1, 'y': 2.0, 'hidden': True}");
+    py_assert!(py, p, "p != {'x': 1, 'z': 2.0}");
+    py_assert!(py, p, "p != [1, 2.0]");
+    assert!(p.borrow(py).hidden);
+}
+
+#[pyclass(eq, eq_tuple)]
+#[derive(PartialEq)]
+struct Coord {
+    #[pyo3(get)]
+    x: i32,
+    #[pyo3(get)]
+    y: i32,
+    label: &'static str,
+}
+
+#[test]
+fn eq_tuple() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let c = Py::new(
+        py,
+        Coord {
+            x: 1,
+            y: 2,
+            label: "origin",
+        },
+    )
+    .unwrap();
+
+    py_assert!(py, c, "c == (1, 2) and (1, 2) == c");
+    py_assert!(py, c, "c == (1.0, 2) and not c != (1, 2)");
+    py_assert!(py, c, "c != (2, 1)");
+    py_assert!(py, c, "c != (1,) and c != (1, 2, 'origin') and c != ()");
+    py_assert!(py, c, "c != [1, 2] and c != {'x': 1, 'y': 2}");
+    py_expect_exception!(py, c, "c < (1, 2)", PyTypeError);
+    assert_eq!(c.borrow(py).label, "origin");
+}
+
+#[pyclass(eq, hash_fields, frozen)]
+#[derive(PartialEq)]
+struct Version {
+    #[pyo3(get)]
+    major: u32,
+    #[pyo3(get)]
+    minor: u32,
+    #[pyo3(get)]
+    tag: String,
+}
+
+impl Version {
+    fn new(major: u32, minor: u32, tag: &str) -> Self {
+        Version {
+            major,
+            minor,
+            tag: tag.to_string(),
+        }
+    }
+}
+
+#[test]
+fn hash_fields() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let a = Py::new(py, Version::new(1, 2, "beta")).unwrap();
+    let b = Py::new(py, Version::new(1, 2, "beta")).unwrap();
+    let c = Py::new(py, Version::new(2, 1, "beta")).unwrap();
+    let d = Py::new(py, Version::new(1, 2, "rc")).unwrap();
+
+    py_assert!(py, a b, "a == b and hash(a) == hash(b)");
+    py_assert!(py, a, "hash(a) == hash((1, 2, 'beta'))");
+    py_assert!(py, a c d, "hash(a) != hash(c) and hash(a) != hash(d)");
+    py_assert!(py, a b c, "len({a, b, c}) == 2");
+}
+
+#[pyclass(eq, eq_tuple, hash_fields, frozen)]
+#[derive(PartialEq)]
+struct Span(u32, u32);
+
+#[test]
+fn tuple_struct_eq_hash() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let a = Py::new(py, Span(3, 7)).unwrap();
+    let b = Py::new(py, Span(3, 7)).unwrap();
+    let c = Py::new(py, Span(7, 3)).unwrap();
+
+    py_assert!(py, a b, "a == b and hash(a) == hash(b)");
+    py_assert!(py, a c, "a != c and hash(a) != hash(c)");
+    py_assert!(py, a, "a == (3, 7) and a != (7, 3) and a != (3,)");
+    py_assert!(py, a, "hash(a) == hash((3, 7))");
+    py_assert!(py, a b c, "len({a, b, c}) == 2");
+}
+
+static HASH_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts how often it is hashed.
+struct CountedKey(&'static str);
+
+impl Hash for CountedKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        HASH_CALLS.fetch_add(1, Ordering::SeqCst);
+        self.0.hash(state);
+    }
+}
+
+#[pyclass(hash, cache_hash, frozen)]
+#[derive(Hash)]
+struct CachedHash {
+    key: CountedKey,
+}
+
+#[test]
+fn cache_hash() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let a = Py::new(
+        py,
+        CachedHash {
+            key: CountedKey("a"),
+        },
+    )
+    .unwrap();
+    let b = Py::new(
+        py,
+        CachedHash {
+            key: CountedKey("a"),
+        },
+    )
+    .unwrap();
+    let c = Py::new(
+        py,
+        CachedHash {
+            key: CountedKey("c"),
+        },
+    )
+    .unwrap();
+    assert_eq!(HASH_CALLS.load(Ordering::SeqCst), 0);
+
+    let hash = a.as_ref(py).hash().unwrap();
+    assert_eq!(HASH_CALLS.load(Ordering::SeqCst), 1);
+    py_assert!(py, a, "hash(a) == hash(a)");
+    assert_eq!(a.as_ref(py).hash().unwrap(), hash);
+    assert_eq!(HASH_CALLS.load(Ordering::SeqCst), 1);
+
+    py_assert!(py, a b c, "hash(a) == hash(b) and hash(a) != hash(c)");
+    assert_eq!(HASH_CALLS.load(Ordering::SeqCst), 3);
+}
+
+#[pyclass]
+struct Account {
+    #[pyo3(get, hash)]
+    id: u64,
+    #[pyo3(get, set)]
+    balance: i64,
+    label: String,
+}
+
+#[test]
+fn hash_key_field() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let a = Py::new(
+        py,
+        Account {
+            id: 1,
+            balance: 10,
+            label: "a".to_string(),
+        },
+    )
+    .unwrap();
+    let b = Py::new(
+        py,
+        Account {
+            id: 1,
+            balance: -5,
+            label: "b".to_string(),
+        },
+    )
+    .unwrap();
+    let c = Py::new(
+        py,
+        Account {
+            id: 2,
+            balance: 10,
+            label: "a".to_string(),
+        },
+    )
+    .unwrap();
+    assert_ne!(a.borrow(py).label, b.borrow(py).label);
+
+    py_assert!(py, a b c, "a == b and not a != b and hash(a) == hash(b)");
+    py_assert!(py, a b c, "a != c and not a == c");
+    py_assert!(py, a b c, "len({a, b, c}) == 2");
+    py_assert!(py, a, "a != 1 and a != (1,)");
+    b.borrow_mut(py).balance = 0;
+    py_assert!(py, a b, "a == b");
+}
+
+#[pyclass]
+struct Draft {
+    #[pyo3(get, hash)]
+    id: Option<u64>,
+    title: String,
+}
+
+#[test]
+fn hash_option_key_field() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let draft = |id, title: &str| {
+        Py::new(
+            py,
+            Draft {
+                id,
+                title: title.to_string(),
+            },
+        )
+        .unwrap()
+    };
+    let a = draft(None, "a");
+    let b = draft(None, "b");
+    let c = draft(Some(7), "c");
+    let d = draft(Some(7), "d");
+    assert_ne!(a.borrow(py).title, b.borrow(py).title);
+    assert_ne!(c.borrow(py).title, d.borrow(py).title);
+
+    py_assert!(py, a b, "a == b and hash(a) == hash(b)");
+    py_assert!(py, a c, "a != c");
+    py_assert!(py, c d, "c == d and hash(c) == hash(d)");
+    assert_eq!(
+        a.as_ref(py).hash().unwrap(),
+        pyo3::class::impl_::NONE_KEY_HASH
+    );
+
+    let mut hasher = DefaultHasher::new();
+    7u64.hash(&mut hasher);
+    assert_eq!(c.as_ref(py).hash().unwrap(), hasher.finish() as isize);
+}
+
+#[pyclass(ord)]
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Release {
+    major: u32,
+    minor: u32,
+}
+
+#[test]
+fn ord_uses_ord() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let r1_0 = Py::new(py, Release { major: 1, minor: 0 }).unwrap();
+    let r1_2 = Py::new(py, Release { major: 1, minor: 2 }).unwrap();
+    let r2_0 = Py::new(py, Release { major: 2, minor: 0 }).unwrap();
+    let r1_2b = Py::new(py, Release { major: 1, minor: 2 }).unwrap();
+
+    py_assert!(py, r1_0 r1_2, "r1_0 < r1_2 and r1_0 <= r1_2 and not r1_0 > r1_2");
+    py_assert!(py, r1_2 r2_0, "r2_0 > r1_2 and r2_0 >= r1_2 and r2_0 != r1_2");
+    py_assert!(py, r1_2 r1_2b, "r1_2 == r1_2b and r1_2 <= r1_2b and r1_2 >= r1_2b");
+    py_assert!(py, r1_2 r1_2b, "not r1_2 < r1_2b and not r1_2 != r1_2b");
+    py_assert!(
+        py,
+        r1_0 r1_2 r2_0,
+        "sorted([r2_0, r1_0, r1_2]) == [r1_0, r1_2, r2_0]"
+    );
+
+    py_assert!(py, r1_0, "r1_0 != (1, 0) and not r1_0 == 1");
+    py_expect_exception!(py, r1_0, "r1_0 < 1", PyTypeError);
+}
@@ -0,0 +1,228 @@
+use pyo3::prelude::*;
+use pyo3::py_run;
+use pyo3::types::PyList;
+use pyo3::AsPyPointer;
+
+mod common;
+
+#[pyclass(deepcopy)]
+struct Node {
+    #[pyo3(get, set)]
+    name: String,
+    #[pyo3(get, set)]
+    children: PyObject,
+}
+
+#[test]
+fn deepcopy_copies_py_fields() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let node = Py::new(
+        py,
+        Node {
+            name: "root".to_string(),
+            children: PyList::new(py, &[1, 2]).into(),
+        },
+    )
+    .unwrap();
+
+    py_run!(
+        py,
+        node,
+        r#"
+import copy
+copied = copy.deepcopy(node)
+assert copied is not node
+assert copied.name == "root"
+assert copied.children == [1, 2]
+assert copied.children is not node.children
+node.children.append(3)
+node.name = "changed"
+assert copied.children == [1, 2]
+assert copied.name == "root"
+"#
+    );
+}
+
+#[test]
+fn deepcopy_handles_cycles() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let node = Py::new(
+        py,
+        Node {
+            name: "root".to_string(),
+            children: PyList::empty(py).into(),
+        },
+    )
+    .unwrap();
+
+    py_run!(
+        py,
+        node,
+        r#"
+import copy
+node.children.append(node)
+memo = {}
+copied = copy.deepcopy(node, memo)
+assert copied is not node
+assert copied.children[0] is copied
+assert memo[id(node)] is copied
+"#
+    );
+}
+
+#[pyclass(copy)]
+#[derive(Clone)]
+struct Tagged {
+    #[pyo3(get, set)]
+    tag: String,
+    #[pyo3(get)]
+    items: Py<PyList>,
+}
+
+#[test]
+fn copy_clones_struct() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let tagged = Py::new(
+        py,
+        Tagged {
+            tag: "a".to_string(),
+            items: PyList::new(py, &[1]).into(),
+        },
+    )
+    .unwrap();
+
+    py_run!(
+        py,
+        tagged,
+        r#"
+import copy
+shallow = copy.copy(tagged)
+deep = copy.deepcopy(tagged)
+assert shallow is not tagged and deep is not tagged
+assert shallow.items is tagged.items
+assert deep.items is not tagged.items
+tagged.tag = "b"
+tagged.items.append(2)
+assert shallow.tag == "a" and deep.tag == "a"
+assert shallow.items == [1, 2]
+assert deep.items == [1]
+"#
+    );
+}
+
+#[pyclass(frozen, deepcopy)]
+struct FrozenNode {
+    #[pyo3(get)]
+    name: String,
+    #[pyo3(get)]
+    children: PyObject,
+}
+
+#[test]
+fn frozen_deepcopy() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let node = Py::new(
+        py,
+        FrozenNode {
+            name: "root".to_string(),
+            children: PyList::new(py, &[1]).into(),
+        },
+    )
+    .unwrap();
+
+    py_run!(
+        py,
+        node,
+        r#"
+import copy
+copied = copy.deepcopy(node)
+assert copied is not node
+assert copied.name == "root"
+assert copied.children == [1]
+assert copied.children is not node.children
+
+node.children.append(node)
+memo = {}
+copied = copy.deepcopy(node, memo)
+assert copied.children[1] is copied
+assert memo[id(node)] is copied
+"#
+    );
+}
+
+#[pyclass(frozen, copy)]
+#[derive(Clone)]
+struct FrozenTagged {
+    #[pyo3(get)]
+    tag: String,
+    #[pyo3(get)]
+    items: Py<PyList>,
+}
+
+#[test]
+fn frozen_copy() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let tagged = Py::new(
+        py,
+        FrozenTagged {
+            tag: "a".to_string(),
+            items: PyList::new(py, &[1]).into(),
+        },
+    )
+    .unwrap();
+
+    py_run!(
+        py,
+        tagged,
+        r#"
+import copy
+deep = copy.deepcopy(tagged)
+assert deep is not tagged and deep.tag == "a"
+assert deep.items == [1] and deep.items is not tagged.items
+"#
+    );
+}
+
+#[pyclass(frozen, clone)]
+struct Version {
+    #[pyo3(get)]
+    major: u32,
+}
+
+#[test]
+fn frozen_clone_copy_is_identity() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let version = Py::new(py, Version { major: 1 }).unwrap();
+    let refcnt = version.get_refcnt(py);
+    let copied = py
+        .import("copy")
+        .unwrap()
+        .getattr("copy")
+        .unwrap()
+        .call1((version.as_ref(py),))
+        .unwrap();
+    assert_eq!(copied.as_ptr(), version.as_ptr());
+    assert_eq!(version.get_refcnt(py), refcnt + 1);
+
+    py_run!(
+        py,
+        version,
+        r#"
+import copy
+assert copy.copy(version) is version
+assert copy.copy(version).major == 1
+"#
+    );
+}
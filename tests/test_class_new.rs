@@ -1,5 +1,7 @@
-use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::py_run;
+use pyo3::types::IntoPyDict;
 
 #[pyclass]
 struct EmptyClassWithNew {}
@@ -195,3 +197,218 @@ fn new_with_custom_error() {
     let err = typeobj.call0().unwrap_err();
     assert_eq!(err.to_string(), "ValueError: custom error");
 }
+
+#[pyclass(positional_new)]
+struct PositionalNew {
+    #[pyo3(get, set)]
+    x: i32,
+    #[pyo3(get)]
+    count: usize,
+    #[pyo3(get, set)]
+    label: String,
+}
+
+#[test]
+fn positional_new() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let typeobj = py.get_type::<PositionalNew>();
+
+    let obj = typeobj.call((1, "a"), None).unwrap();
+    let obj = obj.cast_as::<PyCell<PositionalNew>>().unwrap();
+    assert_eq!(obj.borrow().x, 1);
+    assert_eq!(obj.borrow().count, 0);
+    assert_eq!(obj.borrow().label, "a");
+
+    let kwargs = [("label", "b")].into_py_dict(py);
+    let obj = typeobj.call((2,), Some(kwargs)).unwrap();
+    let obj = obj.cast_as::<PyCell<PositionalNew>>().unwrap();
+    assert_eq!(obj.borrow().x, 2);
+    assert_eq!(obj.borrow().label, "b");
+
+    let err = typeobj.call((1,), None).unwrap_err();
+    assert!(err.is_instance::<PyTypeError>(py));
+    let err = typeobj.call((1, "a", 2), None).unwrap_err();
+    assert!(err.is_instance::<PyTypeError>(py));
+    let err = typeobj.call(("a", 1), None).unwrap_err();
+    assert!(err.is_instance::<PyTypeError>(py));
+}
+
+#[pyclass(lenient_new)]
+struct LenientNew {
+    #[pyo3(get, set)]
+    x: i32,
+    #[pyo3(get)]
+    count: usize,
+}
+
+#[test]
+fn lenient_new() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let typeobj = py.get_type::<LenientNew>();
+
+    let kwargs = [("x", 1), ("added_in_next_version", 2)].into_py_dict(py);
+    let obj = typeobj.call((), Some(kwargs)).unwrap();
+    let obj = obj.cast_as::<PyCell<LenientNew>>().unwrap();
+    assert_eq!(obj.borrow().x, 1);
+    assert_eq!(obj.borrow().count, 0);
+
+    let kwargs = [("count", 5)].into_py_dict(py);
+    let obj = typeobj.call((3,), Some(kwargs)).unwrap();
+    let obj = obj.cast_as::<PyCell<LenientNew>>().unwrap();
+    assert_eq!(obj.borrow().x, 3);
+    assert_eq!(obj.borrow().count, 0);
+
+    let kwargs = [("unknown", 1)].into_py_dict(py);
+    let err = typeobj.call((), Some(kwargs)).unwrap_err();
+    assert!(err.is_instance::<PyTypeError>(py));
+    let err = typeobj.call((1, 2), None).unwrap_err();
+    assert!(err.is_instance::<PyTypeError>(py));
+}
+
+#[pyclass(subclass, frozen, positional_new, intern = 2)]
+struct Interned {
+    #[pyo3(get)]
+    name: String,
+}
+
+#[test]
+fn intern() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let typeobj = py.get_type::<Interned>();
+
+    py_run!(
+        py,
+        typeobj,
+        r#"
+a = typeobj("a")
+assert typeobj("a") is a
+assert typeobj(name="a") is a
+assert typeobj("b") is not a
+assert typeobj("b") is typeobj("b")
+
+# The cache is full, so further instances are not cached.
+assert typeobj("c") is not typeobj("c")
+
+class Sub(typeobj):
+    pass
+
+assert Sub("a") is not a
+assert Sub("a") is not Sub("a")
+"#
+    );
+    let err = typeobj.call1((vec!["a"],)).unwrap_err();
+    assert!(err.is_instance::<PyTypeError>(py));
+}
+
+#[pyclass(frozen, positional_new, intern = 8)]
+struct InternedPoint {
+    #[pyo3(get)]
+    x: PyObject,
+}
+
+#[test]
+fn intern_is_immutable_and_typed() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let typeobj = py.get_type::<InternedPoint>();
+
+    py_run!(
+        py,
+        typeobj,
+        r#"
+a = typeobj(1)
+try:
+    a.x = 99
+except AttributeError:
+    pass
+else:
+    assert False, "interned instances must be immutable"
+assert typeobj(1) is a
+assert typeobj(1).x == 1
+
+assert typeobj(True) is not a
+assert typeobj(True).x is True
+assert typeobj(1.0) is not a
+assert type(typeobj(1.0).x) is float
+"#
+    );
+}
+
+fn check_bounds(bounds: &Bounds) -> PyResult<()> {
+    if bounds.low > bounds.high {
+        return Err(PyValueError::new_err(format!(
+            "low ({}) must not exceed high ({})",
+            bounds.low, bounds.high
+        )));
+    }
+    Ok(())
+}
+
+#[pyclass(positional_new, post_init = check_bounds)]
+struct Bounds {
+    #[pyo3(get, set)]
+    low: i32,
+    #[pyo3(get, set)]
+    high: i32,
+}
+
+#[pyclass(post_init = Window::validate)]
+struct Window {
+    #[pyo3(get)]
+    width: u32,
+    #[pyo3(get)]
+    height: u32,
+}
+
+impl Window {
+    fn validate(&self) -> PyResult<()> {
+        if self.width * 2 < self.height {
+            return Err(PyValueError::new_err("window is too narrow"));
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl Window {
+    #[new]
+    fn new(width: u32, height: u32) -> Self {
+        Window { width, height }
+    }
+}
+
+#[test]
+fn post_init() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let bounds = py.get_type::<Bounds>();
+    let obj = bounds.call1((1, 2)).unwrap();
+    assert_eq!(obj.cast_as::<PyCell<Bounds>>().unwrap().borrow().high, 2);
+    let err = bounds.call1((3, 2)).unwrap_err();
+    assert!(err.is_instance::<PyValueError>(py));
+    assert_eq!(
+        err.to_string(),
+        "ValueError: low (3) must not exceed high (2)"
+    );
+
+    let window = py.get_type::<Window>();
+    py_run!(
+        py,
+        window,
+        r#"
+assert window(4, 8).height == 8
+try:
+    window(2, 8)
+except ValueError as e:
+    assert str(e) == "window is too narrow"
+else:
+    assert False
+"#
+    );
+    // Objects created from Rust are not validated.
+    assert!(Py::new(py, Bounds { low: 3, high: 2 }).is_ok());
+}
@@ -6,6 +6,7 @@ fn test_compile_errors() {
     t.compile_fail("tests/ui/invalid_need_module_arg_position.rs");
     t.compile_fail("tests/ui/invalid_property_args.rs");
     t.compile_fail("tests/ui/invalid_pyclass_args.rs");
+    t.compile_fail("tests/ui/invalid_pyclass_enum.rs");
     t.compile_fail("tests/ui/invalid_pyfunctions.rs");
     t.compile_fail("tests/ui/invalid_pymethods.rs");
     t.compile_fail("tests/ui/invalid_pymethod_names.rs");
@@ -37,8 +38,25 @@ fn test_compile_errors() {
         t.compile_fail("tests/ui/deprecations.rs");
         t.compile_fail("tests/ui/invalid_frompy_derive.rs");
         t.compile_fail("tests/ui/invalid_pymethod_receiver.rs");
+        t.compile_fail("tests/ui/invalid_frozen_pyclass.rs");
+        t.compile_fail("tests/ui/invalid_pyclass_extends.rs");
+        t.compile_fail("tests/ui/invalid_freelist.rs");
+        t.compile_fail("tests/ui/invalid_pyclass_hash.rs");
+        t.compile_fail("tests/ui/invalid_pyclass_hash_key.rs");
+        t.compile_fail("tests/ui/invalid_pyclass_eq_richcmp.rs");
+        t.compile_fail("tests/ui/invalid_pyclass_slot_conflicts.rs");
+        t.compile_fail("tests/ui/invalid_pyclass_ord.rs");
+        t.compile_fail("tests/ui/invalid_text_signature.rs");
+        t.compile_fail("tests/ui/invalid_init_subclass.rs");
+        t.compile_fail("tests/ui/invalid_pyclass_copy.rs");
+        t.compile_fail("tests/ui/invalid_overridable.rs");
+        t.compile_fail("tests/ui/invalid_pyclass_get_all.rs");
+        t.compile_fail("tests/ui/invalid_pyclass_module.rs");
         t.compile_fail("tests/ui/pyclass_send.rs");
 
+        #[cfg(feature = "uom")]
+        t.compile_fail("tests/ui/invalid_uom_unit.rs");
+
         #[cfg(Py_LIMITED_API)]
         t.compile_fail("tests/ui/abi3_nativetype_inheritance.rs");
     }
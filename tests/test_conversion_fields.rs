@@ -0,0 +1,153 @@
+//! Round-trips a `#[pyo3(get, set)]` field of each type with an optional conversion through
+//! Python: the getter's value must pass the check, and assigning it back through the setter must
+//! restore the original Rust value.
+
+// Unused when none of the optional features is enabled.
+#[allow(unused_macros)]
+macro_rules! field_round_trip {
+    ($name:ident: $ty:ty = $value:expr, $check:literal) => {
+        mod $name {
+            use pyo3::prelude::*;
+
+            #[pyclass]
+            struct Holder {
+                #[pyo3(get, set)]
+                value: $ty,
+            }
+
+            #[test]
+            fn round_trip() {
+                let gil = Python::acquire_gil();
+                let py = gil.python();
+
+                let holder = Py::new(py, Holder { value: $value }).unwrap();
+                pyo3::py_run!(
+                    py,
+                    holder,
+                    concat!(
+                        "value = holder.value\n",
+                        "assert ",
+                        $check,
+                        "\nholder.value = value"
+                    )
+                );
+                let expected: $ty = $value;
+                assert_eq!(holder.borrow(py).value, expected);
+            }
+        }
+    };
+}
+
+#[cfg(feature = "beef")]
+field_round_trip!(
+    beef_cow: beef::Cow<'static, str> = beef::Cow::borrowed("moo"),
+    "value == 'moo'"
+);
+
+#[cfg(feature = "bstr")]
+field_round_trip!(
+    bstr_bstring: bstr::BString = bstr::BString::from(b"caf\xe9".to_vec()),
+    "value == b'caf\\xe9'"
+);
+
+#[cfg(feature = "camino")]
+field_round_trip!(
+    camino_utf8_path_buf: camino::Utf8PathBuf = "conf/settings.toml".into(),
+    "value == 'conf/settings.toml'"
+);
+
+#[cfg(feature = "chrono-tz")]
+field_round_trip!(
+    chrono_tz_tz: chrono_tz::Tz = chrono_tz::Tz::America__New_York,
+    "str(value) == 'America/New_York'"
+);
+
+#[cfg(feature = "cidr")]
+field_round_trip!(
+    cidr_ip_cidr: cidr::IpCidr = "10.0.0.0/8".parse().unwrap(),
+    "str(value) == '10.0.0.0/8'"
+);
+
+#[cfg(feature = "compact_str")]
+field_round_trip!(
+    compact_str_string: compact_str::CompactString = "short".into(),
+    "value == 'short'"
+);
+
+#[cfg(feature = "either")]
+field_round_trip!(
+    either_either: either::Either<i64, String> = either::Either::Right("three".to_string()),
+    "value == 'three'"
+);
+
+#[cfg(feature = "euclid")]
+field_round_trip!(
+    euclid_point: euclid::default::Point2D<f32> = euclid::point2(1.0, 2.5),
+    "value == (1.0, 2.5)"
+);
+
+#[cfg(feature = "heapless")]
+field_round_trip!(
+    heapless_vec: heapless::Vec<u16, 4> = heapless::Vec::from_slice(&[20, 21]).unwrap(),
+    "value == [20, 21]"
+);
+
+#[cfg(feature = "indexmap")]
+field_round_trip!(
+    indexmap_set: indexmap::IndexSet<String> = vec!["b".into(), "a".into()].into_iter().collect(),
+    "value == ['b', 'a']"
+);
+
+#[cfg(feature = "ipnetwork")]
+field_round_trip!(
+    ipnetwork_ip_network: ipnetwork::IpNetwork = "192.168.0.0/16".parse().unwrap(),
+    "str(value) == '192.168.0.0/16'"
+);
+
+#[cfg(feature = "jiff")]
+field_round_trip!(
+    jiff_timestamp: jiff::Timestamp = jiff::Timestamp::from_second(1_600_000_000).unwrap(),
+    "value.timestamp() == 1600000000"
+);
+
+#[cfg(feature = "nonmax")]
+field_round_trip!(
+    nonmax_u32: nonmax::NonMaxU32 = nonmax::NonMaxU32::new(3).unwrap(),
+    "value == 3"
+);
+
+#[cfg(feature = "semver")]
+field_round_trip!(
+    semver_version: semver::Version = semver::Version::new(0, 13, 2),
+    "value == '0.13.2'"
+);
+
+#[cfg(feature = "serde_bytes")]
+field_round_trip!(
+    serde_bytes_byte_buf: serde_bytes::ByteBuf = serde_bytes::ByteBuf::from(vec![0xca, 0xfe]),
+    "value == b'\\xca\\xfe'"
+);
+
+#[cfg(feature = "time")]
+field_round_trip!(
+    time_duration: time::Duration = time::Duration::milliseconds(1500),
+    "value.total_seconds() == 1.5"
+);
+
+#[cfg(feature = "tinyvec")]
+field_round_trip!(
+    tinyvec_tiny_vec: tinyvec::TinyVec<[i64; 4]> = vec![1, 2, 3].into_iter().collect(),
+    "value == [1, 2, 3]"
+);
+
+#[cfg(feature = "typed-index-collections")]
+field_round_trip!(
+    typed_index_tivec: typed_index_collections::TiVec<usize, String> = vec!["Depot".into()].into(),
+    "value == ['Depot']"
+);
+
+#[cfg(feature = "url")]
+field_round_trip!(
+    url_url: url::Url = url::Url::parse("https://example.com/api").unwrap(),
+    "value == 'https://example.com/api'"
+);
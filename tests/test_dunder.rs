@@ -9,6 +9,8 @@ use pyo3::prelude::*;
 use pyo3::types::{PySlice, PyType};
 use pyo3::{ffi, py_run, AsPyPointer, PyCell};
 use std::convert::TryFrom;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::{isize, iter};
 
 mod common;
@@ -79,6 +81,40 @@ fn iterator() {
     py_assert!(py, inst, "list(inst) == [5, 6, 7]");
 }
 
+#[pyclass(iterator)]
+struct Countdown {
+    remaining: u32,
+}
+
+#[pyproto]
+impl PyIterProtocol for Countdown {
+    fn __next__(mut slf: PyRefMut<Self>) -> Option<u32> {
+        let current = slf.remaining;
+        slf.remaining = current.checked_sub(1)?;
+        Some(current)
+    }
+}
+
+#[test]
+fn generated_iter_returns_self() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let inst = Py::new(py, Countdown { remaining: 3 }).unwrap();
+    py_assert!(py, inst, "iter(inst) is inst");
+    py_run!(
+        py,
+        inst,
+        r#"
+seen = []
+for x in inst:
+    seen.append(x)
+assert seen == [3, 2, 1]
+assert list(inst) == []
+"#
+    );
+}
+
 #[pyclass]
 struct StringMethods {}
 
@@ -117,6 +153,99 @@ fn string_methods() {
     py_expect_exception!(py, obj, "obj.__bytes__('unexpected argument')", PyTypeError);
 }
 
+#[pyclass(repr, repr_maxlen = 12)]
+struct Document {
+    #[pyo3(get)]
+    title: String,
+    #[pyo3(get)]
+    pages: u64,
+    body: String,
+}
+
+#[test]
+fn generated_repr_truncates_fields() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let doc = Py::new(
+        py,
+        Document {
+            title: "Notes".to_string(),
+            pages: 3,
+            body: "x".repeat(10_000),
+        },
+    )
+    .unwrap();
+    py_assert!(py, doc, "repr(doc) == \"Document(title='Notes', pages=3)\"");
+
+    doc.borrow_mut(py).pages = std::u64::MAX;
+    doc.borrow_mut(py).title = "A".repeat(1000);
+    py_assert!(
+        py,
+        doc,
+        "repr(doc) == \"Document(title='AAAAAAAA..., pages=184467440...)\""
+    );
+    // Each field's repr is at most 12 characters.
+    py_assert!(
+        py,
+        doc,
+        "len(repr(doc)) == len('Document(title=, pages=)') + 2 * 12"
+    );
+}
+
+#[pyclass(repr, str)]
+struct Temperature {
+    #[pyo3(get)]
+    celsius: f64,
+}
+
+impl std::fmt::Display for Temperature {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}°C", self.celsius)
+    }
+}
+
+#[test]
+fn generated_repr_and_str() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let temp = Py::new(py, Temperature { celsius: 21.5 }).unwrap();
+    py_assert!(py, temp, "repr(temp) == 'Temperature(celsius=21.5)'");
+    py_assert!(py, temp, "str(temp) == '21.5°C'");
+    py_assert!(py, temp, "f'{temp}' == '21.5°C'");
+    py_assert!(py, temp, "repr([temp]) == '[Temperature(celsius=21.5)]'");
+}
+
+#[pyclass(repr_debug)]
+#[derive(Debug)]
+struct Coordinate {
+    lat: f64,
+    lon: f64,
+    name: Option<String>,
+}
+
+#[test]
+fn generated_repr_debug() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let coord = Coordinate {
+        lat: 51.5,
+        lon: -0.12,
+        name: Some("London".to_string()),
+    };
+    let expected = format!("{:?}", coord);
+    let coord = Py::new(py, coord).unwrap();
+    py_assert!(py, coord expected, "repr(coord) == expected");
+    py_assert!(
+        py,
+        coord,
+        "repr(coord) == 'Coordinate { lat: 51.5, lon: -0.12, name: Some(\"London\") }'"
+    );
+    py_assert!(py, coord, "str(coord) == repr(coord)");
+}
+
 #[pyclass]
 struct Comparisons {
     val: i32,
@@ -413,6 +542,115 @@ fn context_manager() {
     assert!(c.exit_called);
 }
 
+#[pyclass]
+struct Connection {
+    #[pyo3(get)]
+    closed: bool,
+}
+
+#[pyclass(enter = acquire, exit = release)]
+struct Pool {
+    active: Option<Py<Connection>>,
+}
+
+impl Pool {
+    fn acquire(&mut self, py: Python) -> PyResult<Py<Connection>> {
+        let conn = Py::new(py, Connection { closed: false })?;
+        self.active = Some(conn.clone_ref(py));
+        Ok(conn)
+    }
+
+    fn release(&mut self, py: Python) {
+        if let Some(conn) = self.active.take() {
+            conn.borrow_mut(py).closed = true;
+        }
+    }
+}
+
+#[test]
+fn generated_context_manager() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let pool = PyCell::new(py, Pool { active: None }).unwrap();
+    py_run!(
+        py,
+        pool,
+        r#"
+with pool as conn:
+    assert conn is not pool
+    assert not conn.closed
+assert conn.closed
+
+try:
+    with pool as conn2:
+        raise ValueError("not suppressed")
+except ValueError:
+    pass
+else:
+    assert False
+assert conn2.closed and conn2 is not conn
+"#
+    );
+    assert!(pool.borrow().active.is_none());
+}
+
+#[pyclass(enter = lock, exit = unlock, reentrant)]
+struct ReentrantLock {
+    acquired: u32,
+    released: u32,
+}
+
+impl ReentrantLock {
+    fn lock(&mut self, py: Python) -> PyResult<Py<Connection>> {
+        self.acquired += 1;
+        Py::new(py, Connection { closed: false })
+    }
+
+    fn unlock(&mut self, _py: Python) {
+        self.released += 1;
+    }
+}
+
+#[test]
+fn reentrant_context_manager() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let lock = PyCell::new(
+        py,
+        ReentrantLock {
+            acquired: 0,
+            released: 0,
+        },
+    )
+    .unwrap();
+    py_run!(
+        py,
+        lock,
+        r#"
+with lock as outer:
+    with lock as inner:
+        assert inner is outer
+        with lock:
+            pass
+    try:
+        with lock:
+            raise ValueError("not suppressed")
+    except ValueError:
+        pass
+with lock as again:
+    assert again is not outer
+"#
+    );
+    {
+        let lock = lock.borrow();
+        assert_eq!(lock.acquired, 2);
+        assert_eq!(lock.released, 2);
+    }
+    py_expect_exception!(py, lock, "lock.__exit__(None, None, None)", PyRuntimeError);
+}
+
 #[test]
 fn test_basics() {
     let gil = Python::acquire_gil();
@@ -515,6 +753,35 @@ fn inherited_dict() {
     );
 }
 
+// Repeating `dict` on the subclass doesn't add a second dict slot
+#[pyclass(dict, extends=DunderDictSupport)]
+struct RepeatDict {
+    _value: usize,
+}
+
+#[test]
+#[cfg_attr(all(Py_LIMITED_API, not(Py_3_10)), ignore)]
+fn repeated_dict() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    assert_eq!(
+        std::mem::size_of::<PyCell<RepeatDict>>(),
+        std::mem::size_of::<PyCell<InheritDict>>()
+    );
+    let inst = PyCell::new(py, (RepeatDict { _value: 0 }, DunderDictSupport {})).unwrap();
+    let inherited = py.get_type::<InheritDict>();
+    py_run!(
+        py,
+        inst inherited,
+        r#"
+        assert type(inst).__basicsize__ == inherited.__basicsize__
+        assert type(inst).__dictoffset__ == inherited.__dictoffset__
+        inst.a = 1
+        assert inst.__dict__ == {'a': 1}
+    "#
+    );
+}
+
 #[pyclass(weakref, dict)]
 struct WeakRefDunderDictSupport {}
 
@@ -531,6 +798,65 @@ fn weakref_dunder_dict_support() {
     );
 }
 
+#[pyclass]
+struct NoDunderDict {}
+
+#[pyclass(dict, weakref)]
+struct DictCycle {}
+
+#[pymethods]
+impl DictCycle {
+    #[new]
+    fn new() -> Self {
+        DictCycle {}
+    }
+}
+
+// From Python 3.12 the interpreter manages the dict, in front of the object
+#[test]
+#[cfg(all(Py_3_12, not(Py_LIMITED_API)))]
+fn managed_dict() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    assert_eq!(
+        std::mem::size_of::<PyCell<DunderDictSupport>>(),
+        std::mem::size_of::<PyCell<NoDunderDict>>()
+    );
+    let cls = py.get_type::<DictCycle>();
+    py_run!(
+        py,
+        cls,
+        r#"
+        import gc, weakref
+        assert cls.__flags__ & (1 << 4)
+        inst = cls()
+        inst.a = 1
+        assert inst.__dict__ == {'a': 1}
+
+        # A cycle through the dict is collected
+        inst.me = inst
+        ref = weakref.ref(inst)
+        del inst
+        gc.collect()
+        assert ref() is None
+    "#
+    );
+}
+
+#[test]
+#[cfg(not(all(Py_3_12, not(Py_LIMITED_API))))]
+#[cfg_attr(all(Py_LIMITED_API, not(Py_3_9)), ignore)]
+fn dict_slot() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    assert!(
+        std::mem::size_of::<PyCell<DunderDictSupport>>()
+            > std::mem::size_of::<PyCell<NoDunderDict>>()
+    );
+    let ty = py.get_type::<DunderDictSupport>();
+    py_assert!(py, ty, "ty.__dictoffset__ > 0");
+}
+
 #[pyclass]
 struct ClassWithGetAttr {
     #[pyo3(get, set)]
@@ -553,6 +879,78 @@ fn getattr_doesnt_override_member() {
     py_assert!(py, inst, "inst.a == 8");
 }
 
+#[pyclass]
+struct Engine {
+    #[pyo3(get)]
+    name: String,
+    #[pyo3(get)]
+    horsepower: u32,
+}
+
+#[pymethods]
+impl Engine {
+    fn start(&self) -> String {
+        format!("{} started", self.name)
+    }
+}
+
+#[pyclass(proxy = engine)]
+struct Car {
+    engine: Py<Engine>,
+    #[pyo3(get)]
+    name: String,
+}
+
+#[pyclass(proxy = target)]
+struct Forwarder {
+    #[pyo3(set)]
+    target: PyObject,
+}
+
+#[test]
+fn generated_proxy_getattr() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let engine = Py::new(
+        py,
+        Engine {
+            name: "V8".to_string(),
+            horsepower: 450,
+        },
+    )
+    .unwrap();
+    let car = Py::new(
+        py,
+        Car {
+            engine,
+            name: "Roadster".to_string(),
+        },
+    )
+    .unwrap();
+
+    py_assert!(py, car, "car.start() == 'V8 started'");
+    py_assert!(py, car, "car.horsepower == 450");
+    // Attributes of the proxy itself are found first.
+    py_assert!(py, car, "car.name == 'Roadster'");
+    py_assert!(
+        py,
+        car,
+        "hasattr(car, 'start') and not hasattr(car, 'stop')"
+    );
+    py_expect_exception!(py, car, "car.stop", PyAttributeError);
+
+    let a = Py::new(py, Forwarder { target: py.None() }).unwrap();
+    let b = Py::new(py, Forwarder { target: py.None() }).unwrap();
+    py_run!(py, a, "a.target = [1, 2]; assert a.count(2) == 1");
+    py_run!(py, a, "a.target = a");
+    py_expect_exception!(py, a, "a.missing", PyAttributeError);
+    py_run!(py, a b, "a.target = b; b.target = a");
+    py_expect_exception!(py, a, "a.missing", PyRecursionError);
+    // Break the reference cycle.
+    py_run!(py, a b, "a.target = None; b.target = None");
+}
+
 /// Wraps a Python future and yield it once.
 #[pyclass]
 struct OnceFuture {
@@ -623,6 +1021,148 @@ loop.close()
         .unwrap();
 }
 
+#[pyclass(anext = receiver)]
+struct Inbox {
+    receiver: pyo3::class::pyasync::ChannelReceiver<String>,
+}
+
+#[pyclass]
+struct Outbox {
+    sender: Option<pyo3::class::pyasync::ChannelSender<String>>,
+}
+
+#[pymethods]
+impl Outbox {
+    fn send(&self, message: String) {
+        self.sender.as_ref().unwrap().send(message).unwrap();
+    }
+
+    fn close(&mut self) {
+        self.sender = None;
+    }
+}
+
+fn run_async(py: Python, source: &str, locals: &[(&str, PyObject)]) {
+    let globals = PyModule::import(py, "__main__").unwrap().dict();
+    for (name, value) in locals {
+        globals.set_item(name, value).unwrap();
+    }
+    py.run(source, Some(globals), None)
+        .map_err(|e| e.print(py))
+        .unwrap();
+}
+
+#[test]
+fn generated_anext_from_channel() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let (sender, receiver) = pyo3::class::pyasync::channel();
+    let inbox = Py::new(py, Inbox { receiver }).unwrap();
+    let outbox = Py::new(
+        py,
+        Outbox {
+            sender: Some(sender),
+        },
+    )
+    .unwrap();
+
+    // The producer runs on the same event loop, so this only completes if waiting for a message
+    // gives control back to the loop.
+    let source = pyo3::indoc::indoc!(
+        r#"
+import asyncio
+
+async def produce():
+    for message in ["a", "b", "c"]:
+        await asyncio.sleep(0.001)
+        outbox.send(message)
+    outbox.close()
+
+async def collect():
+    return [message async for message in inbox]
+
+async def main():
+    messages, _ = await asyncio.gather(collect(), produce())
+    return messages
+
+loop = asyncio.new_event_loop()
+assert loop.run_until_complete(main()) == ["a", "b", "c"]
+loop.close()
+assert inbox.__aiter__() is inbox
+"#
+    );
+    run_async(
+        py,
+        source,
+        &[("inbox", inbox.into_py(py)), ("outbox", outbox.into_py(py))],
+    );
+}
+
+/// Counts how often `async for` polls the wrapped receiver.
+struct CountingReceiver {
+    receiver: pyo3::class::pyasync::ChannelReceiver<u32>,
+    polls: Arc<AtomicUsize>,
+}
+
+impl pyo3::class::PyChannelReceiver for CountingReceiver {
+    type Item = u32;
+
+    fn poll_recv(&self, cx: &mut std::task::Context) -> std::task::Poll<Option<u32>> {
+        self.polls.fetch_add(1, Ordering::SeqCst);
+        self.receiver.poll_recv(cx)
+    }
+}
+
+#[pyclass(anext = receiver)]
+struct CountingInbox {
+    receiver: CountingReceiver,
+}
+
+#[test]
+fn generated_anext_waits_for_wake() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let (sender, receiver) = pyo3::class::pyasync::channel();
+    let polls = Arc::new(AtomicUsize::new(0));
+    let inbox = Py::new(
+        py,
+        CountingInbox {
+            receiver: CountingReceiver {
+                receiver,
+                polls: polls.clone(),
+            },
+        },
+    )
+    .unwrap();
+
+    // The messages come from another thread after the loop has been idle for a while.
+    let producer = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        sender.send(1).unwrap();
+        sender.send(2).unwrap();
+    });
+    let source = pyo3::indoc::indoc!(
+        r#"
+import asyncio
+
+async def collect():
+    return [message async for message in inbox]
+
+loop = asyncio.new_event_loop()
+assert loop.run_until_complete(collect()) == [1, 2]
+loop.close()
+"#
+    );
+    run_async(py, source, &[("inbox", inbox.into_py(py))]);
+    producer.join().unwrap();
+
+    // One poll finds the channel empty, then each wake-up finds one message or the end. A busy
+    // loop would have polled thousands of times while waiting for the producer.
+    assert!(polls.load(Ordering::SeqCst) <= 6, "{:?}", polls);
+}
+
 /// Increment the count when `__get__` is called.
 #[pyclass]
 struct DescrCounter {
@@ -675,3 +1215,75 @@ assert c.counter.count == 3
         .map_err(|e| e.print(py))
         .unwrap();
 }
+
+#[pyclass(array = to_ndarray)]
+struct Matrix {
+    rows: Vec<Vec<i64>>,
+}
+
+impl Matrix {
+    fn to_ndarray(&self) -> Vec<Vec<i64>> {
+        self.rows.clone()
+    }
+}
+
+#[test]
+fn array_method() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let m = Py::new(
+        py,
+        Matrix {
+            rows: vec![vec![1, 2, 3], vec![4, 5, 6]],
+        },
+    )
+    .unwrap();
+    py_assert!(
+        py,
+        m,
+        "m.__array__.__text_signature__ == '($self, dtype=None, copy=None)'"
+    );
+
+    // numpy is not a test dependency; check what is passed to `numpy.asarray` with a stand-in.
+    py_run!(
+        py,
+        m,
+        r#"
+import sys, types
+numpy = types.ModuleType('numpy')
+numpy.asarray = lambda data, dtype=None: ('array', data, dtype)
+saved = sys.modules.get('numpy')
+sys.modules['numpy'] = numpy
+try:
+    assert m.__array__() == ('array', [[1, 2, 3], [4, 5, 6]], None)
+    assert m.__array__('f8') == ('array', [[1, 2, 3], [4, 5, 6]], 'f8')
+    assert m.__array__(dtype=float, copy=True)[2] is float
+    try:
+        m.__array__(copy=False)
+    except ValueError:
+        pass
+    else:
+        assert False, '__array__(copy=False) should raise ValueError'
+finally:
+    if saved is None:
+        del sys.modules['numpy']
+    else:
+        sys.modules['numpy'] = saved
+"#
+    );
+
+    py_run!(
+        py,
+        m,
+        r#"
+try:
+    import numpy
+except ImportError:
+    pass
+else:
+    a = numpy.asarray(m)
+    assert a.shape == (2, 3) and a.dtype == numpy.int64
+    assert numpy.array(m, dtype=float).tolist() == [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]
+"#
+    );
+}
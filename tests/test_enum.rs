@@ -0,0 +1,222 @@
+use pyo3::prelude::*;
+use pyo3::{py_run, wrap_pyfunction};
+
+mod common;
+
+#[pyclass]
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum MyEnum {
+    Variant,
+    OtherVariant = 10,
+}
+
+#[pymethods]
+impl MyEnum {
+    fn is_variant(&self) -> bool {
+        *self == MyEnum::Variant
+    }
+}
+
+#[test]
+fn test_enum_class_attr() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let my_enum = py.get_type::<MyEnum>();
+    py_assert!(py, my_enum, "my_enum.Variant is my_enum.Variant");
+    py_assert!(py, my_enum, "isinstance(my_enum.Variant, my_enum)");
+    py_assert!(py, my_enum, "my_enum.Variant.is_variant()");
+    py_assert!(py, my_enum, "not my_enum.OtherVariant.is_variant()");
+}
+
+#[pyfunction]
+fn return_enum() -> MyEnum {
+    MyEnum::OtherVariant
+}
+
+#[pyfunction]
+fn enum_arg(e: MyEnum) -> bool {
+    e == MyEnum::Variant
+}
+
+#[test]
+fn test_enum_eq() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let my_enum = py.get_type::<MyEnum>();
+    let f = wrap_pyfunction!(return_enum)(py).unwrap();
+    let g = wrap_pyfunction!(enum_arg)(py).unwrap();
+    py_run!(
+        py,
+        my_enum f g,
+        r#"
+        # A new instance returned from Rust compares equal, but is not the class attribute.
+        assert f() == my_enum.OtherVariant
+        assert f() is not my_enum.OtherVariant
+        assert f() != my_enum.Variant
+        assert not (my_enum.Variant == my_enum.OtherVariant)
+        assert my_enum.Variant != 0
+        assert hash(f()) == hash(my_enum.OtherVariant)
+        assert {my_enum.Variant: 1}[my_enum.Variant] == 1
+        assert g(my_enum.Variant)
+        "#
+    );
+}
+
+#[test]
+fn test_enum_value() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let my_enum = py.get_type::<MyEnum>();
+    py_assert!(py, my_enum, "my_enum.Variant.value == 0");
+    py_assert!(py, my_enum, "int(my_enum.OtherVariant) == 10");
+    py_assert!(
+        py,
+        my_enum,
+        "repr(my_enum.OtherVariant) == 'MyEnum.OtherVariant'"
+    );
+
+    let variant = Py::new(py, MyEnum::Variant).unwrap();
+    assert_eq!(*variant.borrow(py), MyEnum::Variant);
+}
+
+#[pyclass]
+#[derive(Clone, Copy)]
+enum Selection {
+    #[pyo3(falsy)]
+    Nothing,
+    Single,
+    Many,
+}
+
+#[test]
+fn test_enum_falsy() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let selection = py.get_type::<Selection>();
+    py_assert!(py, selection, "not selection.Nothing");
+    py_assert!(py, selection, "bool(selection.Single)");
+    py_assert!(py, selection, "bool(selection.Many)");
+
+    // Without a falsy variant, all variants are truthy.
+    let my_enum = py.get_type::<MyEnum>();
+    py_assert!(py, my_enum, "my_enum.Variant and my_enum.OtherVariant");
+}
+
+#[pyclass(module = "test_enum_pickle")]
+#[derive(Clone, Copy)]
+enum Color {
+    Red,
+    r#Green,
+}
+
+#[test]
+fn test_enum_pickle() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let module = PyModule::new(py, "test_enum_pickle").unwrap();
+    module.add_class::<Color>().unwrap();
+    py.import("sys")
+        .unwrap()
+        .getattr("modules")
+        .unwrap()
+        .set_item("test_enum_pickle", module)
+        .unwrap();
+
+    let color = py.get_type::<Color>();
+    let red = Py::new(py, Color::Red).unwrap();
+    py_run!(
+        py,
+        color red,
+        r#"
+        import pickle
+        assert color.Green.__reduce__() == (getattr, (color, 'Green'))
+        for protocol in range(pickle.HIGHEST_PROTOCOL + 1):
+            assert pickle.loads(pickle.dumps(color.Green, protocol)) is color.Green
+        # A variant created from Rust unpickles as the class attribute.
+        assert pickle.loads(pickle.dumps(red)) is color.Red
+        "#
+    );
+}
+
+#[test]
+fn test_enum_contains() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let my_enum = py.get_type::<MyEnum>();
+    let f = wrap_pyfunction!(return_enum)(py).unwrap();
+    py_run!(
+        py,
+        my_enum f,
+        r#"
+        assert my_enum.Variant in my_enum
+        assert f() in my_enum
+        # An int is a member if it is the discriminant of a variant.
+        assert 0 in my_enum
+        assert 10 in my_enum
+        assert 1 not in my_enum
+        assert 2 ** 100 not in my_enum
+        assert "Variant" not in my_enum
+        assert None not in my_enum
+        "#
+    );
+
+    // Instances of other enums are not members, even with the same discriminant.
+    let selection = py.get_type::<Selection>();
+    py_assert!(py, my_enum selection, "selection.Nothing not in my_enum");
+}
+
+#[test]
+fn test_enum_getitem() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let my_enum = py.get_type::<MyEnum>();
+    py_assert!(py, my_enum, "my_enum['Variant'] is my_enum.Variant");
+    py_assert!(
+        py,
+        my_enum,
+        "my_enum['OtherVariant'] is my_enum.OtherVariant"
+    );
+    // Lookup is case-sensitive, and only by name.
+    py_expect_exception!(py, my_enum, "my_enum['variant']", PyKeyError);
+    py_expect_exception!(py, my_enum, "my_enum['Missing']", PyKeyError);
+    py_expect_exception!(py, my_enum, "my_enum[0]", PyKeyError);
+    py_run!(
+        py,
+        my_enum,
+        r#"
+        try:
+            my_enum['variant']
+        except KeyError as e:
+            assert e.args == ('variant',)
+        "#
+    );
+
+    // Raw identifiers are looked up without the `r#` prefix.
+    let color = py.get_type::<Color>();
+    py_assert!(py, color, "color['Green'] is color.Green");
+}
+
+#[pyclass(format)]
+#[derive(Clone, Copy)]
+enum Level {
+    Debug,
+    Warning,
+}
+
+#[test]
+fn test_enum_format() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let level = py.get_type::<Level>();
+    py_assert!(py, level, "f'{level.Warning:upper}' == 'WARNING'");
+    py_assert!(py, level, "f'{level.Debug:lower}' == 'debug'");
+    py_assert!(py, level, "format(level.Debug, 'upper') == 'DEBUG'");
+    // Without a spec, the variant formats like `str()`.
+    py_assert!(
+        py,
+        level,
+        "f'{level.Debug}' == str(level.Debug) == 'Level.Debug'"
+    );
+    py_expect_exception!(py, level, "f'{level.Debug:>10}'", PyValueError);
+    py_expect_exception!(py, level, "f'{level.Debug:Upper}'", PyValueError);
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "fixedbitset")]
+
+use fixedbitset::FixedBitSet;
+use pyo3::prelude::*;
+use pyo3::py_run;
+
+mod common;
+
+#[pyclass]
+struct Schedule {
+    #[pyo3(get, set, as_indices)]
+    weekdays: FixedBitSet,
+    #[pyo3(get, set)]
+    slots: FixedBitSet,
+}
+
+#[test]
+fn fixedbitset_fields() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let schedule = Py::new(
+        py,
+        Schedule {
+            weekdays: [0, 2, 4].iter().cloned().collect(),
+            slots: FixedBitSet::with_capacity(3),
+        },
+    )
+    .unwrap();
+
+    py_assert!(py, schedule, "schedule.weekdays == frozenset({0, 2, 4})");
+    py_run!(py, schedule, "schedule.weekdays = {1, 6}");
+    assert_eq!(
+        schedule.borrow(py).weekdays.ones().collect::<Vec<_>>(),
+        vec![1, 6]
+    );
+    assert_eq!(schedule.borrow(py).weekdays.len(), 7);
+    py_run!(py, schedule, "schedule.weekdays = schedule.weekdays | {3}");
+    py_assert!(py, schedule, "schedule.weekdays == frozenset({1, 3, 6})");
+    py_expect_exception!(py, schedule, "schedule.weekdays = {-1}", PyOverflowError);
+    py_expect_exception!(py, schedule, "schedule.weekdays = 3", PyTypeError);
+
+    py_assert!(py, schedule, "schedule.slots == [False, False, False]");
+    py_run!(py, schedule, "schedule.slots = [True, False, True, True]");
+    assert_eq!(
+        schedule.borrow(py).slots.ones().collect::<Vec<_>>(),
+        vec![0, 2, 3]
+    );
+    py_assert!(py, schedule, "schedule.slots == [True, False, True, True]");
+}
+
+#[pyclass(to_dict, repr, eq, eq_dict, positional_new)]
+#[derive(PartialEq)]
+struct Week {
+    #[pyo3(get, set, as_indices)]
+    days: FixedBitSet,
+}
+
+#[test]
+fn fixedbitset_fields_in_generated_methods() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let week = Py::new(
+        py,
+        Week {
+            days: [1, 3].iter().cloned().collect(),
+        },
+    )
+    .unwrap();
+    let cls = py.get_type::<Week>();
+    py_assert!(py, week, "week.to_dict() == {'days': frozenset({1, 3})}");
+    py_assert!(py, week, "repr(week) == 'Week(days=frozenset({1, 3}))'");
+    py_assert!(py, week, "week == {'days': frozenset({1, 3})}");
+    py_assert!(py, week cls, "cls({1, 3}).days == week.days");
+}
@@ -37,6 +37,25 @@ fn class_with_freelist() {
     }
 }
 
+mod sizes {
+    pub const FREELIST: usize = 2 * 4;
+}
+
+#[pyclass(freelist = sizes::FREELIST)]
+struct ClassWithConstFreelist {}
+
+#[test]
+fn class_with_const_freelist() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let inst = Py::new(py, ClassWithConstFreelist {}).unwrap();
+    let ptr = inst.as_ptr();
+    drop(inst);
+    let inst2 = Py::new(py, ClassWithConstFreelist {}).unwrap();
+    assert_eq!(ptr, inst2.as_ptr());
+}
+
 struct TestDropCall {
     drop_called: Arc<AtomicBool>,
 }
@@ -181,6 +200,90 @@ fn inherited_weakref() {
     );
 }
 
+#[repr(align(16))]
+struct Aligned;
+
+// Over-aligned, so that the thread checker and the dict and weakref slots leave padding at the end
+// of the object.
+#[pyclass(dict, weakref, unsendable, subclass)]
+struct AlignedWithSlots {
+    _aligned: Aligned,
+}
+
+#[pymethods]
+impl AlignedWithSlots {
+    #[new]
+    fn new() -> Self {
+        AlignedWithSlots { _aligned: Aligned }
+    }
+}
+
+#[test]
+#[cfg_attr(all(Py_LIMITED_API, not(Py_3_9)), ignore)]
+fn dict_and_weakref_with_padding() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let cls = py.get_type::<AlignedWithSlots>();
+    py_run!(
+        py,
+        cls,
+        r#"
+import gc, weakref
+class Sub(cls): pass
+class Value: pass
+for ty in (cls, Sub):
+    inst = ty()
+    inst.value = Value()
+    refs = weakref.ref(inst), weakref.ref(inst.value)
+    del inst
+    gc.collect()
+    assert refs[0]() is None and refs[1]() is None
+"#
+    );
+}
+
+#[pyclass(subclass)]
+struct SlotsBase {
+    _value: u64,
+}
+
+// Both slots are added by the subclass, after the fields of the base class.
+#[pyclass(extends = SlotsBase, weakref, dict)]
+struct SubclassWithSlots {
+    _flag: u8,
+}
+
+#[pymethods]
+impl SubclassWithSlots {
+    #[new]
+    fn new() -> (Self, SlotsBase) {
+        (SubclassWithSlots { _flag: 1 }, SlotsBase { _value: 2 })
+    }
+}
+
+#[test]
+#[cfg_attr(all(Py_LIMITED_API, not(Py_3_9)), ignore)]
+fn subclass_with_dict_and_weakref() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let cls = py.get_type::<SubclassWithSlots>();
+    py_run!(
+        py,
+        cls,
+        r#"
+import gc, weakref
+class Value: pass
+inst = cls()
+inst.value = Value()
+refs = weakref.ref(inst), weakref.ref(inst.value)
+assert inst.__dict__ == {"value": refs[1]()}
+del inst
+gc.collect()
+assert refs[0]() is None and refs[1]() is None
+"#
+    );
+}
+
 #[pyclass(subclass)]
 struct BaseClassWithDrop {
     data: Option<Arc<AtomicBool>>,
@@ -1,6 +1,13 @@
+use pyo3::class::impl_::PyClassLockedField;
 use pyo3::prelude::*;
 use pyo3::py_run;
 use pyo3::types::{IntoPyDict, PyList};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
 
 mod common;
 
@@ -101,6 +108,38 @@ fn getter_setter_autogen() {
     );
 }
 
+#[pyclass]
+struct Headers {
+    #[pyo3(get, set)]
+    values: HashMap<Cow<'static, str>, String>,
+}
+
+#[test]
+fn cow_keyed_hashmap() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let mut values = HashMap::new();
+    values.insert(Cow::Borrowed("host"), "example.org".to_string());
+    values.insert(Cow::Owned(format!("x-{}", "trace")), "1".to_string());
+    let headers = Py::new(py, Headers { values }).unwrap();
+
+    py_assert!(
+        py,
+        headers,
+        "headers.values == {'host': 'example.org', 'x-trace': '1'}"
+    );
+    py_run!(
+        py,
+        headers,
+        "headers.values = {'accept': '*/*', 'host': 'example.com'}"
+    );
+    let headers = headers.borrow(py);
+    assert_eq!(headers.values.len(), 2);
+    assert_eq!(headers.values["host"], "example.com");
+    assert_eq!(headers.values[&Cow::Borrowed("accept")], "*/*");
+}
+
 #[pyclass]
 struct RefGetterSetter {
     num: i32,
@@ -158,3 +197,815 @@ fn tuple_struct_getter_setter() {
     py_run!(py, inst, "inst.num = 20");
     py_assert!(py, inst, "inst.num == 20");
 }
+
+#[pyclass(to_dict)]
+struct ToDictClass {
+    #[pyo3(get, set)]
+    num: i32,
+    #[pyo3(get)]
+    items: Vec<String>,
+    #[pyo3(set)]
+    write_only: i32,
+    #[allow(dead_code)]
+    hidden: i32,
+}
+
+#[test]
+fn to_dict() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let inst = Py::new(
+        py,
+        ToDictClass {
+            num: 10,
+            items: vec!["a".to_string(), "b".to_string()],
+            write_only: 0,
+            hidden: 0,
+        },
+    )
+    .unwrap();
+
+    py_assert!(
+        py,
+        inst,
+        "inst.to_dict() == {'num': 10, 'items': ['a', 'b']}"
+    );
+    py_run!(py, inst, "inst.num = 20; inst.write_only = 1");
+    py_assert!(py, inst, "inst.to_dict()['num'] == 20");
+    assert_eq!(inst.borrow(py).write_only, 1);
+}
+
+static CLONE_STARTED: AtomicBool = AtomicBool::new(false);
+static OTHER_THREAD_RAN: AtomicBool = AtomicBool::new(false);
+
+/// A value whose `clone` only finishes once another thread has run Python code.
+struct Expensive(u64);
+
+impl Clone for Expensive {
+    fn clone(&self) -> Self {
+        CLONE_STARTED.store(true, Ordering::SeqCst);
+        let start = Instant::now();
+        while !OTHER_THREAD_RAN.load(Ordering::SeqCst) {
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "other thread could not acquire the GIL"
+            );
+            thread::sleep(Duration::from_millis(1));
+        }
+        Expensive(self.0)
+    }
+}
+
+impl IntoPy<PyObject> for Expensive {
+    fn into_py(self, py: Python) -> PyObject {
+        self.0.into_py(py)
+    }
+}
+
+#[pyclass]
+struct HeavyGetter {
+    #[pyo3(get, allow_threads)]
+    value: Expensive,
+}
+
+#[test]
+fn getter_allow_threads() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let obj = PyCell::new(
+        py,
+        HeavyGetter {
+            value: Expensive(42),
+        },
+    )
+    .unwrap();
+
+    let other = thread::spawn(|| {
+        while !CLONE_STARTED.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(1));
+        }
+        Python::with_gil(|py| py.run("assert sum(range(10)) == 45", None, None).unwrap());
+        OTHER_THREAD_RAN.store(true, Ordering::SeqCst);
+    });
+
+    py_assert!(py, obj, "obj.value == 42");
+    other.join().unwrap();
+}
+
+#[pyclass]
+struct SharedCounter {
+    #[pyo3(get, lock)]
+    count: Arc<parking_lot::Mutex<u32>>,
+    #[pyo3(get, lock)]
+    label: std::sync::Mutex<String>,
+}
+
+#[test]
+fn getter_lock() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let count = Arc::new(parking_lot::Mutex::new(1));
+    let obj = PyCell::new(
+        py,
+        SharedCounter {
+            count: count.clone(),
+            label: std::sync::Mutex::new("counter".to_string()),
+        },
+    )
+    .unwrap();
+    py_assert!(py, obj, "obj.count == 1");
+    py_assert!(py, obj, "obj.label == 'counter'");
+
+    // The other thread takes the lock and then waits for the GIL, so the getter must release the
+    // GIL while it waits for the lock.
+    let (locked, wait_for_lock) = mpsc::channel();
+    let other = thread::spawn(move || {
+        let mut guard = count.lock();
+        locked.send(()).unwrap();
+        Python::with_gil(|py| py.run("assert sum(range(10)) == 45", None, None).unwrap());
+        *guard = 2;
+    });
+    wait_for_lock.recv().unwrap();
+    py_assert!(py, obj, "obj.count == 2");
+    other.join().unwrap();
+
+    let count = &obj.borrow().count;
+    let value = <Arc<parking_lot::Mutex<u32>> as PyClassLockedField>::lock_clone(count, py);
+    assert_eq!(value.unwrap(), 2);
+}
+
+#[pyclass(to_dict, repr, eq, eq_dict, eq_tuple)]
+struct Gauge {
+    #[pyo3(get, lock)]
+    level: std::sync::Mutex<u32>,
+    #[pyo3(get)]
+    name: String,
+}
+
+impl PartialEq for Gauge {
+    fn eq(&self, other: &Self) -> bool {
+        *self.level.lock().unwrap() == *other.level.lock().unwrap() && self.name == other.name
+    }
+}
+
+#[test]
+fn lock_with_generated_methods() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let gauge = PyCell::new(
+        py,
+        Gauge {
+            level: std::sync::Mutex::new(3),
+            name: "tank".to_string(),
+        },
+    )
+    .unwrap();
+    py_assert!(py, gauge, "gauge.to_dict() == {'level': 3, 'name': 'tank'}");
+    py_assert!(py, gauge, "repr(gauge) == \"Gauge(level=3, name='tank')\"");
+    py_assert!(py, gauge, "gauge == {'level': 3, 'name': 'tank'}");
+    py_assert!(py, gauge, "gauge == (3, 'tank')");
+    *gauge.borrow().level.lock().unwrap() = 4;
+    py_assert!(py, gauge, "gauge.to_dict()['level'] == 4");
+}
+
+#[pyclass]
+struct Invoice {
+    #[pyo3(get, set, scale = 2)]
+    amount: i64,
+}
+
+#[test]
+fn scaled_fixed_point() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let inv = PyCell::new(py, Invoice { amount: 1234 }).unwrap();
+    py_run!(
+        py,
+        inv,
+        r#"
+from decimal import Decimal
+assert inv.amount == Decimal("12.34")
+assert str(inv.amount) == "12.34"
+inv.amount = 7
+assert inv.amount == 7 and str(inv.amount) == "7.00"
+"#
+    );
+    assert_eq!(inv.borrow().amount, 700);
+
+    py_run!(
+        py,
+        inv,
+        "inv.amount = __import__('decimal').Decimal('-0.5')"
+    );
+    assert_eq!(inv.borrow().amount, -50);
+    // Rounds half to even.
+    py_run!(
+        py,
+        inv,
+        "inv.amount = __import__('decimal').Decimal('0.125')"
+    );
+    assert_eq!(inv.borrow().amount, 12);
+    py_run!(py, inv, "inv.amount = 0.135");
+    assert_eq!(inv.borrow().amount, 14);
+
+    py_expect_exception!(py, inv, "inv.amount = '1.00'", PyTypeError);
+    py_expect_exception!(py, inv, "inv.amount = float('nan')", PyValueError);
+    py_expect_exception!(py, inv, "inv.amount = 10 ** 20", PyOverflowError);
+    assert_eq!(inv.borrow().amount, 14);
+}
+
+#[pyclass]
+struct Artifact {
+    #[pyo3(get, set, hex)]
+    sha256: [u8; 32],
+    #[pyo3(get, hex)]
+    salt: Vec<u8>,
+}
+
+#[test]
+fn hex_bytes() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let mut sha256 = [0u8; 32];
+    sha256[0] = 0xab;
+    sha256[31] = 0x01;
+    let artifact = PyCell::new(
+        py,
+        Artifact {
+            sha256,
+            salt: vec![0xde, 0xad],
+        },
+    )
+    .unwrap();
+
+    py_run!(
+        py,
+        artifact,
+        r#"
+assert artifact.sha256 == "ab" + "00" * 30 + "01"
+assert artifact.salt == "dead"
+artifact.sha256 = "0123456789ABCDEF" * 4
+assert artifact.sha256 == "0123456789abcdef" * 4
+"#
+    );
+    assert_eq!(
+        artifact.borrow().sha256[..8],
+        [1, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]
+    );
+
+    py_expect_exception!(py, artifact, "artifact.sha256 = 'ab' * 31", PyValueError);
+    py_expect_exception!(py, artifact, "artifact.sha256 = 'abc'", PyValueError);
+    py_expect_exception!(py, artifact, "artifact.sha256 = 'zz' * 32", PyValueError);
+    py_expect_exception!(py, artifact, "artifact.sha256 = b'\\x00' * 32", PyTypeError);
+    assert_eq!(artifact.borrow().sha256[31], 0xef);
+}
+
+#[pyclass]
+struct Attachment {
+    #[pyo3(get, set, base64)]
+    data: Vec<u8>,
+}
+
+#[test]
+fn base64_bytes() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let attachment = PyCell::new(
+        py,
+        Attachment {
+            data: (0..=255).collect(),
+        },
+    )
+    .unwrap();
+
+    py_run!(
+        py,
+        attachment,
+        r#"
+import base64
+assert attachment.data == base64.b64encode(bytes(range(256))).decode()
+for n in range(5):
+    data = bytes(range(250, 250 + n))
+    attachment.data = base64.b64encode(data).decode()
+    assert attachment.data == base64.b64encode(data).decode()
+"#
+    );
+    assert_eq!(attachment.borrow().data, vec![250, 251, 252, 253]);
+
+    for invalid in &[
+        "'abc'",
+        "'ab=c'",
+        "'ab==ab=='",
+        "'a==='",
+        "'ab!d'",
+        "'YWJj\\n'",
+    ] {
+        let code = format!("attachment.data = {}", invalid);
+        py_expect_exception!(py, attachment, &code, PyValueError);
+    }
+    py_expect_exception!(py, attachment, "attachment.data = b'YWJj'", PyTypeError);
+    assert_eq!(attachment.borrow().data, vec![250, 251, 252, 253]);
+}
+
+#[pyclass]
+struct Packet {
+    #[pyo3(get, set, as_bytes)]
+    payload: Vec<u8>,
+    #[pyo3(get, set)]
+    checksum: Vec<u8>,
+}
+
+#[test]
+fn as_bytes() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let packet = PyCell::new(
+        py,
+        Packet {
+            payload: vec![0, 1, 255],
+            checksum: vec![7],
+        },
+    )
+    .unwrap();
+
+    py_run!(
+        py,
+        packet,
+        r#"
+assert type(packet.payload) is bytes
+assert packet.payload == b"\x00\x01\xff"
+assert packet.checksum == [7]
+packet.payload = b"ok"
+assert packet.payload == b"ok"
+packet.payload = bytearray(b"\x10")
+"#
+    );
+    assert_eq!(packet.borrow().payload, vec![0x10]);
+
+    py_expect_exception!(py, packet, "packet.payload = [1, 2]", PyTypeError);
+    py_expect_exception!(py, packet, "packet.payload = 'ok'", PyTypeError);
+    assert_eq!(packet.borrow().payload, vec![0x10]);
+}
+
+#[pyclass]
+struct Nonce {
+    #[pyo3(get, set, as_bytes)]
+    value: [u8; 16],
+}
+
+#[test]
+fn byte_array_as_bytes() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let nonce = PyCell::new(py, Nonce { value: [7; 16] }).unwrap();
+
+    py_run!(
+        py,
+        nonce,
+        r#"
+assert nonce.value == b"\x07" * 16
+nonce.value = bytes(range(16))
+assert nonce.value == bytes(range(16))
+"#
+    );
+    assert_eq!(nonce.borrow().value[15], 15);
+
+    py_expect_exception!(py, nonce, "nonce.value = b'\\x00' * 15", PyValueError);
+    py_expect_exception!(py, nonce, "nonce.value = b'\\x00' * 17", PyValueError);
+    py_expect_exception!(py, nonce, "nonce.value = list(range(16))", PyTypeError);
+    assert_eq!(nonce.borrow().value[15], 15);
+}
+
+#[pyclass]
+struct Holder {
+    #[pyo3(get, set)]
+    data: PyObject,
+    #[pyo3(get, set)]
+    items: Py<PyList>,
+}
+
+#[test]
+fn py_object_fields() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let holder = PyCell::new(
+        py,
+        Holder {
+            data: py.None(),
+            items: PyList::empty(py).into(),
+        },
+    )
+    .unwrap();
+
+    py_run!(
+        py,
+        holder,
+        r#"
+import sys
+data = {"nested": [1, 2]}
+holder.data = data
+assert holder.data is data
+count = sys.getrefcount(data)
+for _ in range(10):
+    assert holder.data is data
+assert sys.getrefcount(data) == count
+items = [3]
+holder.items = items
+assert holder.items is items
+"#
+    );
+    assert_eq!(
+        holder
+            .borrow()
+            .items
+            .as_ref(py)
+            .get_item(0)
+            .extract::<i32>()
+            .unwrap(),
+        3
+    );
+
+    py_expect_exception!(py, holder, "holder.items = (1, 2)", PyTypeError);
+}
+
+#[pyclass(to_dict, repr)]
+struct Renamed {
+    #[pyo3(get, set, name = "count")]
+    internal_count: u32,
+    #[pyo3(get, name = "type")]
+    kind: String,
+}
+
+#[test]
+fn renamed_fields() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let inst = Py::new(
+        py,
+        Renamed {
+            internal_count: 1,
+            kind: "widget".to_string(),
+        },
+    )
+    .unwrap();
+    py_run!(
+        py,
+        inst,
+        r#"
+        assert inst.count == 1
+        assert inst.type == "widget"
+        assert not hasattr(inst, "internal_count")
+        inst.count = 5
+        assert inst.to_dict() == {"count": 5, "type": "widget"}
+        assert repr(inst) == "Renamed(count=5, type='widget')"
+        "#
+    );
+    assert_eq!(inst.borrow(py).internal_count, 5);
+    py_expect_exception!(py, inst, "inst.type = 'gadget'", PyAttributeError);
+}
+
+#[pyclass]
+struct Point3 {
+    #[pyo3(get)]
+    x: i32,
+    #[pyo3(get, set, name = "y_coord")]
+    y: i32,
+    #[pyo3(get, no_match_arg)]
+    z: i32,
+    #[pyo3(set)]
+    label: String,
+}
+
+#[test]
+fn match_args() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let point = Py::new(
+        py,
+        Point3 {
+            x: 1,
+            y: 2,
+            z: 3,
+            label: String::new(),
+        },
+    )
+    .unwrap();
+    py_assert!(py, point, "point.__match_args__ == ('x', 'y_coord')");
+    py_assert!(
+        py,
+        point,
+        "type(point).__match_args__ is point.__match_args__"
+    );
+    assert!(point.borrow(py).label.is_empty());
+
+    if py.version_info() >= (3, 10) {
+        // `match` is a syntax error before Python 3.10, so compile it only when available.
+        py_run!(
+            py,
+            point,
+            r#"
+Point3 = type(point)
+exec('''
+match point:
+    case Point3(x, y):
+        matched = (x, y)
+assert matched == (1, 2)
+''')
+"#
+        );
+    }
+
+    // Classes without `#[pyo3(get)]` fields get no `__match_args__`.
+    let cls = py.get_type::<ClassWithProperties>();
+    py_assert!(py, cls, "not hasattr(cls, '__match_args__')");
+}
+
+#[pyclass(match_args(y_coord, x))]
+struct ReorderedPoint {
+    #[pyo3(get)]
+    x: i32,
+    #[pyo3(get, name = "y_coord")]
+    y: i32,
+    #[pyo3(get)]
+    z: i32,
+}
+
+#[pyclass(match_args())]
+struct NoPositionalPattern {
+    #[pyo3(get)]
+    value: i32,
+}
+
+#[test]
+fn explicit_match_args() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let point = Py::new(py, ReorderedPoint { x: 1, y: 2, z: 3 }).unwrap();
+    py_assert!(py, point, "point.__match_args__ == ('y_coord', 'x')");
+    py_assert!(py, point, "point.z == 3");
+
+    let cls = py.get_type::<NoPositionalPattern>();
+    py_assert!(py, cls, "cls.__match_args__ == ()");
+
+    if py.version_info() >= (3, 10) {
+        py_run!(
+            py,
+            point,
+            r#"
+ReorderedPoint = type(point)
+exec('''
+match point:
+    case ReorderedPoint(a, b):
+        matched = (a, b)
+assert matched == (2, 1)
+''')
+"#
+        );
+    }
+}
+
+#[pyclass(get_all, set_all)]
+struct Config {
+    name: String,
+    retries: u32,
+    // An explicit `get` or `set` replaces `get_all` and `set_all` for the field.
+    #[pyo3(get)]
+    version: u32,
+    #[pyo3(name = "timeout_s")]
+    timeout: f64,
+}
+
+#[test]
+fn get_all_set_all() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let config = Py::new(
+        py,
+        Config {
+            name: "default".to_string(),
+            retries: 3,
+            version: 1,
+            timeout: 0.5,
+        },
+    )
+    .unwrap();
+    py_run!(
+        py,
+        config,
+        r#"
+assert (config.name, config.retries, config.version, config.timeout_s) == ('default', 3, 1, 0.5)
+config.name = 'fast'
+config.retries = 0
+config.timeout_s = 0.1
+assert config.__match_args__ == ('name', 'retries', 'version', 'timeout_s')
+"#
+    );
+    let borrowed = config.borrow(py);
+    assert_eq!(borrowed.name, "fast");
+    assert_eq!(borrowed.retries, 0);
+    assert_eq!(borrowed.timeout, 0.1);
+    drop(borrowed);
+
+    py_expect_exception!(py, config, "config.version = 2", PyAttributeError);
+    py_expect_exception!(py, config, "config.retries = -1", PyOverflowError);
+}
+
+#[pyclass]
+struct History {
+    #[pyo3(get, set)]
+    entries: VecDeque<String>,
+    #[pyo3(get, set, as_deque)]
+    recent: VecDeque<u32>,
+}
+
+#[test]
+fn vec_deque_fields() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let history = Py::new(
+        py,
+        History {
+            entries: vec!["a".to_string(), "b".to_string()].into(),
+            recent: vec![1, 2].into(),
+        },
+    )
+    .unwrap();
+    py_run!(
+        py,
+        history,
+        r#"
+from collections import deque
+assert history.entries == ['a', 'b']
+assert type(history.recent) is deque and history.recent == deque([1, 2])
+history.entries = deque(['c'])
+history.recent = [3, 4, 5]
+assert history.entries == ['c']
+assert history.recent == deque([3, 4, 5])
+"#
+    );
+    let borrowed = history.borrow(py);
+    assert_eq!(borrowed.entries, vec!["c".to_string()]);
+    assert_eq!(borrowed.recent, vec![3, 4, 5]);
+    drop(borrowed);
+
+    py_expect_exception!(py, history, "history.recent = 3", PyTypeError);
+}
+
+#[pyclass(to_dict, repr, eq, eq_dict, eq_tuple, positional_new)]
+#[derive(PartialEq)]
+struct Record {
+    #[pyo3(get, set, hex)]
+    id: Vec<u8>,
+    #[pyo3(get, set, base64)]
+    token: Vec<u8>,
+    #[pyo3(get, set, as_bytes)]
+    raw: [u8; 2],
+    #[pyo3(get, set, scale = 2)]
+    price: i64,
+    #[pyo3(get, set, as_deque)]
+    queue: VecDeque<u32>,
+    #[pyo3(get, set, as_frozenset)]
+    tags: BTreeSet<String>,
+}
+
+#[pyclass(frozen, hash_fields)]
+struct FrozenRecord {
+    #[pyo3(get, hex)]
+    id: Vec<u8>,
+    #[pyo3(get, scale = 1)]
+    price: i64,
+}
+
+#[test]
+fn field_conversions_in_generated_methods() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let record = Py::new(
+        py,
+        Record {
+            id: vec![0xab, 0xcd],
+            token: b"hi".to_vec(),
+            raw: [1, 2],
+            price: 1250,
+            queue: vec![1, 2].into(),
+            tags: vec!["a".to_string()].into_iter().collect(),
+        },
+    )
+    .unwrap();
+    let cls = py.get_type::<Record>();
+    py_run!(
+        py,
+        record cls,
+        r#"
+from collections import deque
+from decimal import Decimal
+values = {
+    'id': 'abcd',
+    'token': 'aGk=',
+    'raw': b'\x01\x02',
+    'price': Decimal('12.50'),
+    'queue': deque([1, 2]),
+    'tags': frozenset({'a'}),
+}
+# Every generated method sees the values of the getters.
+assert record.to_dict() == values
+for name, value in values.items():
+    assert getattr(record, name) == value
+assert repr(record) == (
+    "Record(id='abcd', token='aGk=', raw=b'\\x01\\x02', price=Decimal('12.50'), "
+    "queue=deque([1, 2]), tags=frozenset({'a'}))"
+)
+assert record == values
+assert record == tuple(values.values())
+assert record != dict(values, id='abce')
+
+# The constructor accepts what the setters accept.
+copy = cls('abcd', 'aGk=', b'\x01\x02', Decimal('12.50'), [1, 2], {'a'})
+assert copy == record
+assert copy.to_dict() == values
+try:
+    cls('abc', 'aGk=', b'\x01\x02', 1, [], set())
+except ValueError as e:
+    assert 'even number of digits' in str(e), str(e)
+else:
+    assert False
+try:
+    cls('abcd', 'aGk=', b'\x01\x02', '1', [], set())
+except TypeError as e:
+    assert "argument 'price'" in str(e), str(e)
+else:
+    assert False
+"#
+    );
+
+    let frozen = Py::new(
+        py,
+        FrozenRecord {
+            id: vec![0xab],
+            price: 15,
+        },
+    )
+    .unwrap();
+    py_run!(
+        py,
+        frozen,
+        "from decimal import Decimal; assert hash(frozen) == hash(('ab', Decimal('1.5')))"
+    );
+}
+
+#[pyclass]
+struct Contact {
+    #[pyo3(get, set)]
+    email: Option<String>,
+    #[pyo3(get, set)]
+    name: String,
+}
+
+#[test]
+fn option_field() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let contact = Py::new(
+        py,
+        Contact {
+            email: Some("a@example.com".to_string()),
+            name: "A".to_string(),
+        },
+    )
+    .unwrap();
+    py_run!(
+        py,
+        contact,
+        r#"
+assert contact.email == 'a@example.com'
+contact.email = None
+assert contact.email is None
+contact.email = 'b@example.com'
+assert contact.email == 'b@example.com'
+del contact.email
+assert contact.email is None
+"#
+    );
+    assert_eq!(contact.borrow(py).email, None);
+
+    py_expect_exception!(py, contact, "contact.email = 1", PyTypeError);
+    py_expect_exception!(py, contact, "del contact.name", PyAttributeError);
+    py_assert!(py, contact, "contact.name == 'A'");
+}
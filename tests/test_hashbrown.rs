@@ -0,0 +1,49 @@
+#![cfg(feature = "hashbrown")]
+
+use hashbrown::hash_map::DefaultHashBuilder;
+use pyo3::prelude::*;
+use pyo3::py_run;
+use std::collections::{HashMap, HashSet};
+
+mod common;
+
+/// `hashbrown`'s default hasher is `ahash`, which stands in for any custom `BuildHasher`.
+#[pyclass]
+struct Inventory {
+    #[pyo3(get, set)]
+    counts: HashMap<String, u32, DefaultHashBuilder>,
+    #[pyo3(get, set)]
+    tags: HashSet<String, DefaultHashBuilder>,
+}
+
+#[test]
+fn custom_hasher_fields() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let mut counts = HashMap::default();
+    counts.insert("apples".to_string(), 3);
+    let mut tags = HashSet::default();
+    tags.insert("fruit".to_string());
+    let inventory = Py::new(py, Inventory { counts, tags }).unwrap();
+
+    py_assert!(py, inventory, "inventory.counts == {'apples': 3}");
+    py_assert!(py, inventory, "type(inventory.counts) is dict");
+    py_assert!(py, inventory, "inventory.tags == {'fruit'}");
+
+    py_run!(py, inventory, "inventory.counts = {'pears': 1, 'plums': 2}");
+    py_run!(py, inventory, "inventory.tags = {'stone', 'fruit'}");
+    {
+        let inventory = inventory.borrow(py);
+        assert_eq!(inventory.counts.len(), 2);
+        assert_eq!(inventory.counts["plums"], 2);
+        assert!(inventory.tags.contains("stone"));
+    }
+
+    py_expect_exception!(
+        py,
+        inventory,
+        "inventory.counts = {'pears': -1}",
+        PyOverflowError
+    );
+}
@@ -1,7 +1,7 @@
 use pyo3::prelude::*;
 use pyo3::py_run;
 
-use pyo3::types::IntoPyDict;
+use pyo3::types::{IntoPyDict, PyDict, PyType};
 
 mod common;
 
@@ -29,6 +29,101 @@ fn subclass() {
     .unwrap();
 }
 
+#[pyclass(subclass)]
+struct Plugin {}
+
+#[pymethods]
+impl Plugin {
+    #[classmethod]
+    #[args(kwargs = "**")]
+    fn __init_subclass__(cls: &PyType, kwargs: Option<&PyDict>) -> PyResult<()> {
+        let name = match kwargs.and_then(|kwargs| kwargs.get_item("name")) {
+            Some(name) => name.extract()?,
+            None => cls.name()?.to_lowercase(),
+        };
+        cls.setattr("plugin_name", name)
+    }
+}
+
+#[test]
+fn init_subclass_hook() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let plugin = py.get_type::<Plugin>();
+
+    py_run!(
+        py,
+        plugin,
+        r#"
+class Csv(plugin): pass
+class Json(plugin, name="js"): pass
+class Json5(Json): pass
+assert Csv.plugin_name == "csv"
+assert Json.plugin_name == "js"
+assert Json5.plugin_name == "json5"
+assert not hasattr(plugin, "plugin_name")
+"#
+    );
+}
+
+#[pyclass(subclass)]
+struct Shape {}
+
+#[pymethods]
+impl Shape {
+    #[new]
+    fn new() -> Self {
+        Shape {}
+    }
+
+    #[pyo3(overridable)]
+    fn area(&self, scale: f64) -> PyResult<f64> {
+        Ok(0.0 * scale)
+    }
+
+    #[pyo3(overridable)]
+    fn reset(&self) {}
+
+    fn describe(slf: &PyCell<Self>) -> PyResult<String> {
+        Shape::dispatch_reset(slf)?;
+        Ok(format!("area {}", Shape::dispatch_area(slf, 2.0)?))
+    }
+}
+
+#[test]
+fn overridable_method_dispatch() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let shape = py.get_type::<Shape>();
+
+    py_run!(
+        py,
+        shape,
+        r#"
+class Square(shape):
+    resets = 0
+    def area(self, scale):
+        return 4.0 * scale
+    def reset(self):
+        type(self).resets += 1
+
+assert shape().describe() == "area 0"
+assert Square().describe() == "area 8"
+assert Square.resets == 1
+"#
+    );
+
+    let square = py
+        .eval(
+            "type('Square', (shape,), {'area': lambda self, scale: 3.0})()",
+            None,
+            Some([("shape", shape)].into_py_dict(py)),
+        )
+        .unwrap();
+    let square: &PyCell<Shape> = square.downcast().unwrap();
+    assert_eq!(Shape::dispatch_area(square, 1.0).unwrap(), 3.0);
+}
+
 #[pymethods]
 impl BaseClass {
     #[new]
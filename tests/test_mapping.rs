@@ -126,3 +126,25 @@ fn test_reversed() {
     let d = map_dict(py);
     py_assert!(py, *d, "set(reversed(m)) == {'1', '2', '3'}");
 }
+
+#[pyclass(mapping)]
+struct Registry {}
+
+#[test]
+fn test_mapping_registration() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let registry = py.get_type::<Registry>();
+    let mapping = py.get_type::<Mapping>();
+    py_run!(
+        py,
+        registry mapping,
+        r#"
+import collections.abc
+assert issubclass(registry, collections.abc.Mapping)
+assert not issubclass(registry, collections.abc.Sequence)
+assert not issubclass(mapping, collections.abc.Mapping)
+"#
+    );
+}
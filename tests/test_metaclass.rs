@@ -0,0 +1,116 @@
+use pyo3::prelude::*;
+use pyo3::py_run;
+use pyo3::types::PyType;
+
+mod common;
+
+fn quacks(instance: &PyAny) -> PyResult<bool> {
+    instance.hasattr("quack")
+}
+
+fn quacks_type(subclass: &PyType) -> PyResult<bool> {
+    subclass.hasattr("quack")
+}
+
+#[pyclass(subclass, instancecheck = quacks, subclasscheck = quacks_type)]
+struct Duck {}
+
+#[pymethods]
+impl Duck {
+    #[new]
+    fn new() -> Self {
+        Duck {}
+    }
+}
+
+#[test]
+fn instancecheck_and_subclasscheck() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let duck = py.get_type::<Duck>();
+
+    py_run!(
+        py,
+        duck,
+        r#"
+class Quacker:
+    def quack(self):
+        return "quack"
+
+class Sub(duck):
+    pass
+
+assert isinstance(duck(), duck)
+assert isinstance(Sub(), duck)
+assert isinstance(Quacker(), duck)
+assert not isinstance(object(), duck)
+
+assert issubclass(Sub, duck)
+assert issubclass(Quacker, duck)
+assert not issubclass(int, duck)
+
+# Subclasses of the class keep the default behaviour.
+assert not isinstance(Quacker(), Sub)
+assert not issubclass(Quacker, Sub)
+
+assert type(duck).__name__ == "DuckMeta"
+assert isinstance(duck, type)
+assert type(Sub) is type(duck)
+"#
+    );
+    py_expect_exception!(py, duck, "issubclass(1, duck)", PyTypeError);
+}
+
+#[pyclass(extends = Duck)]
+struct Mallard {}
+
+#[test]
+fn metaclass_is_inherited() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let duck = py.get_type::<Duck>();
+    let mallard = py.get_type::<Mallard>();
+
+    py_assert!(py, duck mallard, "type(mallard) is type(duck)");
+    py_assert!(py, duck mallard, "issubclass(mallard, duck)");
+}
+
+fn honks(instance: &PyAny) -> PyResult<bool> {
+    instance.hasattr("honk")
+}
+
+#[pyclass(extends = Duck, instancecheck = honks)]
+struct Goose {}
+
+#[test]
+fn subclass_with_own_hook() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let duck = py.get_type::<Duck>();
+    let goose = py.get_type::<Goose>();
+
+    py_run!(
+        py,
+        duck goose,
+        r#"
+class Honker:
+    def honk(self):
+        return "honk"
+
+class Quacker:
+    def quack(self):
+        return "quack"
+
+assert type(goose).__name__ == "GooseMeta"
+assert issubclass(type(goose), type(duck))
+
+assert isinstance(Honker(), goose)
+assert not isinstance(Quacker(), goose)
+assert isinstance(Quacker(), duck)
+
+# The hooks of `Duck` keep the default behaviour for its subclasses.
+assert not issubclass(Quacker, goose)
+assert issubclass(goose, duck)
+"#
+    );
+}
@@ -728,6 +728,105 @@ fn test_raw_idents() {
     })
 }
 
+#[pyclass(rename_all = "camelCase")]
+struct CamelCase {
+    #[pyo3(get, set)]
+    max_size: usize,
+    #[pyo3(get, name = "min_size")]
+    min_size: usize,
+}
+
+#[pymethods]
+impl CamelCase {
+    #[new]
+    fn new() -> Self {
+        CamelCase {
+            max_size: 1,
+            min_size: 0,
+        }
+    }
+
+    #[text_signature = "($self, extra_size)"]
+    fn grow_by(&mut self, extra_size: usize) {
+        self.max_size += extra_size;
+    }
+
+    #[getter]
+    fn get_size_range(&self) -> usize {
+        self.max_size - self.min_size
+    }
+
+    #[pyo3(name = "shrink_to_fit")]
+    fn shrink_to_fit(&mut self) {
+        self.max_size = self.min_size;
+    }
+
+    #[staticmethod]
+    fn default_max_size() -> usize {
+        1
+    }
+
+    fn __len__(&self) -> usize {
+        self.max_size
+    }
+}
+
+#[pyclass(rename_all = "PascalCase")]
+struct PascalCase {}
+
+#[pymethods]
+impl PascalCase {
+    #[classmethod]
+    fn from_nothing(_cls: &PyType) -> Self {
+        PascalCase {}
+    }
+}
+
+#[pyclass(rename_all = "kebab-case")]
+struct KebabCase {
+    #[pyo3(get)]
+    _private_value: i32,
+}
+
+#[pymethods]
+impl KebabCase {
+    fn get_value(&self) -> i32 {
+        self._private_value
+    }
+}
+
+#[test]
+fn test_rename_all() {
+    Python::with_gil(|py| {
+        let camel = PyCell::new(py, CamelCase::new()).unwrap();
+        py_run!(
+            py,
+            camel,
+            r#"
+            assert camel.maxSize == 1
+            camel.growBy(2)
+            assert camel.maxSize == 3
+            camel.maxSize = 4
+            assert camel.sizeRange == 4
+            assert camel.defaultMaxSize() == 1
+            assert camel.__len__() == 4
+            assert camel.growBy.__text_signature__ == "($self, extra_size)"
+            camel.shrink_to_fit()
+            assert camel.min_size == 0 and camel.maxSize == 0
+            assert not hasattr(camel, "max_size") and not hasattr(camel, "grow_by")
+            "#
+        );
+        py_expect_exception!(py, camel, "camel.growBy()", PyTypeError);
+
+        let pascal = py.get_type::<PascalCase>();
+        py_assert!(py, pascal, "isinstance(pascal.FromNothing(), pascal)");
+
+        let kebab = PyCell::new(py, KebabCase { _private_value: 5 }).unwrap();
+        py_assert!(py, kebab, "getattr(kebab, '_private-value') == 5");
+        py_assert!(py, kebab, "getattr(kebab, 'get-value')() == 5");
+    });
+}
+
 // Regression test for issue 1505 - Python argument not detected correctly when inside a macro.
 
 #[pyclass]
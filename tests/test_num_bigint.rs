@@ -0,0 +1,62 @@
+#![cfg(all(feature = "num-bigint", not(any(Py_LIMITED_API, PyPy))))]
+
+use num_bigint::{BigInt, BigUint};
+use pyo3::prelude::*;
+use pyo3::py_run;
+
+#[macro_use]
+mod common;
+
+#[pyclass]
+struct BigIntFields {
+    #[pyo3(get, set)]
+    signed: BigInt,
+    #[pyo3(get, set)]
+    unsigned: BigUint,
+}
+
+#[test]
+fn bigint_field_round_trip() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let inst = Py::new(
+        py,
+        BigIntFields {
+            signed: -(BigInt::from(1) << 100usize),
+            unsigned: BigUint::from(1u32) << 100usize,
+        },
+    )
+    .unwrap();
+
+    py_assert!(py, inst, "inst.signed == -(2 ** 100)");
+    py_assert!(py, inst, "inst.unsigned == 2 ** 100");
+
+    py_run!(
+        py,
+        inst,
+        "inst.signed = -(3 ** 200); inst.unsigned = 3 ** 200"
+    );
+    py_assert!(py, inst, "inst.signed == -(3 ** 200)");
+    py_assert!(py, inst, "inst.unsigned == 3 ** 200");
+    assert_eq!(inst.borrow(py).signed, -BigInt::from(3).pow(200));
+    assert_eq!(inst.borrow(py).unsigned, BigUint::from(3u32).pow(200));
+}
+
+#[test]
+fn biguint_field_rejects_negative() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let inst = Py::new(
+        py,
+        BigIntFields {
+            signed: BigInt::from(0),
+            unsigned: BigUint::from(0u32),
+        },
+    )
+    .unwrap();
+
+    py_expect_exception!(py, inst, "inst.unsigned = -(2 ** 100)", PyOverflowError);
+    py_assert!(py, inst, "inst.unsigned == 0");
+}
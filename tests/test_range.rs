@@ -0,0 +1,52 @@
+use pyo3::prelude::*;
+use pyo3::py_run;
+use std::ops::{Range, RangeInclusive};
+
+mod common;
+
+#[pyclass]
+struct Selection {
+    #[pyo3(get, set)]
+    rows: Range<i64>,
+    #[pyo3(get, set)]
+    columns: RangeInclusive<u32>,
+}
+
+#[test]
+fn range_fields() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let selection = Py::new(
+        py,
+        Selection {
+            rows: 0..10,
+            columns: 1..=3,
+        },
+    )
+    .unwrap();
+
+    py_assert!(py, selection, "selection.rows == range(10)");
+    py_assert!(py, selection, "list(selection.columns) == [1, 2, 3]");
+
+    py_run!(py, selection, "selection.rows = range(-5, 5)");
+    assert_eq!(selection.borrow(py).rows, -5..5);
+    py_run!(py, selection, "selection.rows = slice(2, 4)");
+    assert_eq!(selection.borrow(py).rows, 2..4);
+    py_run!(py, selection, "selection.columns = range(4, 8)");
+    assert_eq!(selection.borrow(py).columns, 4..=7);
+
+    py_expect_exception!(
+        py,
+        selection,
+        "selection.rows = range(0, 10, 2)",
+        PyValueError
+    );
+    py_expect_exception!(
+        py,
+        selection,
+        "selection.columns = range(-1, 2)",
+        PyOverflowError
+    );
+    assert_eq!(selection.borrow(py).rows, 2..4);
+}
@@ -1,7 +1,8 @@
 use pyo3::class::PySequenceProtocol;
 use pyo3::exceptions::{PyIndexError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::{IntoPyDict, PyList};
+use pyo3::types::{IntoPyDict, PyList, PySequence};
+use pyo3::PyTryFrom;
 
 use pyo3::py_run;
 
@@ -299,3 +300,188 @@ fn test_option_list_get() {
     py_assert!(py, list, "list[1] == None");
     py_expect_exception!(py, list, "list[2]", PyIndexError);
 }
+
+#[pyclass(getitem = items)]
+struct Readings {
+    items: Vec<f64>,
+}
+
+#[test]
+fn test_generated_getitem() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let readings = PyCell::new(
+        py,
+        Readings {
+            items: vec![1.5, 2.5, 3.5],
+        },
+    )
+    .unwrap();
+
+    py_assert!(py, readings, "readings[0] == 1.5");
+    py_assert!(py, readings, "readings[2] == 3.5");
+    py_assert!(py, readings, "readings[-1] == 3.5");
+    py_expect_exception!(
+        py,
+        readings,
+        "readings[99]",
+        PyIndexError,
+        "index 99 out of range for sequence of length 3"
+    );
+    py_expect_exception!(
+        py,
+        readings,
+        "readings[-4]",
+        PyIndexError,
+        "index -4 out of range for sequence of length 3"
+    );
+    py_expect_exception!(py, readings, "readings['a']", PyTypeError);
+}
+
+#[pyclass(getitem = items)]
+struct SizedReadings {
+    items: Vec<f64>,
+}
+
+#[pyproto]
+impl PySequenceProtocol for SizedReadings {
+    fn __len__(&self) -> usize {
+        self.items.len()
+    }
+}
+
+#[test]
+fn test_generated_getitem_with_len() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let readings = PyCell::new(
+        py,
+        SizedReadings {
+            items: vec![1.5, 2.5, 3.5],
+        },
+    )
+    .unwrap();
+
+    // Negative indices are counted from the end once, whether or not the class has `__len__`.
+    py_assert!(py, readings, "readings[-1] == 3.5");
+    py_assert!(py, readings, "readings[-3] == 1.5");
+    py_expect_exception!(
+        py,
+        readings,
+        "readings[-5]",
+        PyIndexError,
+        "index -5 out of range for sequence of length 3"
+    );
+}
+
+#[pyclass(sequence_from = samples)]
+struct Recording {
+    samples: Vec<i32>,
+}
+
+#[test]
+fn test_generated_sequence_from() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let recording = PyCell::new(
+        py,
+        Recording {
+            samples: vec![10, 20, 30, 40],
+        },
+    )
+    .unwrap();
+
+    py_assert!(py, recording, "len(recording) == 4");
+    py_assert!(py, recording, "recording[0] == 10 and recording[-1] == 40");
+    py_assert!(py, recording, "recording[1:3] == [20, 30]");
+    py_assert!(py, recording, "recording[::-2] == [40, 20]");
+    py_assert!(py, recording, "recording[10:] == []");
+    py_assert!(py, recording, "list(recording) == [10, 20, 30, 40]");
+    py_assert!(
+        py,
+        recording,
+        "list(reversed(recording)) == [40, 30, 20, 10]"
+    );
+    py_assert!(py, recording, "30 in recording and 50 not in recording");
+    py_expect_exception!(
+        py,
+        recording,
+        "recording[4]",
+        PyIndexError,
+        "index 4 out of range for sequence of length 4"
+    );
+    py_expect_exception!(
+        py,
+        recording,
+        "recording[-5]",
+        PyIndexError,
+        "index -5 out of range for sequence of length 4"
+    );
+    py_expect_exception!(py, recording, "recording['a']", PyTypeError);
+    let seq = <PySequence as PyTryFrom>::try_from(recording.as_ref()).unwrap();
+    assert!(seq
+        .get_item(-5)
+        .unwrap_err()
+        .is_instance::<PyIndexError>(py));
+
+    // `__iter__` works on a snapshot.
+    let iter = recording.as_ref().iter().unwrap();
+    recording.borrow_mut().samples.push(50);
+    assert_eq!(iter.count(), 4);
+    py_assert!(py, recording, "len(recording) == 5");
+}
+
+#[pyclass(sequence, sequence_from = frames)]
+struct Clip {
+    frames: Vec<u32>,
+}
+
+#[test]
+fn test_sequence_registration() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let clip = PyCell::new(
+        py,
+        Clip {
+            frames: vec![1, 2, 3],
+        },
+    )
+    .unwrap();
+
+    py_run!(
+        py,
+        clip,
+        r#"
+import collections.abc
+assert isinstance(clip, collections.abc.Sequence)
+assert not isinstance(clip, collections.abc.Mapping)
+assert issubclass(type(clip), collections.abc.Sequence)
+"#
+    );
+    let recording = py.get_type::<Recording>();
+    py_run!(
+        py,
+        recording,
+        "import collections.abc; assert not issubclass(recording, collections.abc.Sequence)"
+    );
+
+    if py.version_info() >= (3, 10) {
+        // `match` is a syntax error before Python 3.10, so compile it only when available.
+        py_run!(
+            py,
+            clip,
+            r#"
+exec('''
+match clip:
+    case [first, *rest]:
+        matched = (first, rest)
+assert matched == (1, [2, 3])
+''')
+"#
+        );
+    }
+}
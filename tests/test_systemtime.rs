@@ -0,0 +1,55 @@
+use pyo3::prelude::*;
+use pyo3::py_run;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+mod common;
+
+#[pyclass]
+struct Event {
+    #[pyo3(get, set)]
+    timestamp: SystemTime,
+}
+
+#[test]
+fn system_time_field() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let event = Py::new(
+        py,
+        Event {
+            timestamp: UNIX_EPOCH + Duration::from_millis(1_234_567_890_250),
+        },
+    )
+    .unwrap();
+
+    py_run!(
+        py,
+        event,
+        r#"
+import datetime
+assert event.timestamp.tzinfo is datetime.timezone.utc
+assert event.timestamp.timestamp() == 1234567890.25
+event.timestamp = event.timestamp + datetime.timedelta(seconds=1)
+"#
+    );
+    assert_eq!(
+        event.borrow(py).timestamp,
+        UNIX_EPOCH + Duration::from_millis(1_234_567_891_250)
+    );
+
+    py_run!(py, event, "event.timestamp = 1234567890.5");
+    assert_eq!(
+        event.borrow(py).timestamp,
+        UNIX_EPOCH + Duration::from_millis(1_234_567_890_500)
+    );
+    py_assert!(py, event, "event.timestamp.timestamp() == 1234567890.5");
+
+    py_expect_exception!(
+        py,
+        event,
+        "event.timestamp = __import__('datetime').datetime(2020, 1, 1)",
+        PyValueError
+    );
+    py_expect_exception!(py, event, "event.timestamp = 'now'", PyTypeError);
+}
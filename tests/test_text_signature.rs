@@ -1,5 +1,6 @@
 use pyo3::prelude::*;
-use pyo3::{types::PyType, wrap_pyfunction, wrap_pymodule, PyCell};
+use pyo3::types::{PyDict, PyTuple, PyType};
+use pyo3::{py_run, wrap_pyfunction, wrap_pymodule, PyCell};
 
 mod common;
 
@@ -100,6 +101,98 @@ fn class_with_signature() {
     );
 }
 
+#[test]
+#[cfg_attr(all(Py_LIMITED_API, not(Py_3_10)), ignore)]
+fn class_with_generated_signature() {
+    /// docs
+    #[pyclass]
+    struct MyClass {}
+
+    #[pymethods]
+    impl MyClass {
+        #[new]
+        #[args(a, b = "None", "*", c = 42, d = "-1.5", e = "\"x\"", f = "Vec::new()")]
+        fn new(_py: Python, a: i32, b: Option<i32>, c: i32, d: f64, e: &str, f: Vec<i32>) -> Self {
+            let _ = (a, b, c, d, e, f);
+            Self {}
+        }
+    }
+
+    #[pyclass]
+    struct VarArgs {}
+
+    #[pymethods]
+    impl VarArgs {
+        #[new]
+        #[args(a, args = "*", flag = "false", kwargs = "**")]
+        fn new(a: i32, args: &PyTuple, flag: bool, kwargs: Option<&PyDict>) -> Self {
+            let _ = (a, args, flag, kwargs);
+            Self {}
+        }
+    }
+
+    #[pyclass(lenient_new)]
+    struct Lenient {
+        #[pyo3(set)]
+        x: i32,
+        #[pyo3(set)]
+        y: i32,
+    }
+
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let typeobj = py.get_type::<MyClass>();
+
+    py_assert!(py, typeobj, "typeobj.__doc__ == 'docs'");
+    py_assert!(
+        py,
+        typeobj,
+        "typeobj.__text_signature__ == '(a, b=None, *, c=42, d=-1.5, e=\"x\", f=...)'"
+    );
+    py_run!(
+        py,
+        typeobj,
+        "import inspect; assert list(inspect.signature(typeobj).parameters) == list('abcdef')"
+    );
+
+    let typeobj = py.get_type::<VarArgs>();
+    py_assert!(
+        py,
+        typeobj,
+        "typeobj.__text_signature__ == '(a, *args, flag=False, **kwargs)'"
+    );
+
+    let typeobj = py.get_type::<Lenient>();
+    py_assert!(
+        py,
+        typeobj,
+        "typeobj.__text_signature__ == '(x, y, **kwargs)'"
+    );
+}
+
+#[test]
+#[cfg_attr(all(Py_LIMITED_API, not(Py_3_10)), ignore)]
+fn class_with_signature_opt_out() {
+    #[pyclass]
+    #[text_signature = "()"]
+    struct MyClass {}
+
+    #[pymethods]
+    impl MyClass {
+        #[new]
+        fn new(a: i32) -> Self {
+            let _ = a;
+            Self {}
+        }
+    }
+
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let typeobj = py.get_type::<MyClass>();
+
+    py_assert!(py, typeobj, "typeobj.__text_signature__ == '()'");
+}
+
 #[test]
 fn test_function() {
     #[pyfunction(a, b = "None", "*", c = 42)]
@@ -115,6 +208,21 @@ fn test_function() {
     py_assert!(py, f, "f.__text_signature__ == '(a, b=None, *, c=42)'");
 }
 
+#[test]
+fn test_function_with_markers() {
+    #[pyfunction(a, args = "*", kwargs = "**")]
+    #[text_signature = "(a, /, *args, **kwargs)"]
+    fn my_function(a: i32, args: &PyTuple, kwargs: Option<&PyDict>) {
+        let _ = (a, args, kwargs);
+    }
+
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let f = wrap_pyfunction!(my_function)(py).unwrap();
+
+    py_assert!(py, f, "f.__text_signature__ == '(a, /, *args, **kwargs)'");
+}
+
 #[test]
 fn test_pyfn() {
     #[pymodule]
@@ -0,0 +1,77 @@
+#![cfg(feature = "uom")]
+
+use pyo3::prelude::*;
+use pyo3::py_run;
+use uom::si::f64::{Length, ThermodynamicTemperature};
+use uom::si::length::{kilometer, meter};
+use uom::si::thermodynamic_temperature::degree_celsius;
+
+mod common;
+
+#[pyclass]
+struct Route {
+    #[pyo3(get, set, unit = "meter")]
+    distance: Length,
+    #[pyo3(get, name = "distance_km", unit = "kilometer")]
+    distance_in_km: Length,
+    #[pyo3(get, set, unit = "degree_celsius")]
+    temperature: ThermodynamicTemperature,
+}
+
+#[test]
+fn unit_fields() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let route = Py::new(
+        py,
+        Route {
+            distance: Length::new::<kilometer>(1.5),
+            distance_in_km: Length::new::<meter>(2500.0),
+            temperature: ThermodynamicTemperature::new::<degree_celsius>(20.0),
+        },
+    )
+    .unwrap();
+
+    py_assert!(py, route, "route.distance == 1500.0");
+    py_assert!(py, route, "route.distance_km == 2.5");
+    py_assert!(py, route, "abs(route.temperature - 20.0) < 1e-9");
+
+    py_run!(py, route, "route.distance = 42");
+    py_run!(py, route, "route.temperature = -40.0");
+    {
+        let route = route.borrow(py);
+        assert_eq!(route.distance.get::<kilometer>(), 0.042);
+        assert!((route.temperature.get::<degree_celsius>() + 40.0).abs() < 1e-9);
+    }
+    py_assert!(py, route, "route.distance == 42.0");
+
+    py_expect_exception!(py, route, "route.distance = '42 m'", PyTypeError);
+    py_assert!(py, route, "route.distance == 42.0");
+}
+
+#[pyclass(to_dict, repr, eq, eq_dict, positional_new)]
+#[derive(PartialEq)]
+struct Leg {
+    #[pyo3(get, set, unit = "kilometer")]
+    distance: Length,
+}
+
+#[test]
+fn unit_fields_in_generated_methods() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let leg = Py::new(
+        py,
+        Leg {
+            distance: Length::new::<meter>(2500.0),
+        },
+    )
+    .unwrap();
+    let cls = py.get_type::<Leg>();
+    py_assert!(py, leg, "leg.to_dict() == {'distance': 2.5}");
+    py_assert!(py, leg, "repr(leg) == 'Leg(distance=2.5)'");
+    py_assert!(py, leg, "leg == {'distance': 2.5}");
+    py_assert!(py, leg cls, "cls(2.5) == leg");
+}
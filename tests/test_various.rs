@@ -1,5 +1,5 @@
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyTuple};
+use pyo3::types::{IntoPyDict, PyDict, PyTuple};
 use pyo3::{py_run, wrap_pyfunction, PyCell};
 
 use std::fmt;
@@ -168,6 +168,134 @@ fn test_pickle() {
     );
 }
 
+#[pyclass(getnewargs_ex, module = "test_getnewargs_ex")]
+struct KeywordOnly {
+    #[pyo3(get)]
+    name: String,
+    #[pyo3(get)]
+    size: u32,
+}
+
+#[pymethods]
+impl KeywordOnly {
+    #[new]
+    #[args("*", name, size)]
+    fn new(name: String, size: u32) -> Self {
+        KeywordOnly { name, size }
+    }
+}
+
+#[test]
+fn test_pickle_getnewargs_ex() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let module = PyModule::new(py, "test_getnewargs_ex").unwrap();
+    module.add_class::<KeywordOnly>().unwrap();
+    add_module(py, module).unwrap();
+    let inst = PyCell::new(
+        py,
+        KeywordOnly {
+            name: "box".to_string(),
+            size: 3,
+        },
+    )
+    .unwrap();
+    py_run!(
+        py,
+        inst,
+        r#"
+        assert inst.__getnewargs_ex__() == ((), {'name': 'box', 'size': 3})
+
+        import copy, pickle
+        for protocol in range(2, pickle.HIGHEST_PROTOCOL + 1):
+            inst2 = pickle.loads(pickle.dumps(inst, protocol))
+            assert type(inst2) is type(inst)
+            assert (inst2.name, inst2.size) == ('box', 3)
+
+        inst3 = copy.copy(inst)
+        assert inst3 is not inst
+        assert (inst3.name, inst3.size) == ('box', 3)
+    "#
+    );
+}
+
+#[pyclass(getstate, module = "test_getstate")]
+struct Session {
+    #[pyo3(get)]
+    user: String,
+    visits: u32,
+    #[pyo3(get, transient)]
+    connected: bool,
+    #[pyo3(transient)]
+    cache: Vec<u32>,
+}
+
+#[pymethods]
+impl Session {
+    #[new]
+    fn new() -> Self {
+        Session {
+            user: String::new(),
+            visits: 0,
+            connected: true,
+            cache: vec![1],
+        }
+    }
+}
+
+#[test]
+fn test_pickle_getstate_transient() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let module = PyModule::new(py, "test_getstate").unwrap();
+    module.add_class::<Session>().unwrap();
+    add_module(py, module).unwrap();
+    let inst = PyCell::new(
+        py,
+        Session {
+            user: "ann".to_string(),
+            visits: 7,
+            connected: true,
+            cache: vec![1, 2, 3],
+        },
+    )
+    .unwrap();
+    py_run!(
+        py,
+        inst,
+        r#"
+        assert inst.__getstate__() == {'user': 'ann', 'visits': 7}
+
+        import pickle
+        for protocol in range(2, pickle.HIGHEST_PROTOCOL + 1):
+            inst2 = pickle.loads(pickle.dumps(inst, protocol))
+            assert type(inst2) is type(inst)
+            assert inst2.user == 'ann'
+            assert inst2.__getstate__() == {'user': 'ann', 'visits': 7}
+            assert not inst2.connected
+
+        try:
+            type(inst)().__setstate__({'user': 'bob'})
+        except KeyError as e:
+            assert e.args == ('visits',)
+        else:
+            assert False
+    "#
+    );
+    let restored: &PyCell<Session> = py
+        .eval(
+            "__import__('pickle').loads(__import__('pickle').dumps(inst))",
+            None,
+            Some([("inst", inst)].into_py_dict(py)),
+        )
+        .unwrap()
+        .downcast()
+        .unwrap();
+    let restored = restored.borrow();
+    assert_eq!(restored.visits, 7);
+    assert!(restored.cache.is_empty());
+}
+
 /// Testing https://github.com/PyO3/pyo3/issues/1106. A result type that
 /// implements `From<MyError> for PyErr` should be automatically converted
 /// when using `#[pyfunction]`.
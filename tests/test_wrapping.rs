@@ -0,0 +1,64 @@
+use pyo3::prelude::*;
+use pyo3::py_run;
+use std::num::Wrapping;
+
+mod common;
+
+#[pyclass]
+struct Counter {
+    #[pyo3(get, set)]
+    ticks: Wrapping<u32>,
+}
+
+#[test]
+fn wrapping_field() {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let counter = Py::new(
+        py,
+        Counter {
+            ticks: Wrapping(std::u32::MAX),
+        },
+    )
+    .unwrap();
+
+    py_assert!(py, counter, "counter.ticks == 2**32 - 1");
+    py_run!(py, counter, "counter.ticks = counter.ticks + 3");
+    assert_eq!(counter.borrow(py).ticks, Wrapping(2));
+    py_run!(py, counter, "counter.ticks = -1");
+    assert_eq!(counter.borrow(py).ticks, Wrapping(std::u32::MAX));
+    py_expect_exception!(py, counter, "counter.ticks = '1'", PyTypeError);
+}
+
+#[rustversion::since(1.74)]
+#[pyclass]
+struct Level {
+    #[pyo3(get, set)]
+    volume: std::num::Saturating<u8>,
+}
+
+#[rustversion::since(1.74)]
+#[test]
+fn saturating_field() {
+    use std::num::Saturating;
+
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+
+    let level = Py::new(
+        py,
+        Level {
+            volume: Saturating(10),
+        },
+    )
+    .unwrap();
+
+    py_assert!(py, level, "level.volume == 10");
+    py_run!(py, level, "level.volume = 300");
+    assert_eq!(level.borrow(py).volume, Saturating(255));
+    py_run!(py, level, "level.volume = -5");
+    assert_eq!(level.borrow(py).volume, Saturating(0));
+    py_run!(py, level, "level.volume = 255");
+    assert_eq!(level.borrow(py).volume, Saturating(255));
+}
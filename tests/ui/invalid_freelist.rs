@@ -0,0 +1,13 @@
+use pyo3::prelude::*;
+
+#[pyclass(freelist = 0)]
+struct EmptyFreelist {}
+
+fn capacity() -> usize {
+    8
+}
+
+#[pyclass(freelist = capacity())]
+struct NonConstFreelist {}
+
+fn main() {}
@@ -0,0 +1,41 @@
+use pyo3::prelude::*;
+use pyo3::PyMappingProtocol;
+
+#[pyclass(frozen)]
+struct Frozen {
+    value: i32,
+}
+
+#[pymethods]
+impl Frozen {
+    fn set_value(&mut self, value: i32) {
+        self.value = value;
+    }
+}
+
+#[pyclass(frozen)]
+struct FrozenSetter {
+    #[pyo3(get, set)]
+    value: i32,
+}
+
+#[pyclass(frozen, extends = Frozen)]
+struct FrozenSub {}
+
+#[pyclass(frozen)]
+struct FrozenMapping {
+    value: i32,
+}
+
+#[pyproto]
+impl PyMappingProtocol for FrozenMapping {
+    fn __setitem__(&mut self, _key: i32, value: i32) {
+        self.value = value;
+    }
+}
+
+fn borrow_mut_frozen(py: Python, frozen: Py<Frozen>) {
+    frozen.borrow_mut(py).value = 1;
+}
+
+fn main() {}
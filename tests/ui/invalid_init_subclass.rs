@@ -0,0 +1,21 @@
+use pyo3::prelude::*;
+use pyo3::types::PyType;
+
+#[pyclass]
+struct NotSubclassable {}
+
+#[pymethods]
+impl NotSubclassable {
+    #[classmethod]
+    fn __init_subclass__(_cls: &PyType) {}
+}
+
+#[pyclass(subclass)]
+struct InstanceMethod {}
+
+#[pymethods]
+impl InstanceMethod {
+    fn __init_subclass__(&self) {}
+}
+
+fn main() {}
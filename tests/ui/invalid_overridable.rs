@@ -0,0 +1,26 @@
+use pyo3::prelude::*;
+
+#[pyclass]
+struct Final {}
+
+#[pymethods]
+impl Final {
+    #[pyo3(overridable)]
+    fn method(&self) {}
+}
+
+#[pyclass(subclass)]
+struct Base {}
+
+#[pymethods]
+impl Base {
+    #[staticmethod]
+    #[pyo3(overridable)]
+    fn static_method() {}
+}
+
+#[pyfunction]
+#[pyo3(overridable)]
+fn function() {}
+
+fn main() {}
@@ -27,4 +27,36 @@ impl ClassWithSetter {
 #[pyclass]
 struct TupleGetterSetter(#[pyo3(get, set)] i32);
 
+#[pyclass]
+struct DuplicateName {
+    #[pyo3(get)]
+    count: i32,
+    #[pyo3(get, name = "count")]
+    other_count: i32,
+}
+
+#[pyclass]
+struct InvalidName {
+    #[pyo3(get, name = "not an identifier")]
+    value: i32,
+}
+
+#[pyclass]
+struct SetOnlyNoMatchArg {
+    #[pyo3(set, no_match_arg)]
+    value: i32,
+}
+
+#[pyclass]
+struct InvalidUnit {
+    #[pyo3(get, unit = "square meter")]
+    value: f64,
+}
+
+#[pyclass]
+struct LockWithSet {
+    #[pyo3(get, set, lock)]
+    value: std::sync::Mutex<i32>,
+}
+
 fn main() {}
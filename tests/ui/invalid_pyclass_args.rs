@@ -15,10 +15,92 @@ struct InvalidName2 {}
 #[pyclass(name = CustomName)]
 struct DeprecatedName {}
 
-#[pyclass(module = my_module)]
+#[pyclass(module = 42)]
 struct InvalidModule {}
 
 #[pyclass(weakrev)]
 struct InvalidArg {}
 
+#[pyclass(sequence, mapping)]
+struct SequenceAndMapping {}
+
+#[pyclass(reentrant)]
+struct ReentrantWithoutEnter {}
+
+#[pyclass(frozen, set_all)]
+struct FrozenSetAll {}
+
+#[pyclass(get_all)]
+struct GetAllTuple(u32);
+
+#[pyclass]
+struct TransientWithoutGetstate {
+    #[pyo3(transient)]
+    cache: u32,
+}
+
+#[pyclass(rename_all = "snake")]
+struct UnknownRenameRule {}
+
+#[pyclass(eq, ord)]
+struct EqAndOrd {}
+
+#[pyclass(match_args(x, y))]
+struct MatchArgsUnknown {
+    #[pyo3(get)]
+    x: u32,
+    #[pyo3(set)]
+    y: u32,
+}
+
+#[pyclass(match_args(x, x))]
+struct MatchArgsRepeated {
+    #[pyo3(get)]
+    x: u32,
+}
+
+#[pyclass(repr, repr_debug)]
+#[derive(Debug)]
+struct ReprAndReprDebug {}
+
+#[pyclass(copy, deepcopy)]
+#[derive(Clone)]
+struct CopyAndDeepcopy {}
+
+#[pyclass(buffer(data = data))]
+struct BufferWithoutShape {
+    data: Vec<f64>,
+}
+
+#[pyclass(buffer(data = data, strides = strides))]
+struct BufferUnknownField {
+    data: Vec<f64>,
+}
+
+#[pyclass(clone)]
+struct CloneWithoutFrozen {}
+
+#[pyclass(frozen, clone, copy)]
+#[derive(Clone)]
+struct CloneAndCopy {}
+
+#[pyclass(hash, cache_hash)]
+#[derive(Hash)]
+struct CacheHashWithoutFrozen {}
+
+#[pyclass(hash_fields)]
+struct HashFieldsWithoutFrozen {}
+
+#[pyclass(positional_new, intern = 4)]
+struct InternWithoutFrozen {
+    #[pyo3(get, set)]
+    x: u32,
+}
+
+#[pyclass(format)]
+struct FormatOnStruct {}
+
+#[pyclass(frozen, gc)]
+struct GcWithFrozen {}
+
 fn main() {}
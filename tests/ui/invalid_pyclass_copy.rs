@@ -0,0 +1,8 @@
+use pyo3::prelude::*;
+
+#[pyclass(copy)]
+struct NotClone {
+    value: i32,
+}
+
+fn main() {}
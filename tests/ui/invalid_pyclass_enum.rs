@@ -0,0 +1,31 @@
+use pyo3::prelude::*;
+
+#[pyclass]
+enum DataVariant {
+    Unit,
+    Tuple(i32),
+}
+
+#[pyclass]
+enum NoVariants {}
+
+#[pyclass(eq)]
+enum UnsupportedOption {
+    Unit,
+}
+
+#[pyclass]
+enum TwoFalsyVariants {
+    #[pyo3(falsy)]
+    Empty,
+    #[pyo3(falsy)]
+    Missing,
+}
+
+#[pyclass]
+enum UnknownVariantOption {
+    #[pyo3(truthy)]
+    Unit,
+}
+
+fn main() {}
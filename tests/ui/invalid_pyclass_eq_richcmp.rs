@@ -0,0 +1,18 @@
+use pyo3::class::basic::CompareOp;
+use pyo3::prelude::*;
+use pyo3::PyObjectProtocol;
+
+#[pyclass(eq)]
+#[derive(PartialEq)]
+struct Point {
+    x: i32,
+}
+
+#[pyproto]
+impl PyObjectProtocol for Point {
+    fn __richcmp__(&self, other: PyRef<Point>, _op: CompareOp) -> bool {
+        self.x == other.x
+    }
+}
+
+fn main() {}
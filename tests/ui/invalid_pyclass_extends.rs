@@ -0,0 +1,8 @@
+use pyo3::prelude::*;
+
+struct NotABaseClass;
+
+#[pyclass(extends = NotABaseClass)]
+struct InvalidBase {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use pyo3::prelude::*;
+
+#[derive(Clone)]
+struct Opaque;
+
+#[pyclass(get_all, set_all)]
+struct Settings {
+    level: u32,
+    handle: Opaque,
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+use pyo3::prelude::*;
+
+#[pyclass(hash)]
+#[derive(Hash)]
+struct Reading {
+    sensor: u32,
+    value: f64,
+}
+
+#[pyclass(hash)]
+struct Unhashable {
+    value: f64,
+}
+
+#[pyclass(frozen, hash_fields)]
+struct UnhashableField {
+    #[pyo3(get)]
+    sensor: u32,
+    #[pyo3(get)]
+    value: f64,
+}
+
+#[pyclass(frozen, hash_fields)]
+struct LockedField {
+    #[pyo3(get, lock)]
+    level: std::sync::Mutex<u32>,
+}
+
+fn main() {}
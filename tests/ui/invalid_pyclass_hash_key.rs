@@ -0,0 +1,36 @@
+use pyo3::prelude::*;
+
+#[pyclass]
+struct SettableKey {
+    #[pyo3(get, set, hash)]
+    id: u64,
+}
+
+#[pyclass]
+struct TwoKeys {
+    #[pyo3(hash)]
+    id: u64,
+    #[pyo3(hash)]
+    name: String,
+}
+
+#[pyclass(eq)]
+#[derive(PartialEq)]
+struct KeyAndEq {
+    #[pyo3(hash)]
+    id: u64,
+}
+
+#[pyclass]
+struct UnhashableKey {
+    #[pyo3(hash)]
+    value: f64,
+}
+
+#[pyclass]
+struct LockedKey {
+    #[pyo3(get, lock, hash)]
+    id: std::sync::Mutex<u64>,
+}
+
+fn main() {}
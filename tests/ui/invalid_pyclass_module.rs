@@ -0,0 +1,8 @@
+use pyo3::prelude::*;
+
+const MODULE_NUMBER: u32 = 42;
+
+#[pyclass(module = MODULE_NUMBER)]
+struct NumberModule {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use pyo3::prelude::*;
+
+#[pyclass(ord)]
+#[derive(PartialEq, PartialOrd)]
+struct Unordered {
+    value: f64,
+}
+
+fn main() {}
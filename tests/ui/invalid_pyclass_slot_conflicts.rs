@@ -0,0 +1,51 @@
+use pyo3::prelude::*;
+use pyo3::{PyIterProtocol, PyMappingProtocol, PyObjectProtocol};
+
+#[pyclass(repr, str, hash)]
+#[derive(Debug, Hash)]
+struct Point {
+    #[pyo3(get)]
+    x: i32,
+}
+
+impl std::fmt::Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.x)
+    }
+}
+
+#[pyproto]
+impl PyObjectProtocol for Point {
+    fn __repr__(&self) -> String {
+        format!("Point({})", self.x)
+    }
+
+    fn __str__(&self) -> String {
+        self.x.to_string()
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.x as u64
+    }
+}
+
+#[pyclass(getitem = values, iterator)]
+struct Values {
+    values: Vec<i32>,
+}
+
+#[pyproto]
+impl PyMappingProtocol for Values {
+    fn __getitem__(&self, index: isize) -> i32 {
+        self.values[index as usize]
+    }
+}
+
+#[pyproto]
+impl PyIterProtocol for Values {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+}
+
+fn main() {}
@@ -26,4 +26,25 @@ impl TestClass {
     fn new(&self) -> Self { Self { num: 0 } }
 }
 
+#[pymethods]
+impl TestClass {
+    fn reset(&mut self) { self.num = 0 }
+
+    #[pyo3(name = "reset")]
+    fn reset_to_one(&mut self) { self.num = 1 }
+}
+
+#[pymethods]
+impl TestClass {
+    #[getter]
+    fn value(&self) -> u32 { self.num }
+
+    #[setter]
+    fn set_value(&mut self, value: u32) { self.num = value }
+
+    #[classattr]
+    #[pyo3(name = "value")]
+    const DEFAULT_VALUE: u32 = 0;
+}
+
 fn main() {}
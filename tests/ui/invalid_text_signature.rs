@@ -0,0 +1,45 @@
+use pyo3::prelude::*;
+
+#[pyfunction]
+#[text_signature = "(a, b=(1, 2)"]
+fn unbalanced(_a: i32, _b: (i32, i32)) {}
+
+#[pyfunction]
+#[text_signature = "(a, /, b, /)"]
+fn two_slashes(_a: i32, _b: i32) {}
+
+#[pyfunction(_a, "*", _b)]
+#[text_signature = "(a, *, /, b)"]
+fn slash_after_star(_a: i32, _b: i32) {}
+
+#[pyfunction]
+#[text_signature = "(a, *)"]
+fn bare_star(_a: i32) {}
+
+#[pyfunction(_kwargs = "**")]
+#[text_signature = "(**kwargs, a)"]
+fn kwargs_not_last(_a: i32, _kwargs: Option<&pyo3::types::PyDict>) {}
+
+#[pyfunction]
+#[text_signature = "(a, *, b)"]
+fn keyword_only_mismatch(_a: i32, _b: i32) {}
+
+#[pyfunction]
+#[text_signature = "(a, *args)"]
+fn varargs_mismatch(_a: i32) {}
+
+#[pyclass]
+#[text_signature = "(a, *, *, b)"]
+struct TwoStars {}
+
+#[pyclass]
+struct MyClass {}
+
+#[pymethods]
+impl MyClass {
+    #[args(_kwargs = "**")]
+    #[text_signature = "($self, a)"]
+    fn method(&self, _a: i32, _kwargs: Option<&pyo3::types::PyDict>) {}
+}
+
+fn main() {}
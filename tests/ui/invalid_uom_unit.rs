@@ -0,0 +1,17 @@
+use pyo3::prelude::*;
+use uom::si::f64::Length;
+use uom::si::mass::kilogram;
+
+#[pyclass]
+struct WrongQuantity {
+    #[pyo3(get, set, unit = "kilogram")]
+    distance: Length,
+}
+
+#[pyclass]
+struct UnknownUnit {
+    #[pyo3(get, unit = "uom::si::length::league")]
+    distance: Length,
+}
+
+fn main() {}
@@ -5,4 +5,14 @@ struct ClassWithGenerics<A> {
     a: A,
 }
 
+struct Wrapper<T> {
+    inner: T,
+}
+
+#[pyclass]
+type GenericWrapper<T> = Wrapper<T>;
+
+#[pyclass(repr)]
+type ReprWrapper = Wrapper<i64>;
+
 fn main() {}